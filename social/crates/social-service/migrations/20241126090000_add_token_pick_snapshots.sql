@@ -0,0 +1,21 @@
+-- migrate:up
+-- Table: social.token_pick_snapshots
+CREATE TABLE IF NOT EXISTS social.token_pick_snapshots (
+    id BIGSERIAL PRIMARY KEY,
+    token_pick_id bigint NOT NULL,
+    price numeric(28,18) NOT NULL,
+    market_cap numeric(36,18) NOT NULL,
+    liquidity numeric,
+    volume_24h numeric,
+    observed_at timestamp with time zone NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT token_pick_snapshots_token_pick_id_fkey FOREIGN KEY (token_pick_id)
+        REFERENCES social.token_picks (id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_token_pick_snapshots_pick_observed_at ON social.token_pick_snapshots(token_pick_id, observed_at);
+CREATE INDEX IF NOT EXISTS idx_token_pick_snapshots_observed_at ON social.token_pick_snapshots(observed_at);
+
+-- migrate:down
+DROP INDEX IF EXISTS social.idx_token_pick_snapshots_observed_at;
+DROP INDEX IF EXISTS social.idx_token_pick_snapshots_pick_observed_at;
+DROP TABLE IF EXISTS social.token_pick_snapshots;
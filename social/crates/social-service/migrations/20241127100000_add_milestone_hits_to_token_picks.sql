@@ -0,0 +1,15 @@
+-- migrate:up
+ALTER TABLE social.token_picks
+    ADD COLUMN IF NOT EXISTS milestone_hits jsonb NOT NULL DEFAULT '{}'::jsonb;
+
+-- Existing hits only recorded the 2x milestone
+UPDATE social.token_picks
+SET milestone_hits = jsonb_build_object('2x', hit_date)
+WHERE hit_date IS NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_token_picks_milestone_hits ON social.token_picks USING gin (milestone_hits);
+
+-- migrate:down
+DROP INDEX IF EXISTS social.idx_token_picks_milestone_hits;
+
+ALTER TABLE social.token_picks DROP COLUMN IF EXISTS milestone_hits;
@@ -0,0 +1,9 @@
+-- migrate:up
+ALTER TABLE social.token_picks
+    ADD COLUMN IF NOT EXISTS lowest_market_cap numeric(36,18),
+    ADD COLUMN IF NOT EXISTS max_drawdown numeric(10,2);
+
+-- migrate:down
+ALTER TABLE social.token_picks
+    DROP COLUMN IF EXISTS max_drawdown,
+    DROP COLUMN IF EXISTS lowest_market_cap;
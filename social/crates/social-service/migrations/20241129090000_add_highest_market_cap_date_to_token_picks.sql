@@ -0,0 +1,6 @@
+-- migrate:up
+ALTER TABLE social.token_picks
+    ADD COLUMN IF NOT EXISTS highest_market_cap_date timestamp with time zone;
+
+-- migrate:down
+ALTER TABLE social.token_picks DROP COLUMN IF EXISTS highest_market_cap_date;
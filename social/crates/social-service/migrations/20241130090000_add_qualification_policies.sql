@@ -0,0 +1,44 @@
+-- migrate:up
+-- Table: social.qualification_policies
+-- A policy applies to an environment and/or a group, NULL meaning any. Changing the rules inserts
+-- a new version so picks keep being judged by the version they were created under.
+CREATE TABLE IF NOT EXISTS social.qualification_policies (
+    id BIGSERIAL PRIMARY KEY,
+    environment character varying(32),
+    group_id bigint,
+    version integer NOT NULL,
+    min_market_cap numeric(36,18) NOT NULL,
+    min_liquidity_volume_ratio numeric(10,4) NOT NULL,
+    large_cap_threshold numeric(36,18) NOT NULL,
+    large_cap_min_liquidity numeric(36,18) NOT NULL,
+    bust_min_volume numeric(36,18) NOT NULL,
+    bust_min_liquidity numeric(36,18) NOT NULL,
+    bust_min_market_cap numeric(36,18) NOT NULL,
+    is_active boolean NOT NULL DEFAULT true,
+    created_at timestamp with time zone NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT qualification_policies_group_id_fkey FOREIGN KEY (group_id)
+        REFERENCES social.groups (id) ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_qualification_policies_scope_version
+    ON social.qualification_policies(COALESCE(environment, ''), COALESCE(group_id, 0), version);
+
+-- Version 1 holds the thresholds that used to be hardcoded
+INSERT INTO social.qualification_policies (
+    id, environment, group_id, version, min_market_cap, min_liquidity_volume_ratio,
+    large_cap_threshold, large_cap_min_liquidity, bust_min_volume, bust_min_liquidity,
+    bust_min_market_cap
+)
+VALUES (1, NULL, NULL, 1, 40000, 0.04, 1000000, 40000, 20000, 10000, 30000)
+ON CONFLICT DO NOTHING;
+
+SELECT setval('social.qualification_policies_id_seq', (SELECT MAX(id) FROM social.qualification_policies));
+
+ALTER TABLE social.token_picks
+    ADD COLUMN IF NOT EXISTS qualification_policy_id bigint NOT NULL DEFAULT 1
+        REFERENCES social.qualification_policies (id);
+
+-- migrate:down
+ALTER TABLE social.token_picks DROP COLUMN IF EXISTS qualification_policy_id;
+DROP INDEX IF EXISTS social.idx_qualification_policies_scope_version;
+DROP TABLE IF EXISTS social.qualification_policies;
@@ -0,0 +1,25 @@
+-- migrate:up
+-- The earliest pick of a token across every group is its first call, later picks are follow-ups
+ALTER TABLE social.token_picks
+    ADD COLUMN IF NOT EXISTS first_call_pick_id bigint,
+    ADD COLUMN IF NOT EXISTS first_call_date timestamp with time zone;
+
+CREATE INDEX IF NOT EXISTS idx_token_picks_token_call_date ON social.token_picks(token_address, token_chain, call_date);
+
+WITH first_calls AS (
+    SELECT DISTINCT ON (token_address, token_chain) token_address, token_chain, id, call_date
+    FROM social.token_picks
+    ORDER BY token_address, token_chain, call_date, id
+)
+UPDATE social.token_picks tp
+SET first_call_pick_id = fc.id,
+    first_call_date = fc.call_date
+FROM first_calls fc
+WHERE tp.token_address = fc.token_address
+AND tp.token_chain = fc.token_chain;
+
+-- migrate:down
+DROP INDEX IF EXISTS social.idx_token_picks_token_call_date;
+ALTER TABLE social.token_picks
+    DROP COLUMN IF EXISTS first_call_date,
+    DROP COLUMN IF EXISTS first_call_pick_id;
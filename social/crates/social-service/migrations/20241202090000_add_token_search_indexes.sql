@@ -0,0 +1,10 @@
+-- migrate:up
+-- Token search matches a case-insensitive prefix of the symbol, name or address
+CREATE INDEX IF NOT EXISTS idx_token_symbol_prefix ON social.tokens(lower(symbol) text_pattern_ops);
+CREATE INDEX IF NOT EXISTS idx_token_name_prefix ON social.tokens(lower(name) text_pattern_ops);
+CREATE INDEX IF NOT EXISTS idx_token_address_prefix ON social.tokens(lower(address) text_pattern_ops);
+
+-- migrate:down
+DROP INDEX IF EXISTS social.idx_token_address_prefix;
+DROP INDEX IF EXISTS social.idx_token_name_prefix;
+DROP INDEX IF EXISTS social.idx_token_symbol_prefix;
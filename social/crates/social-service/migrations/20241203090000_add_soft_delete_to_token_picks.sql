@@ -0,0 +1,40 @@
+-- migrate:up
+-- Cancelled picks are kept with who removed them and why, so a caller can't erase losing picks
+ALTER TABLE social.token_picks
+    ADD COLUMN IF NOT EXISTS deleted_at timestamp with time zone,
+    ADD COLUMN IF NOT EXISTS deleted_by character varying(128),
+    ADD COLUMN IF NOT EXISTS deletion_reason text;
+
+CREATE INDEX IF NOT EXISTS idx_token_picks_deleted_at
+    ON social.token_picks(deleted_at DESC)
+    WHERE deleted_at IS NOT NULL;
+
+-- Table: social.token_pick_audit_log
+-- Every deletion and restoration of a pick
+CREATE TABLE IF NOT EXISTS social.token_pick_audit_log (
+    id BIGSERIAL PRIMARY KEY,
+    token_pick_id bigint NOT NULL,
+    action character varying(16) NOT NULL,
+    actor character varying(128) NOT NULL,
+    reason text,
+    created_at timestamp with time zone NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT token_pick_audit_log_token_pick_id_fkey FOREIGN KEY (token_pick_id)
+        REFERENCES social.token_picks (id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_token_pick_audit_log_token_pick_id
+    ON social.token_pick_audit_log(token_pick_id, created_at);
+
+-- How long after the call a pick can still be cancelled, the previous hardcoded minute by default
+ALTER TABLE social.qualification_policies
+    ADD COLUMN IF NOT EXISTS cancellation_window_seconds integer NOT NULL DEFAULT 60;
+
+-- migrate:down
+ALTER TABLE social.qualification_policies DROP COLUMN IF EXISTS cancellation_window_seconds;
+DROP INDEX IF EXISTS social.idx_token_pick_audit_log_token_pick_id;
+DROP TABLE IF EXISTS social.token_pick_audit_log;
+DROP INDEX IF EXISTS social.idx_token_picks_deleted_at;
+ALTER TABLE social.token_picks
+    DROP COLUMN IF EXISTS deletion_reason,
+    DROP COLUMN IF EXISTS deleted_by,
+    DROP COLUMN IF EXISTS deleted_at;
@@ -0,0 +1,36 @@
+-- migrate:up
+-- Table: social.user_stats
+-- Pick stats of each user per time period and group, group 0 counting every group. Rows are
+-- recomputed when the user's picks are updated, so leaderboards read them instead of every pick.
+CREATE TABLE IF NOT EXISTS social.user_stats (
+    user_id uuid NOT NULL,
+    time_period character varying(16) NOT NULL,
+    group_id bigint NOT NULL DEFAULT 0,
+    total_picks integer NOT NULL DEFAULT 0,
+    hits integer NOT NULL DEFAULT 0,
+    hit_rate numeric NOT NULL DEFAULT 0,
+    average_hit_return numeric NOT NULL DEFAULT 0,
+    pick_returns numeric NOT NULL DEFAULT 0,
+    average_pick_return numeric NOT NULL DEFAULT 0,
+    average_drawdown numeric NOT NULL DEFAULT 0,
+    best_pick jsonb NOT NULL DEFAULT '{}'::jsonb,
+    milestones jsonb NOT NULL DEFAULT '[]'::jsonb,
+    original_calls integer NOT NULL DEFAULT 0,
+    original_call_rate numeric NOT NULL DEFAULT 0,
+    last_pick_date timestamp with time zone,
+    updated_at timestamp with time zone NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT user_stats_pkey PRIMARY KEY (user_id, time_period, group_id),
+    CONSTRAINT user_stats_user_id_fkey FOREIGN KEY (user_id)
+        REFERENCES public.user (id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_stats_leaderboard
+    ON social.user_stats(time_period, group_id);
+
+CREATE INDEX IF NOT EXISTS idx_user_stats_updated_at
+    ON social.user_stats(updated_at);
+
+-- migrate:down
+DROP INDEX IF EXISTS social.idx_user_stats_updated_at;
+DROP INDEX IF EXISTS social.idx_user_stats_leaderboard;
+DROP TABLE IF EXISTS social.user_stats;
@@ -0,0 +1,25 @@
+-- migrate:up
+-- Table: social.leaderboard_rank_snapshots
+-- Hourly and daily ranks of every user on each global leaderboard, to show rank movements and
+-- rank charts
+CREATE TABLE IF NOT EXISTS social.leaderboard_rank_snapshots (
+    resolution character varying(16) NOT NULL,
+    sort character varying(32) NOT NULL,
+    time_period character varying(16) NOT NULL,
+    snapshot_at timestamp with time zone NOT NULL,
+    user_id uuid NOT NULL,
+    rank integer NOT NULL,
+    total_ranked integer NOT NULL,
+    sort_value numeric NOT NULL DEFAULT 0,
+    CONSTRAINT leaderboard_rank_snapshots_pkey
+        PRIMARY KEY (resolution, sort, time_period, snapshot_at, user_id),
+    CONSTRAINT leaderboard_rank_snapshots_user_id_fkey FOREIGN KEY (user_id)
+        REFERENCES public.user (id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_leaderboard_rank_snapshots_user
+    ON social.leaderboard_rank_snapshots(user_id, resolution, sort, time_period, snapshot_at);
+
+-- migrate:down
+DROP INDEX IF EXISTS social.idx_leaderboard_rank_snapshots_user;
+DROP TABLE IF EXISTS social.leaderboard_rank_snapshots;
@@ -0,0 +1,7 @@
+-- migrate:up
+-- Each points rule is awarded once per user and context, so re-evaluating picks is idempotent
+CREATE UNIQUE INDEX IF NOT EXISTS idx_point_transactions_award
+    ON social.point_transactions(user_id, action_type, context);
+
+-- migrate:down
+DROP INDEX IF EXISTS social.idx_point_transactions_award;
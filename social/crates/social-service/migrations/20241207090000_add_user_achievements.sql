@@ -0,0 +1,61 @@
+-- migrate:up
+-- Table: social.user_achievements
+-- Achievements of the catalogue each user earned, once per user
+CREATE TABLE IF NOT EXISTS social.user_achievements (
+    user_id uuid NOT NULL,
+    achievement_id character varying(50) NOT NULL,
+    earned_at timestamp with time zone NOT NULL,
+    token_pick_id bigint,
+    created_at timestamp with time zone NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT user_achievements_pkey PRIMARY KEY (user_id, achievement_id),
+    CONSTRAINT user_achievements_user_id_fkey FOREIGN KEY (user_id)
+        REFERENCES public.user (id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_achievements_earned_at
+    ON social.user_achievements(earned_at);
+
+-- Announces achievements earned in the last day, so evaluating past picks for the first time
+-- does not announce every old achievement
+CREATE OR REPLACE FUNCTION social.notify_user_achievement()
+RETURNS trigger AS $$
+DECLARE
+    user_data jsonb;
+BEGIN
+    IF NEW.earned_at < CURRENT_TIMESTAMP - INTERVAL '1 day' THEN
+        RETURN NEW;
+    END IF;
+
+    SELECT jsonb_build_object(
+        'id', u.id,
+        'username', u.username,
+        'telegram_id', u.telegram_id
+    )
+    FROM public.user u
+    WHERE u.id = NEW.user_id
+    INTO user_data;
+
+    PERFORM pg_notify(
+        'social.user_achievements',
+        jsonb_build_object(
+            'eventDate', CURRENT_TIMESTAMP,
+            'achievementId', NEW.achievement_id,
+            'earnedAt', NEW.earned_at,
+            'tokenPickId', NEW.token_pick_id,
+            'user', user_data
+        )::text
+    );
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER user_achievement_notify_trigger
+    AFTER INSERT ON social.user_achievements
+    FOR EACH ROW
+    EXECUTE FUNCTION social.notify_user_achievement();
+
+-- migrate:down
+DROP TRIGGER IF EXISTS user_achievement_notify_trigger ON social.user_achievements;
+DROP FUNCTION IF EXISTS social.notify_user_achievement();
+DROP INDEX IF EXISTS social.idx_user_achievements_earned_at;
+DROP TABLE IF EXISTS social.user_achievements;
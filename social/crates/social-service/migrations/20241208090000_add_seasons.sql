@@ -0,0 +1,34 @@
+-- migrate:up
+-- Table: social.seasons
+-- Named competitions counting the picks made between their start and end dates
+CREATE TABLE IF NOT EXISTS social.seasons (
+    id BIGSERIAL PRIMARY KEY,
+    name character varying(100) NOT NULL,
+    group_id bigint,
+    starts_at timestamp with time zone NOT NULL,
+    ends_at timestamp with time zone NOT NULL,
+    closed_at timestamp with time zone,
+    standings_uri text,
+    created_at timestamp with time zone NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT seasons_dates_check CHECK (starts_at < ends_at),
+    CONSTRAINT seasons_group_id_fkey FOREIGN KEY (group_id)
+        REFERENCES social.groups (id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_seasons_open
+    ON social.seasons(ends_at) WHERE closed_at IS NULL;
+
+-- Table: social.season_standings
+-- Final standings of a closed season, frozen so later pick updates don't change them
+CREATE TABLE IF NOT EXISTS social.season_standings (
+    season_id bigint PRIMARY KEY,
+    standings jsonb NOT NULL,
+    created_at timestamp with time zone NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT season_standings_season_id_fkey FOREIGN KEY (season_id)
+        REFERENCES social.seasons (id) ON DELETE CASCADE
+);
+
+-- migrate:down
+DROP TABLE IF EXISTS social.season_standings;
+DROP INDEX IF EXISTS social.idx_seasons_open;
+DROP TABLE IF EXISTS social.seasons;
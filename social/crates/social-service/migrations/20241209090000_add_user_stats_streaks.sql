@@ -0,0 +1,15 @@
+-- migrate:up
+-- Hit streaks and weekly consistency of each user's picks, filled as user stats are recomputed
+ALTER TABLE social.user_stats
+    ADD COLUMN IF NOT EXISTS current_streak integer NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS longest_streak integer NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS consistency_score numeric NOT NULL DEFAULT 0;
+
+-- Recompute every user's stats on the next refresh
+UPDATE social.user_stats SET updated_at = '-infinity';
+
+-- migrate:down
+ALTER TABLE social.user_stats
+    DROP COLUMN IF EXISTS consistency_score,
+    DROP COLUMN IF EXISTS longest_streak,
+    DROP COLUMN IF EXISTS current_streak;
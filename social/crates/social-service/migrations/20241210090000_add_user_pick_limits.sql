@@ -0,0 +1,14 @@
+-- migrate:up
+-- Table: social.user_pick_limits
+-- Per-user overrides of the daily pick limit applied across every group
+CREATE TABLE IF NOT EXISTS social.user_pick_limits (
+    user_id uuid PRIMARY KEY,
+    max_daily_picks integer,
+    updated_at timestamp with time zone NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT user_pick_limits_max_daily_picks_check CHECK (max_daily_picks >= 0),
+    CONSTRAINT user_pick_limits_user_id_fkey FOREIGN KEY (user_id)
+        REFERENCES public.user (id) ON DELETE CASCADE
+);
+
+-- migrate:down
+DROP TABLE IF EXISTS social.user_pick_limits;
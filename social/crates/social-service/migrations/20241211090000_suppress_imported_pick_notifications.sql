@@ -0,0 +1,16 @@
+-- migrate:up
+-- Picks inserted in a transaction setting `social.suppress_pick_notifications` to `on`, such as
+-- imported historical picks, are not announced to the followers of their caller
+DROP TRIGGER IF EXISTS token_pick_notify_trigger ON social.token_picks;
+CREATE TRIGGER token_pick_notify_trigger
+    AFTER INSERT ON social.token_picks
+    FOR EACH ROW
+    WHEN (current_setting('social.suppress_pick_notifications', true) IS DISTINCT FROM 'on')
+    EXECUTE FUNCTION social.notify_token_pick();
+
+-- migrate:down
+DROP TRIGGER IF EXISTS token_pick_notify_trigger ON social.token_picks;
+CREATE TRIGGER token_pick_notify_trigger
+    AFTER INSERT ON social.token_picks
+    FOR EACH ROW
+    EXECUTE FUNCTION social.notify_token_pick();
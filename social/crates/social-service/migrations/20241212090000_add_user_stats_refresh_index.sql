@@ -0,0 +1,14 @@
+-- migrate:up
+-- Every user with stats has an all time row across every group, refreshed with the rest of their
+-- rows, so the stale refresh walks this index instead of every pick
+CREATE INDEX IF NOT EXISTS idx_user_stats_refresh
+    ON social.user_stats(updated_at)
+    WHERE time_period = 'all_time' AND group_id = 0;
+
+DROP INDEX IF EXISTS social.idx_user_stats_updated_at;
+
+-- migrate:down
+CREATE INDEX IF NOT EXISTS idx_user_stats_updated_at
+    ON social.user_stats(updated_at);
+
+DROP INDEX IF EXISTS social.idx_user_stats_refresh;
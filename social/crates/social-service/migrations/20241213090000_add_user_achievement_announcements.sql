@@ -0,0 +1,87 @@
+-- migrate:up
+-- Only achievements earned by live pick updates are announced. The first evaluation of a user
+-- saves the achievements they already had without announcing them.
+ALTER TABLE social.user_achievements
+    ADD COLUMN IF NOT EXISTS announce boolean NOT NULL DEFAULT false;
+
+-- Table: social.achievement_evaluations
+-- Users whose achievements were evaluated at least once
+CREATE TABLE IF NOT EXISTS social.achievement_evaluations (
+    user_id uuid NOT NULL,
+    evaluated_at timestamp with time zone NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT achievement_evaluations_pkey PRIMARY KEY (user_id),
+    CONSTRAINT achievement_evaluations_user_id_fkey FOREIGN KEY (user_id)
+        REFERENCES public.user (id) ON DELETE CASCADE
+);
+
+INSERT INTO social.achievement_evaluations (user_id)
+SELECT DISTINCT user_id FROM social.user_achievements
+ON CONFLICT DO NOTHING;
+
+CREATE OR REPLACE FUNCTION social.notify_user_achievement()
+RETURNS trigger AS $$
+DECLARE
+    user_data jsonb;
+BEGIN
+    IF NOT NEW.announce THEN
+        RETURN NEW;
+    END IF;
+
+    SELECT jsonb_build_object(
+        'id', u.id,
+        'username', u.username,
+        'telegram_id', u.telegram_id
+    )
+    FROM public.user u
+    WHERE u.id = NEW.user_id
+    INTO user_data;
+
+    PERFORM pg_notify(
+        'social.user_achievements',
+        jsonb_build_object(
+            'eventDate', CURRENT_TIMESTAMP,
+            'achievementId', NEW.achievement_id,
+            'earnedAt', NEW.earned_at,
+            'tokenPickId', NEW.token_pick_id,
+            'user', user_data
+        )::text
+    );
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+-- migrate:down
+CREATE OR REPLACE FUNCTION social.notify_user_achievement()
+RETURNS trigger AS $$
+DECLARE
+    user_data jsonb;
+BEGIN
+    IF NEW.earned_at < CURRENT_TIMESTAMP - INTERVAL '1 day' THEN
+        RETURN NEW;
+    END IF;
+
+    SELECT jsonb_build_object(
+        'id', u.id,
+        'username', u.username,
+        'telegram_id', u.telegram_id
+    )
+    FROM public.user u
+    WHERE u.id = NEW.user_id
+    INTO user_data;
+
+    PERFORM pg_notify(
+        'social.user_achievements',
+        jsonb_build_object(
+            'eventDate', CURRENT_TIMESTAMP,
+            'achievementId', NEW.achievement_id,
+            'earnedAt', NEW.earned_at,
+            'tokenPickId', NEW.token_pick_id,
+            'user', user_data
+        )::text
+    );
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TABLE IF EXISTS social.achievement_evaluations;
+ALTER TABLE social.user_achievements DROP COLUMN IF EXISTS announce;
@@ -0,0 +1,6 @@
+-- migrate:up
+-- Hit streaks now count every qualified pick instead of the first pick of each token, recompute
+-- every user's stats on the next refresh
+UPDATE social.user_stats SET updated_at = '-infinity';
+
+-- migrate:down
@@ -0,0 +1,23 @@
+-- migrate:up
+-- Table: social.leaderboard_min_picks
+-- Fewest picks a user needs to be ranked on a leaderboard by a ranking mode other than raw, per
+-- leaderboard sort and time period. The 'default' leaderboard applies to sorts without their own.
+CREATE TABLE IF NOT EXISTS social.leaderboard_min_picks (
+    leaderboard text NOT NULL,
+    time_period text NOT NULL,
+    min_picks integer NOT NULL,
+    updated_at timestamp with time zone NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT leaderboard_min_picks_pkey PRIMARY KEY (leaderboard, time_period),
+    CONSTRAINT leaderboard_min_picks_min_picks_check CHECK (min_picks >= 0)
+);
+
+INSERT INTO social.leaderboard_min_picks (leaderboard, time_period, min_picks)
+VALUES ('default', 'six_hours', 2),
+       ('default', 'day', 3),
+       ('default', 'week', 5),
+       ('default', 'month', 10),
+       ('default', 'all_time', 20)
+ON CONFLICT DO NOTHING;
+
+-- migrate:down
+DROP TABLE IF EXISTS social.leaderboard_min_picks;
@@ -0,0 +1,372 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use std::sync::Arc;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{
+    models::{
+        groups::UserPickLimit,
+        profiles::LeaderboardMinPicks,
+        qualification_policies::QualificationPolicy,
+        seasons::Season,
+        token_picks::{DeletedTokenPickResponse, TokenPickResponse},
+    },
+    repositories::token_repository::TokenPickScope,
+    utils::{
+        cursor::page_size,
+        errors::{app_error::AppError, error_payload::ErrorPayload},
+    },
+    AppState,
+};
+
+use super::api_models::{
+    query::DeletedTokenPicksQuery,
+    request::{
+        BackfillRequest, CreateQualificationPolicyRequest, CreateSeasonRequest,
+        ImportTokenPicksQuery, RestoreTokenPickRequest, SetLeaderboardMinPicksRequest,
+        SetUserPickLimitRequest,
+    },
+    response::{BackfillResponse, CursorPaginatedResponse, ImportJobResponse},
+};
+
+pub const TAG: &str = "admin";
+
+/// Replay OHLCV candles to recompute the performance of a pick, a user's picks or a time window
+#[utoipa::path(
+    post,
+    tag = TAG,
+    path = "/backfill",
+    operation_id = "backfillTokenPicks",
+    request_body = BackfillRequest,
+    responses(
+        (status = 202, description = "Backfill started", body = BackfillResponse),
+        (status = 400, description = "Invalid backfill scope", body = ErrorPayload),
+        (status = 401, description = "Missing or invalid admin API key", body = ErrorPayload),
+        (status = 500, description = "Internal server error", body = ErrorPayload)
+    )
+)]
+pub(super) async fn backfill_token_picks(
+    State(app_state): State<Arc<AppState>>,
+    Json(body): Json<BackfillRequest>,
+) -> Result<(StatusCode, Json<BackfillResponse>), AppError> {
+    let scope = match (body.pick_id, body.user_id, body.from, body.to) {
+        (Some(pick_id), None, None, None) => TokenPickScope::Pick(pick_id),
+        (None, Some(user_id), None, None) => TokenPickScope::User(user_id),
+        (None, None, Some(from), Some(to)) if from < to => TokenPickScope::Window(from, to),
+        _ => {
+            return Err(AppError::BadRequest(
+                "Provide exactly one of pickId, userId or a from/to window".to_string(),
+            ))
+        }
+    };
+
+    let picks = app_state.backfill_service.list_picks(&scope).await?;
+    let picks_queued = picks.len();
+    info!("Backfilling {} token picks for {:?}", picks_queued, scope);
+
+    let backfill_service = app_state.backfill_service.clone();
+    tokio::spawn(async move {
+        backfill_service.backfill_picks(picks).await;
+    });
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(BackfillResponse { picks_queued }),
+    ))
+}
+
+/// List every qualification policy version
+#[utoipa::path(
+    get,
+    tag = TAG,
+    path = "/qualification-policies",
+    operation_id = "listQualificationPolicies",
+    responses(
+        (status = 200, description = "Qualification policies retrieved successfully", body = Vec<QualificationPolicy>),
+        (status = 401, description = "Missing or invalid admin API key", body = ErrorPayload),
+        (status = 500, description = "Internal server error", body = ErrorPayload)
+    )
+)]
+pub(super) async fn list_qualification_policies(
+    State(app_state): State<Arc<AppState>>,
+) -> Result<(StatusCode, Json<Vec<QualificationPolicy>>), AppError> {
+    let policies = app_state
+        .qualification_policy_service
+        .list_policies()
+        .await?;
+    Ok((StatusCode::OK, Json(policies)))
+}
+
+/// Create a new version of the qualification policy for an environment and/or group. Existing
+/// picks keep the version they were created under.
+#[utoipa::path(
+    post,
+    tag = TAG,
+    path = "/qualification-policies",
+    operation_id = "createQualificationPolicy",
+    request_body = CreateQualificationPolicyRequest,
+    responses(
+        (status = 201, description = "Qualification policy created successfully", body = QualificationPolicy),
+        (status = 400, description = "Invalid request data", body = ErrorPayload),
+        (status = 401, description = "Missing or invalid admin API key", body = ErrorPayload),
+        (status = 500, description = "Internal server error", body = ErrorPayload)
+    )
+)]
+pub(super) async fn create_qualification_policy(
+    State(app_state): State<Arc<AppState>>,
+    Json(body): Json<CreateQualificationPolicyRequest>,
+) -> Result<(StatusCode, Json<QualificationPolicy>), AppError> {
+    let policy = app_state
+        .qualification_policy_service
+        .create_policy(body)
+        .await?;
+    Ok((StatusCode::CREATED, Json(policy)))
+}
+
+/// List the minimum picks of every leaderboard and time period
+#[utoipa::path(
+    get,
+    tag = TAG,
+    path = "/leaderboards/min-picks",
+    operation_id = "listLeaderboardMinPicks",
+    responses(
+        (status = 200, description = "Leaderboard minimum picks retrieved successfully", body = Vec<LeaderboardMinPicks>),
+        (status = 401, description = "Missing or invalid admin API key", body = ErrorPayload),
+        (status = 500, description = "Internal server error", body = ErrorPayload)
+    )
+)]
+pub(super) async fn list_leaderboard_min_picks(
+    State(app_state): State<Arc<AppState>>,
+) -> Result<(StatusCode, Json<Vec<LeaderboardMinPicks>>), AppError> {
+    let min_picks = app_state
+        .profile_service
+        .list_leaderboard_min_picks()
+        .await?;
+    Ok((StatusCode::OK, Json(min_picks)))
+}
+
+/// Set the fewest picks a user needs to be ranked on a leaderboard in a time period by ranking
+/// modes other than `raw`. Cached leaderboards apply it once they expire.
+#[utoipa::path(
+    put,
+    tag = TAG,
+    path = "/leaderboards/min-picks",
+    operation_id = "setLeaderboardMinPicks",
+    request_body = SetLeaderboardMinPicksRequest,
+    responses(
+        (status = 200, description = "Leaderboard minimum picks set successfully", body = LeaderboardMinPicks),
+        (status = 400, description = "Invalid request data", body = ErrorPayload),
+        (status = 401, description = "Missing or invalid admin API key", body = ErrorPayload),
+        (status = 500, description = "Internal server error", body = ErrorPayload)
+    )
+)]
+pub(super) async fn set_leaderboard_min_picks(
+    State(app_state): State<Arc<AppState>>,
+    Json(body): Json<SetLeaderboardMinPicksRequest>,
+) -> Result<(StatusCode, Json<LeaderboardMinPicks>), AppError> {
+    let min_picks = app_state
+        .profile_service
+        .set_leaderboard_min_picks(body)
+        .await?;
+    Ok((StatusCode::OK, Json(min_picks)))
+}
+
+/// Import historical token picks from CSV or JSONL rows with the columns `address`, `chain`,
+/// `telegram_user_id`, `telegram_chat_id`, `timestamp` and the optional `telegram_message_id`,
+/// `market_cap` and `supply`. Calls are priced from the OHLCV candle of their timestamp. The rows
+/// are imported in the background, without notifying followers.
+#[utoipa::path(
+    post,
+    tag = TAG,
+    path = "/imports/picks",
+    operation_id = "importTokenPicks",
+    request_body(content = String, content_type = "text/plain", description = "CSV with a header row, or one JSON object per line"),
+    responses(
+        (status = 202, description = "Import started, its report is polled by its ID", body = ImportJobResponse),
+        (status = 400, description = "Too many rows", body = ErrorPayload),
+        (status = 401, description = "Missing or invalid admin API key", body = ErrorPayload),
+        (status = 500, description = "Internal server error", body = ErrorPayload)
+    ),
+    params(ImportTokenPicksQuery)
+)]
+pub(super) async fn import_token_picks(
+    State(app_state): State<Arc<AppState>>,
+    Query(query): Query<ImportTokenPicksQuery>,
+    body: String,
+) -> Result<(StatusCode, Json<ImportJobResponse>), AppError> {
+    let job = app_state
+        .import_service
+        .clone()
+        .start_import(query.format, &body, query.dry_run)
+        .await?;
+    Ok((StatusCode::ACCEPTED, Json(job)))
+}
+
+/// Get the status of a token picks import, with the report of every row once completed
+#[utoipa::path(
+    get,
+    tag = TAG,
+    path = "/imports/picks/{id}",
+    operation_id = "getTokenPicksImport",
+    responses(
+        (status = 200, description = "Import retrieved successfully", body = ImportJobResponse),
+        (status = 401, description = "Missing or invalid admin API key", body = ErrorPayload),
+        (status = 404, description = "Import not found or expired", body = ErrorPayload),
+        (status = 500, description = "Internal server error", body = ErrorPayload)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Import ID")
+    )
+)]
+pub(super) async fn get_token_picks_import(
+    State(app_state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+) -> Result<(StatusCode, Json<ImportJobResponse>), AppError> {
+    let job = app_state.import_service.get_import_job(id).await?;
+    Ok((StatusCode::OK, Json(job)))
+}
+
+/// List soft deleted token picks, most recently deleted first
+#[utoipa::path(
+    get,
+    tag = TAG,
+    path = "/picks/deleted",
+    operation_id = "listDeletedTokenPicks",
+    responses(
+        (status = 200, description = "Deleted token picks retrieved successfully", body = CursorPaginatedResponse<Vec<DeletedTokenPickResponse>>),
+        (status = 400, description = "Invalid cursor", body = ErrorPayload),
+        (status = 401, description = "Missing or invalid admin API key", body = ErrorPayload),
+        (status = 500, description = "Internal server error", body = ErrorPayload)
+    ),
+    params(DeletedTokenPicksQuery)
+)]
+pub(super) async fn list_deleted_token_picks(
+    State(app_state): State<Arc<AppState>>,
+    Query(query): Query<DeletedTokenPicksQuery>,
+) -> Result<
+    (
+        StatusCode,
+        Json<CursorPaginatedResponse<Vec<DeletedTokenPickResponse>>>,
+    ),
+    AppError,
+> {
+    let limit = page_size(query.limit);
+    let (picks, next_cursor) = app_state
+        .token_service
+        .list_deleted_token_picks(limit, query.cursor)
+        .await?;
+    Ok((
+        StatusCode::OK,
+        Json(CursorPaginatedResponse {
+            items: picks,
+            limit,
+            next_cursor,
+        }),
+    ))
+}
+
+/// Restore a soft deleted token pick, so it counts in stats and leaderboards again
+#[utoipa::path(
+    post,
+    tag = TAG,
+    path = "/picks/{id}/restore",
+    operation_id = "restoreTokenPick",
+    request_body = RestoreTokenPickRequest,
+    responses(
+        (status = 200, description = "Token pick restored successfully", body = TokenPickResponse),
+        (status = 400, description = "Invalid request data", body = ErrorPayload),
+        (status = 401, description = "Missing or invalid admin API key", body = ErrorPayload),
+        (status = 404, description = "Deleted token pick not found", body = ErrorPayload),
+        (status = 500, description = "Internal server error", body = ErrorPayload)
+    ),
+    params(
+        ("id" = i64, Path, description = "Token pick ID")
+    )
+)]
+pub(super) async fn restore_token_pick(
+    State(app_state): State<Arc<AppState>>,
+    Path(id): Path<i64>,
+    Json(body): Json<RestoreTokenPickRequest>,
+) -> Result<(StatusCode, Json<TokenPickResponse>), AppError> {
+    let pick = app_state.token_service.restore_token_pick(id, body).await?;
+    Ok((StatusCode::OK, Json(pick)))
+}
+
+/// Create a season counting the picks made between its start and end dates, in one group or
+/// every group
+#[utoipa::path(
+    post,
+    tag = TAG,
+    path = "/seasons",
+    operation_id = "createSeason",
+    request_body = CreateSeasonRequest,
+    responses(
+        (status = 201, description = "Season created successfully", body = Season),
+        (status = 400, description = "Invalid request data", body = ErrorPayload),
+        (status = 401, description = "Missing or invalid admin API key", body = ErrorPayload),
+        (status = 404, description = "Group not found", body = ErrorPayload),
+        (status = 500, description = "Internal server error", body = ErrorPayload)
+    )
+)]
+pub(super) async fn create_season(
+    State(app_state): State<Arc<AppState>>,
+    Json(body): Json<CreateSeasonRequest>,
+) -> Result<(StatusCode, Json<Season>), AppError> {
+    let season = app_state.season_service.create_season(body).await?;
+    Ok((StatusCode::CREATED, Json(season)))
+}
+
+/// Override the daily pick limit of a user across every group
+#[utoipa::path(
+    put,
+    tag = TAG,
+    path = "/users/{id}/pick-limit",
+    operation_id = "setUserPickLimit",
+    request_body = SetUserPickLimitRequest,
+    responses(
+        (status = 200, description = "User pick limit set successfully", body = UserPickLimit),
+        (status = 400, description = "Invalid request data", body = ErrorPayload),
+        (status = 401, description = "Missing or invalid admin API key", body = ErrorPayload),
+        (status = 404, description = "User not found", body = ErrorPayload),
+        (status = 500, description = "Internal server error", body = ErrorPayload)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "User ID")
+    )
+)]
+pub(super) async fn set_user_pick_limit(
+    State(app_state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+    Json(body): Json<SetUserPickLimitRequest>,
+) -> Result<(StatusCode, Json<UserPickLimit>), AppError> {
+    let limit = app_state.user_service.set_pick_limit(id, body).await?;
+    Ok((StatusCode::OK, Json(limit)))
+}
+
+/// Restore the default daily pick limit of a user
+#[utoipa::path(
+    delete,
+    tag = TAG,
+    path = "/users/{id}/pick-limit",
+    operation_id = "deleteUserPickLimit",
+    responses(
+        (status = 204, description = "User pick limit override deleted"),
+        (status = 401, description = "Missing or invalid admin API key", body = ErrorPayload),
+        (status = 404, description = "User has no pick limit override", body = ErrorPayload),
+        (status = 500, description = "Internal server error", body = ErrorPayload)
+    ),
+    params(
+        ("id" = Uuid, Path, description = "User ID")
+    )
+)]
+pub(super) async fn delete_user_pick_limit(
+    State(app_state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    app_state.user_service.delete_pick_limit(id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -1,9 +1,23 @@
-use crate::utils::time::{default_time_period, TimePeriod};
+use crate::{
+    models::{
+        profiles::{ProfileDetailsResponse, ProfilePickSummary},
+        rank_history::SnapshotResolution,
+        simulations::SimulationPriceSource,
+        token_picks::{CallType, PickMilestone},
+        tokens::Chain,
+    },
+    utils::{
+        math::bayesian_average,
+        time::{default_time_period, TimePeriod},
+    },
+};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
-#[derive(Serialize, Deserialize, Debug, Clone, Copy, ToSchema, Default)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, ToSchema, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum ProfileLeaderboardSort {
     PickReturns,
@@ -14,14 +28,191 @@ pub enum ProfileLeaderboardSort {
     #[default]
     AverageReturn,
     GreatestHits,
+    /// Number of picks that reached the query `milestone`
+    MilestoneHits,
+    /// Fastest median time to the query `milestone`
+    MedianTimeToHit,
+    /// Number of picks that were the first call of their token across every group
+    OriginalCalls,
+    /// Consecutive picks that hit 2x, up to the latest settled pick
+    CurrentStreak,
+    /// Longest run of consecutive picks that hit 2x
+    LongestStreak,
+    /// Share of recent weeks with at least one pick that hit 2x
+    Consistency,
+}
+
+impl ProfileLeaderboardSort {
+    /// Sorts whose leaderboard ranks are snapshotted. Realized profit is left out as it is not
+    /// kept with the user stats, and most recent pick as it does not rank profiles.
+    pub const SNAPSHOTTED: [ProfileLeaderboardSort; 11] = [
+        ProfileLeaderboardSort::PickReturns,
+        ProfileLeaderboardSort::HitRate,
+        ProfileLeaderboardSort::TotalPicks,
+        ProfileLeaderboardSort::AverageReturn,
+        ProfileLeaderboardSort::GreatestHits,
+        ProfileLeaderboardSort::MilestoneHits,
+        ProfileLeaderboardSort::MedianTimeToHit,
+        ProfileLeaderboardSort::OriginalCalls,
+        ProfileLeaderboardSort::CurrentStreak,
+        ProfileLeaderboardSort::LongestStreak,
+        ProfileLeaderboardSort::Consistency,
+    ];
+
+    /// The value profiles are ranked by, highest first. `milestone` is used by the milestone
+    /// sorts.
+    pub fn sort_key(&self, profile: &ProfileDetailsResponse, milestone: PickMilestone) -> Decimal {
+        self.summary_key(&profile.pick_summary, milestone)
+    }
+
+    /// [Self::sort_key] of a pick summary, for leaderboards of anything with picks
+    pub fn summary_key(&self, summary: &ProfilePickSummary, milestone: PickMilestone) -> Decimal {
+        let milestone_stats = || summary.milestones.iter().find(|m| m.milestone == milestone);
+        match self {
+            ProfileLeaderboardSort::PickReturns => summary.pick_returns,
+            ProfileLeaderboardSort::HitRate => summary.hit_rate,
+            ProfileLeaderboardSort::RealizedProfit => summary.realized_profit,
+            ProfileLeaderboardSort::TotalPicks => Decimal::from(summary.total_picks),
+            ProfileLeaderboardSort::MostRecentPick => Decimal::ZERO,
+            ProfileLeaderboardSort::AverageReturn => summary.average_pick_return,
+            ProfileLeaderboardSort::GreatestHits => summary.best_pick.multiplier,
+            ProfileLeaderboardSort::MilestoneHits => {
+                milestone_stats().map_or(Decimal::ZERO, |m| Decimal::from(m.hits))
+            }
+            // Faster is better, users that never reached the milestone rank last
+            ProfileLeaderboardSort::MedianTimeToHit => milestone_stats()
+                .and_then(|m| m.median_seconds_to_hit)
+                .map_or(Decimal::MIN, |seconds| -Decimal::from(seconds)),
+            ProfileLeaderboardSort::OriginalCalls => Decimal::from(summary.original_calls),
+            ProfileLeaderboardSort::CurrentStreak => Decimal::from(summary.current_streak),
+            ProfileLeaderboardSort::LongestStreak => Decimal::from(summary.longest_streak),
+            ProfileLeaderboardSort::Consistency => summary.consistency_score,
+        }
+    }
+
+    /// [Self::summary_key] adjusted for the number of picks behind the summary. Only hit rate and
+    /// average return are adjusted, other sorts rank by their raw value.
+    pub fn ranked_summary_key(
+        &self,
+        summary: &ProfilePickSummary,
+        milestone: PickMilestone,
+        ranking: &LeaderboardRanking,
+    ) -> Decimal {
+        let samples = summary.total_picks.max(0) as u64;
+        match (self, ranking.mode) {
+            (_, RankingMode::Raw) => self.summary_key(summary, milestone),
+            (ProfileLeaderboardSort::HitRate, RankingMode::Wilson) => {
+                summary.hit_rate_interval.lower
+            }
+            (ProfileLeaderboardSort::HitRate, RankingMode::Bayesian) => bayesian_average(
+                summary.hit_rate,
+                samples,
+                ranking.prior_hit_rate,
+                LeaderboardRanking::PRIOR_PICKS,
+            ),
+            (ProfileLeaderboardSort::AverageReturn, _) => bayesian_average(
+                summary.average_pick_return,
+                samples,
+                ranking.prior_average_return,
+                LeaderboardRanking::PRIOR_PICKS,
+            ),
+            _ => self.summary_key(summary, milestone),
+        }
+    }
+}
+
+impl ToString for ProfileLeaderboardSort {
+    fn to_string(&self) -> String {
+        match self {
+            ProfileLeaderboardSort::PickReturns => "pick_returns".to_string(),
+            ProfileLeaderboardSort::HitRate => "hit_rate".to_string(),
+            ProfileLeaderboardSort::RealizedProfit => "realized_profit".to_string(),
+            ProfileLeaderboardSort::TotalPicks => "total_picks".to_string(),
+            ProfileLeaderboardSort::MostRecentPick => "most_recent_pick".to_string(),
+            ProfileLeaderboardSort::AverageReturn => "average_return".to_string(),
+            ProfileLeaderboardSort::GreatestHits => "greatest_hits".to_string(),
+            ProfileLeaderboardSort::MilestoneHits => "milestone_hits".to_string(),
+            ProfileLeaderboardSort::MedianTimeToHit => "median_time_to_hit".to_string(),
+            ProfileLeaderboardSort::OriginalCalls => "original_calls".to_string(),
+            ProfileLeaderboardSort::CurrentStreak => "current_streak".to_string(),
+            ProfileLeaderboardSort::LongestStreak => "longest_streak".to_string(),
+            ProfileLeaderboardSort::Consistency => "consistency".to_string(),
+        }
+    }
+}
+
+/// How leaderboards account for the number of picks behind hit rates and average returns
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, ToSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RankingMode {
+    /// Rank by the raw values
+    #[default]
+    Raw,
+    /// Rank hit rate by the lower bound of its Wilson score interval. Average return, which has
+    /// no such bound, is ranked as with `bayesian`.
+    Wilson,
+    /// Rank hit rate and average return shrunk towards the leaderboard average
+    Bayesian,
+}
+
+impl ToString for RankingMode {
+    fn to_string(&self) -> String {
+        match self {
+            RankingMode::Raw => "raw".to_string(),
+            RankingMode::Wilson => "wilson".to_string(),
+            RankingMode::Bayesian => "bayesian".to_string(),
+        }
+    }
+}
+
+/// A [RankingMode] with the priors of the leaderboard it ranks
+#[derive(Debug, Clone, Default)]
+pub struct LeaderboardRanking {
+    pub mode: RankingMode,
+    /// Hit rate over every pick on the leaderboard
+    pub prior_hit_rate: Decimal,
+    /// Average return over every pick on the leaderboard
+    pub prior_average_return: Decimal,
+}
+
+impl LeaderboardRanking {
+    /// Number of picks the prior weighs as in Bayesian averages
+    pub const PRIOR_PICKS: u64 = 10;
+
+    pub fn new<'a>(
+        mode: RankingMode,
+        summaries: impl IntoIterator<Item = &'a ProfilePickSummary>,
+    ) -> Self {
+        let mut picks = Decimal::ZERO;
+        let mut hits = Decimal::ZERO;
+        let mut returns = Decimal::ZERO;
+        for summary in summaries {
+            let total_picks = Decimal::from(summary.total_picks);
+            picks += total_picks;
+            hits += summary.hit_rate * total_picks / Decimal::ONE_HUNDRED;
+            returns += summary.average_pick_return * total_picks;
+        }
+        if picks.is_zero() {
+            return LeaderboardRanking {
+                mode,
+                ..Default::default()
+            };
+        }
+
+        LeaderboardRanking {
+            mode,
+            prior_hit_rate: hits * Decimal::ONE_HUNDRED / picks,
+            prior_average_return: returns / picks,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, IntoParams, Default, Clone)]
 pub struct TokenQuery {
     pub username: Option<String>,
     pub picked_after: Option<TimePeriod>,
-    #[param(default = 1)]
-    pub page: u32,
+    /// Cursor of the page to return, from the `next_cursor` of the previous page
+    pub cursor: Option<String>,
     #[param(default = 10)]
     pub limit: u32,
     pub order_by: Option<PickLeaderboardSort>,
@@ -35,6 +226,12 @@ pub struct TokenQuery {
     pub filter_by_group: bool,
     #[serde(deserialize_with = "crate::utils::serde_utils::deserialize_optional_uuid")]
     pub user_id: Option<Uuid>,
+    /// Only return picks on this chain, available options: `solana`, `ethereum`, `base`
+    pub chain: Option<Chain>,
+    /// Only return picks that reached this milestone, also used by the `time_to_milestone` sort
+    pub milestone: Option<PickMilestone>,
+    /// Only return original calls or follow-up calls, available options: `original`, `follow_up`
+    pub call_type: Option<CallType>,
 }
 
 #[derive(Debug, Deserialize, ToSchema, Default)]
@@ -65,8 +262,8 @@ pub struct GroupProfileQuery {
 #[derive(Debug, Deserialize, IntoParams, Default)]
 pub struct GroupMembersQuery {
     pub username: Option<String>,
-    #[param(default = 1)]
-    pub page: u32,
+    /// Cursor of the page to return, from the `next_cursor` of the previous page
+    pub cursor: Option<String>,
     #[param(default = 10)]
     pub limit: u32,
     pub order_by: Option<ProfileLeaderboardSort>,
@@ -89,6 +286,112 @@ pub struct ProfileLeaderboardQuery {
     pub user_id: Option<Uuid>,
     #[serde(default)]
     pub filter_by_group: bool,
+    /// Only rank picks on this chain, available options: `solana`, `ethereum`, `base`
+    pub chain: Option<Chain>,
+    /// Milestone used by the `milestone_hits` and `median_time_to_hit` sorts, defaults to `2x`
+    pub milestone: Option<PickMilestone>,
+    /// Only rank original calls or follow-up calls, available options: `original`, `follow_up`
+    pub call_type: Option<CallType>,
+    /// Cursor of the page to return, from the `next_cursor` of the previous page
+    pub cursor: Option<String>,
+    /// Number of profiles to return, every profile if empty
+    pub limit: Option<u32>,
+    /// How hit rate and average return account for the number of picks, available options:
+    /// `raw`, `wilson`, `bayesian`. Defaults to `raw`. Rankings other than `raw` only rank users
+    /// with the minimum of picks configured for the leaderboard and time period.
+    #[serde(default)]
+    pub ranking: RankingMode,
+}
+
+#[derive(Deserialize, IntoParams, Debug, Default)]
+pub struct RankHistoryQuery {
+    /// Leaderboard sort, defaults to `average_return`
+    #[serde(default)]
+    pub sort: ProfileLeaderboardSort,
+    /// Time period of the leaderboard, defaults to `month`
+    #[serde(default = "default_time_period")]
+    pub picked_after: TimePeriod,
+    /// Snapshots to return, available options: `hourly`, `daily`. Defaults to `daily`
+    #[serde(default)]
+    pub resolution: SnapshotResolution,
+    /// Only return snapshots taken after this date, the last 30 days by default
+    pub since: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize, IntoParams, Debug, Default)]
+pub struct ProfileCompareQuery {
+    /// Comma separated usernames of the users to compare
+    #[serde(deserialize_with = "crate::utils::serde_utils::deserialize_comma_separated")]
+    #[param(value_type = String)]
+    pub users: Vec<String>,
+    /// Time period of the compared picks, defaults to `month`
+    #[serde(default = "default_time_period")]
+    pub picked_after: TimePeriod,
+    /// Only compare picks made in this group, every group if empty
+    pub group_id: Option<i64>,
+}
+
+#[derive(Deserialize, IntoParams, Debug)]
+pub struct FeedQuery {
+    /// The user the feed is built for
+    pub user_id: Uuid,
+    /// Cursor of the page to return, from the `next_cursor` of the previous page
+    pub cursor: Option<String>,
+    /// Number of items to return, defaults to 20
+    pub limit: Option<u32>,
+}
+
+#[derive(Deserialize, IntoParams, Debug, Default)]
+pub struct SimulationQuery {
+    /// Time period of the replayed picks, defaults to `month`
+    #[serde(default = "default_time_period")]
+    pub picked_after: TimePeriod,
+    /// Only replay picks made in this group
+    pub group_id: Option<i64>,
+    /// Minutes between a call and the simulated buy, at most 1440. Defaults to 0
+    #[serde(default)]
+    pub entry_delay_minutes: u32,
+    /// USD put in every pick, at most 1,000,000. Defaults to 100
+    pub position_size: Option<Decimal>,
+    /// Multiple of the entry price positions are sold at, at most 1000. Never taken profit on
+    /// if empty
+    pub take_profit: Option<Decimal>,
+    /// Percentage lost from the entry price positions are sold at, never stopped out if empty
+    pub stop_loss: Option<Decimal>,
+    /// Prices the picks are replayed with, available options: `snapshots`, `ohlcv`. Defaults to
+    /// `snapshots`
+    #[serde(default)]
+    pub source: SimulationPriceSource,
+}
+
+impl SimulationQuery {
+    /// Identifies the simulation in caches
+    pub fn cache_key(&self) -> String {
+        format!(
+            "{}:{}:{}:{}:{}:{}:{}",
+            self.picked_after.to_string(),
+            self.group_id.map_or("all".to_string(), |id| id.to_string()),
+            self.entry_delay_minutes,
+            self.position_size.unwrap_or_default(),
+            self.take_profit.unwrap_or_default(),
+            self.stop_loss.unwrap_or_default(),
+            self.source.to_string()
+        )
+    }
+}
+
+#[derive(Deserialize, IntoParams, Debug, Default)]
+pub struct SeasonLeaderboardQuery {
+    /// Leaderboard sort, defaults to `average_return`
+    #[serde(default)]
+    pub sort: ProfileLeaderboardSort,
+    /// Milestone of the milestone sorts, defaults to `2x`
+    #[serde(default)]
+    pub milestone: PickMilestone,
+    /// Cursor of the page of users to return, from the `next_cursor` of the previous page
+    pub cursor: Option<String>,
+    /// Number of users to return, every user if empty
+    pub limit: Option<u32>,
 }
 
 #[derive(Deserialize, IntoParams, Default)]
@@ -105,14 +408,58 @@ pub enum PickLeaderboardSort {
     Newest,
     #[default]
     Reached,
+    /// Time between the call and the milestone being reached
+    TimeToMilestone,
+}
+
+impl PickLeaderboardSort {
+    /// The `ORDER BY` expression, `milestone` is only used by [PickLeaderboardSort::TimeToMilestone].
+    pub fn order_expression(&self, milestone: PickMilestone) -> String {
+        match self {
+            PickLeaderboardSort::Hottest => "t.volume_24h".to_string(),
+            PickLeaderboardSort::Newest => "tp.call_date".to_string(),
+            PickLeaderboardSort::Reached => "tp.highest_multiplier".to_string(),
+            PickLeaderboardSort::TimeToMilestone => format!(
+                "((tp.milestone_hits->>'{}')::timestamptz - tp.call_date)",
+                milestone.to_string()
+            ),
+        }
+    }
+}
+
+impl PickLeaderboardSort {
+    /// The keyset expression of the sort. Unlike [PickLeaderboardSort::order_expression] it is
+    /// never null, so picks without a value still page in a stable order.
+    pub fn cursor_expression(&self, milestone: PickMilestone) -> String {
+        match self {
+            PickLeaderboardSort::Hottest => "COALESCE(t.volume_24h, -1)".to_string(),
+            PickLeaderboardSort::Newest => "tp.call_date".to_string(),
+            PickLeaderboardSort::Reached => "COALESCE(tp.highest_multiplier, -1)".to_string(),
+            // Picks that never reached the milestone come after the slowest ones
+            PickLeaderboardSort::TimeToMilestone => format!(
+                "COALESCE(EXTRACT(EPOCH FROM {}), {})",
+                self.order_expression(milestone),
+                i32::MAX
+            ),
+        }
+    }
+
+    /// SQL type a cursor key of the sort is cast back to
+    pub fn cursor_type(&self) -> &'static str {
+        match self {
+            PickLeaderboardSort::Newest => "timestamptz",
+            _ => "numeric",
+        }
+    }
 }
 
 impl ToString for PickLeaderboardSort {
     fn to_string(&self) -> String {
         match self {
-            PickLeaderboardSort::Hottest => "t.volume_24h".to_string(),
-            PickLeaderboardSort::Newest => "call_date".to_string(),
-            PickLeaderboardSort::Reached => "highest_multiplier".to_string(),
+            PickLeaderboardSort::Hottest => "hottest".to_string(),
+            PickLeaderboardSort::Newest => "newest".to_string(),
+            PickLeaderboardSort::Reached => "reached".to_string(),
+            PickLeaderboardSort::TimeToMilestone => "time_to_milestone".to_string(),
         }
     }
 }
@@ -124,8 +471,8 @@ pub fn default_limit() -> i64 {
 #[derive(Debug, Deserialize, IntoParams, Default)]
 pub struct GroupPicksQuery {
     pub username: Option<String>,
-    #[param(default = 1)]
-    pub page: u32,
+    /// Cursor of the page to return, from the `next_cursor` of the previous page
+    pub cursor: Option<String>,
     #[param(default = 10)]
     pub limit: u32,
     pub order_by: Option<PickLeaderboardSort>,
@@ -145,4 +492,40 @@ pub struct GroupLeaderboardQuery {
     #[param(default = "month")]
     /// Timeframe to get picks for, available options: `six_hours`, `day`, `week`, `month`, `all_time`
     pub timeframe: TimePeriod,
+    /// Only return picks on this chain, available options: `solana`, `ethereum`, `base`
+    pub chain: Option<Chain>,
+}
+
+#[derive(Debug, Deserialize, IntoParams, Clone)]
+pub struct TokenSearchQuery {
+    /// Prefix of the token symbol, name or address
+    pub q: String,
+    /// Only return tokens on this chain, available options: `solana`, `ethereum`, `base`
+    pub chain: Option<Chain>,
+    #[param(default = 10)]
+    #[serde(default = "default_limit")]
+    /// Number of tokens to return
+    pub limit: i64,
+}
+
+#[derive(Debug, Deserialize, IntoParams, Default)]
+pub struct PickHistoryQuery {
+    /// Cursor of the page to return, from the `next_cursor` of the previous page
+    pub cursor: Option<String>,
+    /// Number of observations to return, defaults to and at most 1000
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Deserialize, IntoParams, Default)]
+pub struct DeletedTokenPicksQuery {
+    /// Cursor of the page to return, from the `next_cursor` of the previous page
+    pub cursor: Option<String>,
+    #[param(default = 10)]
+    #[serde(default = "default_page_limit")]
+    /// Number of picks to return
+    pub limit: u32,
+}
+
+fn default_page_limit() -> u32 {
+    10
 }
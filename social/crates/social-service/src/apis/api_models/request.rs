@@ -1,15 +1,20 @@
-use std::collections::HashMap;
-
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
 use serde::Deserialize;
 use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
 use crate::{
-    models::{groups::GroupSettings, token_picks::TokenPickResponse},
+    models::{
+        groups::GroupSettings,
+        qualification_policies::DEFAULT_CANCELLATION_WINDOW_SECONDS,
+        token_picks::{CallType, PickMilestone},
+        tokens::Chain,
+    },
     utils::time::TimePeriod,
 };
 
-use super::query::PickLeaderboardSort;
+use super::query::{PickLeaderboardSort, ProfileLeaderboardSort};
 
 #[derive(Deserialize, ToSchema, Clone, Default)]
 #[serde(rename_all = "camelCase")]
@@ -39,24 +44,16 @@ pub struct DeleteTokenPickRequest {
     pub telegram_user_id: i64,
     /// Telegram group id
     pub telegram_chat_id: i64,
-}
-
-#[derive(Debug, Deserialize, IntoParams, Default, serde::Serialize, ToSchema)]
-pub struct PaginatedTokenPickGroupResponse {
-    /// Group name and token picks
-    pub items: HashMap<String, Vec<TokenPickResponse>>,
-    pub total: i64,
-    pub page: u32,
-    pub limit: u32,
-    pub total_pages: u32,
+    /// Why the user cancelled the pick
+    pub reason: Option<String>,
 }
 
 #[derive(Debug, Deserialize, IntoParams, Default)]
 pub struct TokenGroupQuery {
     #[serde(deserialize_with = "crate::utils::serde_utils::deserialize_optional_uuid")]
     pub user_id: Option<Uuid>,
-    #[param(default = 1)]
-    pub page: u32,
+    /// Cursor of the page to return, from the `next_cursor` of the previous page
+    pub cursor: Option<String>,
     #[param(default = 10)]
     pub limit: u32,
     pub order_by: Option<PickLeaderboardSort>,
@@ -77,4 +74,176 @@ pub struct RemoveUserRequest {
 pub struct TokenValueDataRequest {
     pub address: Vec<String>,
     pub time_period: Option<TimePeriod>,
+    #[serde(default)]
+    pub chain: Chain,
+}
+
+#[derive(Deserialize, ToSchema, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BackfillRequest {
+    /// Backfill a single pick
+    pub pick_id: Option<i64>,
+    /// Backfill every pick of a user
+    pub user_id: Option<Uuid>,
+    /// Backfill picks called after this date, requires `to`
+    pub from: Option<DateTime<Utc>>,
+    /// Backfill picks called before this date, requires `from`
+    pub to: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize, ToSchema, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateQualificationPolicyRequest {
+    /// Environment the policy applies to, any environment if empty
+    pub environment: Option<String>,
+    /// Group the policy applies to, any group if empty
+    pub group_id: Option<i64>,
+    /// Minimum FDV for a pick to qualify
+    pub min_market_cap: Decimal,
+    /// Minimum liquidity, as a ratio of the 24h volume, below the large cap threshold
+    pub min_liquidity_volume_ratio: Decimal,
+    /// FDV from which `largeCapMinLiquidity` applies instead of the volume ratio
+    pub large_cap_threshold: Decimal,
+    /// Minimum liquidity above the large cap threshold
+    pub large_cap_min_liquidity: Decimal,
+    /// Tokens below this 24h volume count as a bust
+    pub bust_min_volume: Decimal,
+    /// Tokens below this liquidity count as a bust
+    pub bust_min_liquidity: Decimal,
+    /// Tokens below this market cap count as a bust
+    pub bust_min_market_cap: Decimal,
+    /// How long after the call a pick can be cancelled, defaults to 60 seconds
+    #[serde(default = "default_cancellation_window_seconds")]
+    pub cancellation_window_seconds: i32,
+}
+
+#[derive(Deserialize, ToSchema, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateSeasonRequest {
+    pub name: String,
+    /// Group the season is run in, every group if empty
+    pub group_id: Option<i64>,
+    /// Picks made from this date count in the season
+    pub starts_at: DateTime<Utc>,
+    /// Picks made from this date no longer count. The standings are frozen once the last picks
+    /// settled, 7 days later.
+    pub ends_at: DateTime<Utc>,
+}
+
+fn default_cancellation_window_seconds() -> i32 {
+    DEFAULT_CANCELLATION_WINDOW_SECONDS
+}
+
+#[derive(Deserialize, ToSchema, Debug, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportFormat {
+    #[default]
+    Csv,
+    Jsonl,
+}
+
+#[derive(Deserialize, IntoParams, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportTokenPicksQuery {
+    /// Format of the request body, available options: `csv`, `jsonl`
+    #[serde(default)]
+    pub format: ImportFormat,
+    /// Validate the rows and resolve their market cap without saving anything
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// A historical call, as a CSV row with a header or a JSON line
+#[derive(Deserialize, ToSchema, Debug, Clone)]
+pub struct ImportTokenPickRow {
+    pub address: String,
+    /// Detected from the address if empty, available options: `solana`, `ethereum`, `base`
+    pub chain: Option<String>,
+    pub telegram_user_id: i64,
+    pub telegram_chat_id: i64,
+    pub timestamp: DateTime<Utc>,
+    pub telegram_message_id: Option<i64>,
+    /// Market cap of the token at the call, takes precedence over `supply`
+    pub market_cap: Option<Decimal>,
+    /// Supply of the token at the call. Without it or the market cap, the call is priced with the
+    /// current supply of the token and the row reports a warning.
+    pub supply: Option<Decimal>,
+}
+
+#[derive(Deserialize, ToSchema, Debug, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Ndjson,
+    Parquet,
+}
+
+impl ExportFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv",
+            ExportFormat::Ndjson => "application/x-ndjson",
+            ExportFormat::Parquet => "application/vnd.apache.parquet",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Ndjson => "ndjson",
+            ExportFormat::Parquet => "parquet",
+        }
+    }
+}
+
+/// Filters of a token picks export. Without a username or groups, every pick on the platform is
+/// exported.
+#[derive(Deserialize, IntoParams, Debug, Default)]
+pub struct ExportTokenPicksQuery {
+    /// Format of the export, available options: `csv`, `ndjson`, `parquet`
+    #[serde(default)]
+    pub format: ExportFormat,
+    /// Only export the picks of this user
+    pub username: Option<String>,
+    /// Only export the picks made in these groups
+    pub group_ids: Option<Vec<i64>>,
+    pub picked_after: Option<TimePeriod>,
+    pub order_by: Option<PickLeaderboardSort>,
+    pub order_direction: Option<String>,
+    /// Only export picks on this chain, available options: `solana`, `ethereum`, `base`
+    pub chain: Option<Chain>,
+    /// Only export picks that reached this milestone
+    pub milestone: Option<PickMilestone>,
+    /// Only export original calls or follow-up calls, available options: `original`, `follow_up`
+    pub call_type: Option<CallType>,
+    /// Only export picks that qualify for leaderboards
+    #[serde(default)]
+    pub qualified_only: bool,
+}
+
+#[derive(Deserialize, ToSchema, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SetUserPickLimitRequest {
+    /// Picks the user can make per day across every group, unlimited if null
+    pub max_daily_picks: Option<u32>,
+}
+
+#[derive(Deserialize, ToSchema, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SetLeaderboardMinPicksRequest {
+    /// The leaderboard sort, every sort without its own minimum if null
+    pub leaderboard: Option<ProfileLeaderboardSort>,
+    pub time_period: TimePeriod,
+    /// Fewest picks a user needs to be ranked by a ranking mode other than `raw`
+    pub min_picks: u32,
+}
+
+#[derive(Deserialize, ToSchema, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreTokenPickRequest {
+    /// Moderator restoring the pick
+    pub moderator: String,
+    /// Why the pick is restored
+    pub reason: Option<String>,
 }
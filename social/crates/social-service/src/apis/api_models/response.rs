@@ -1,4 +1,4 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, FixedOffset, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
@@ -7,21 +7,26 @@ use uuid::Uuid;
 use crate::{
     external_services::rust_monorepo::get_latest_w_metadata::LatestTokenMetadataResponse,
     models::{
-        groups::{Group, GroupSettings},
+        groups::{CreateOrUpdateGroup, Group, GroupSettings},
         profiles::ProfileDetailsResponse,
-        token_picks::TokenPickResponse,
+        tiers::TiersType,
+        token_picks::{TokenPickResponse, TokenPickSnapshot},
+        tokens::{Chain, Token},
         user_stats::UserStats,
+        users::UserResponse,
     },
     utils::time::TimePeriod,
 };
 
-#[derive(Serialize, ToSchema)]
-pub struct PaginatedTokenPickResponse {
-    pub items: Vec<TokenPickResponse>,
-    pub total: i64,
-    pub page: u32,
+/// Response envelope of cursor paginated list endpoints
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CursorPaginatedResponse<T> {
+    /// Items of the current page
+    pub items: T,
+    /// Number of items requested
     pub limit: u32,
-    pub total_pages: u32,
+    /// Cursor of the next page, empty on the last page
+    pub next_cursor: Option<String>,
 }
 
 #[derive(Serialize, ToSchema)]
@@ -30,15 +35,6 @@ pub struct ProfilePicksAndStatsResponse {
     stats: UserStats,
 }
 
-#[derive(serde::Serialize, ToSchema)]
-pub struct PaginatedGroupMembersResponse {
-    pub items: Vec<ProfileDetailsResponse>,
-    pub total: usize,
-    pub page: u32,
-    pub limit: u32,
-    pub total_pages: u32,
-}
-
 #[derive(Debug, Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct GroupMembersResponse {
@@ -46,6 +42,8 @@ pub struct GroupMembersResponse {
     pub group_name: String,
     pub group_id: i64,
     pub total: i64,
+    /// Cursor of the next page of members, empty on the last page
+    pub next_cursor: Option<String>,
 }
 
 #[derive(Serialize, ToSchema, Default)]
@@ -103,28 +101,28 @@ pub struct SavedTokenPickResponse {
     pub has_update: bool,
 }
 
-#[derive(Serialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct TokenPickDiff {
     pub market_cap_diff: f32,
     pub price_diff: f32,
 }
 
-#[derive(Serialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct TokenPickWithDiffResponse {
     pub pick: TokenPickResponse,
     pub pick_diff: Option<TokenPickDiff>,
 }
 
-#[derive(Serialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum TokenPickResponseType {
     Saved(TokenPickResponse),
     AlreadyCalled(TokenPickWithDiffResponse),
 }
 
-#[derive(Serialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct TokenPickResponseWithMetadata {
     #[serde(flatten)]
     pub pick: TokenPickResponseType,
@@ -143,3 +141,214 @@ pub struct TokenValueDataResponse {
     pub time_period: TimePeriod,
     pub price_human_time: String,
 }
+
+/// A token with every pick of it and statistics about its callers
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenDetailResponse {
+    pub token: Token,
+    pub total_picks: usize,
+    /// Number of users who picked the token
+    pub unique_callers: usize,
+    /// Number of groups the token was picked in
+    pub unique_groups: usize,
+    /// The first call of the token across every group
+    pub first_caller: Option<TokenCaller>,
+    /// The pick with the highest multiplier
+    pub best_caller: Option<TokenCaller>,
+    /// Average highest multiplier of the picks, by the tier of their caller
+    pub multiplier_by_tier: Vec<TierMultiplier>,
+    /// Every pick of the token, oldest first
+    pub picks: Vec<TokenPickResponse>,
+}
+
+/// A pick of a token, summarized as who called it and how it went
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenCaller {
+    pub pick_id: i64,
+    /// Empty for picks made in anonymous groups
+    pub user: Option<UserResponse>,
+    pub group: CreateOrUpdateGroup,
+    pub call_date: DateTime<FixedOffset>,
+    pub market_cap_at_call: Decimal,
+    pub highest_multiplier: f32,
+}
+
+impl From<&TokenPickResponse> for TokenCaller {
+    fn from(pick: &TokenPickResponse) -> Self {
+        Self {
+            pick_id: pick.id,
+            user: pick.user.clone(),
+            group: pick.group.clone(),
+            call_date: pick.call_date,
+            market_cap_at_call: pick.market_cap_at_call,
+            highest_multiplier: pick.highest_mult_post_call,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TierMultiplier {
+    pub tier: TiersType,
+    /// Number of users of the tier who picked the token
+    pub callers: usize,
+    pub picks: usize,
+    pub average_multiplier: f32,
+}
+
+/// A token matching a search, with its latest picks
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenSearchResult {
+    pub token: Token,
+    /// Number of picks of the token across every group
+    pub pick_count: i64,
+    /// Date the token was last picked
+    pub last_pick_date: Option<DateTime<FixedOffset>>,
+    /// Latest picks of the token, newest first
+    pub latest_picks: Vec<TokenPickResponse>,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenPickHistoryResponse {
+    /// The pick ID
+    pub pick_id: i64,
+    /// The market cap at the time the pick was made
+    pub market_cap_at_call: Decimal,
+    /// Date the pick was made
+    pub call_date: DateTime<FixedOffset>,
+    /// Market cap observations since the call, oldest first
+    pub points: Vec<TokenPickSnapshot>,
+    /// Cursor of the next page of observations, `None` on the last page
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BackfillResponse {
+    /// Number of picks queued for the backfill
+    pub picks_queued: usize,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportRowStatus {
+    /// The row passed validation in a dry run
+    Valid,
+    Imported,
+    /// The token was already called in the group within 24 hours of the row
+    Duplicate,
+    Invalid,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportRowReport {
+    /// Line of the row in the file, starting at 1 with CSV headers excluded
+    pub row: usize,
+    pub status: ImportRowStatus,
+    pub address: Option<String>,
+    pub chain: Option<Chain>,
+    /// The market cap at the time of the call, from the row or the OHLCV price of the timestamp
+    /// and the token supply
+    pub market_cap_at_call: Option<Decimal>,
+    /// The created pick, not set in dry runs
+    pub pick_id: Option<i64>,
+    /// Whether the caller did not exist and is (or would be) created
+    pub new_user: bool,
+    /// Whether the group did not exist and is (or would be) created
+    pub new_group: bool,
+    pub errors: Vec<String>,
+    /// Issues that did not invalidate the row, such as a market cap priced from the current supply
+    pub warnings: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportTokenPicksResponse {
+    pub dry_run: bool,
+    pub total_rows: usize,
+    pub valid_rows: usize,
+    pub imported_rows: usize,
+    pub rows: Vec<ImportRowReport>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportJobStatus {
+    Running,
+    Completed,
+    Failed,
+}
+
+/// An import running in the background
+#[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportJobResponse {
+    pub id: Uuid,
+    pub status: ImportJobStatus,
+    pub dry_run: bool,
+    pub total_rows: usize,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    /// Why the import failed
+    pub error: Option<String>,
+    /// Report of every row, once the import completed
+    pub report: Option<ImportTokenPicksResponse>,
+}
+
+/// A token pick flattened into one row of an export
+#[derive(Serialize, ToSchema, Debug)]
+pub struct PickExportRow {
+    pub id: i64,
+    pub chain: String,
+    pub token_address: String,
+    pub token_symbol: String,
+    /// Empty for picks made in anonymous groups
+    pub username: Option<String>,
+    pub group_id: i64,
+    pub group_name: String,
+    pub call_date: DateTime<FixedOffset>,
+    pub price_at_call: Decimal,
+    pub market_cap_at_call: Decimal,
+    pub current_market_cap: Decimal,
+    pub highest_market_cap: Option<Decimal>,
+    pub highest_market_cap_date: Option<DateTime<FixedOffset>>,
+    pub highest_multiplier: f32,
+    pub hit_date: Option<DateTime<FixedOffset>>,
+    pub lowest_market_cap: Option<Decimal>,
+    pub max_drawdown: Option<Decimal>,
+    pub is_original_call: bool,
+    pub minutes_after_first_call: Option<i64>,
+    pub qualification_policy_id: i64,
+}
+
+impl From<TokenPickResponse> for PickExportRow {
+    fn from(pick: TokenPickResponse) -> Self {
+        Self {
+            id: pick.id,
+            chain: pick.token.chain,
+            token_address: pick.token.address,
+            token_symbol: pick.token.symbol,
+            username: pick.user.map(|u| u.username),
+            group_id: pick.group.id,
+            group_name: pick.group.name,
+            call_date: pick.call_date,
+            price_at_call: pick.price_at_call,
+            market_cap_at_call: pick.market_cap_at_call,
+            current_market_cap: pick.current_market_cap,
+            highest_market_cap: pick.highest_mc_post_call,
+            highest_market_cap_date: pick.highest_mc_post_call_date,
+            highest_multiplier: pick.highest_mult_post_call,
+            hit_date: pick.hit_date,
+            lowest_market_cap: pick.lowest_mc_post_call,
+            max_drawdown: pick.max_drawdown,
+            is_original_call: pick.is_original_call,
+            minutes_after_first_call: pick.minutes_after_first_call,
+            qualification_policy_id: pick.qualification_policy_id,
+        }
+    }
+}
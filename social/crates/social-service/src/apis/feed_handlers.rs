@@ -0,0 +1,46 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Json,
+};
+use std::sync::Arc;
+
+use crate::{
+    models::feed::FeedItem,
+    services::feed_service::FeedService,
+    utils::errors::{app_error::AppError, error_payload::ErrorPayload},
+    AppState,
+};
+
+use super::api_models::{query::FeedQuery, response::CursorPaginatedResponse};
+
+pub const TAG: &str = "feed";
+
+/// Get the feed of a user, newest first: the picks of the users they follow, the milestones those
+/// picks reach and the picks made in their groups
+#[utoipa::path(
+    get,
+    tag = TAG,
+    path = "/",
+    operation_id = "getFeed",
+    responses(
+        (status = 200, description = "Feed retrieved successfully", body = CursorPaginatedResponse<Vec<FeedItem>>),
+        (status = 400, description = "Invalid cursor", body = ErrorPayload),
+        (status = 500, description = "Internal server error", body = ErrorPayload)
+    ),
+    params(FeedQuery)
+)]
+pub(super) async fn get_feed(
+    State(app_state): State<Arc<AppState>>,
+    Query(query): Query<FeedQuery>,
+) -> Result<(StatusCode, Json<CursorPaginatedResponse<Vec<FeedItem>>>), AppError> {
+    let (items, next_cursor) = app_state.feed_service.get_feed(&query).await?;
+    Ok((
+        StatusCode::OK,
+        Json(CursorPaginatedResponse {
+            items,
+            limit: FeedService::page_size(&query),
+            next_cursor,
+        }),
+    ))
+}
@@ -14,21 +14,25 @@ use uuid::Uuid;
 use crate::{
     apis::api_models::query::{
         GroupLeaderboardQuery, GroupMembersQuery, GroupPicksQuery, ListGroupMembersQuery,
-        ListGroupsQuery,
+        ListGroupsQuery, SimulationQuery,
     },
     models::{
         groups::{CreateOrUpdateGroup, GroupUser},
+        profiles::ProfileDetailsResponse,
+        simulations::SimulationResponse,
         token_picks::TokenPickResponse,
     },
-    utils::errors::{app_error::AppError, error_payload::ErrorPayload},
+    utils::{
+        cursor::page_size,
+        errors::{app_error::AppError, error_payload::ErrorPayload},
+    },
     AppState,
 };
 
 use super::api_models::{
     request::{AddUserRequest, CreateGroupRequest, TokenGroupQuery},
     response::{
-        GroupResponse, GroupUserResponse, LeaderboardGroupResponse, PaginatedGroupMembersResponse,
-        PaginatedTokenPickResponse,
+        CursorPaginatedResponse, GroupResponse, GroupUserResponse, LeaderboardGroupResponse,
     },
 };
 
@@ -114,7 +118,8 @@ pub(super) async fn list_groups(
     path = "/{id}/picks",
     operation_id = "getGroupPicks",
     responses(
-        (status = 200, description = "Success", body = Vec<PaginatedTokenPickResponse>),
+        (status = 200, description = "Success", body = CursorPaginatedResponse<Vec<TokenPickResponse>>),
+        (status = 400, description = "Invalid cursor", body = ErrorPayload),
         (status = 404, description = "Group not found", body = ErrorPayload),
         (status = 500, description = "Internal server error", body = ErrorPayload)
     ),
@@ -124,33 +129,34 @@ pub(super) async fn get_group_picks(
     State(app_state): State<Arc<AppState>>,
     Path(group_id): Path<i64>,
     Query(query): Query<GroupPicksQuery>,
-) -> Result<(StatusCode, Json<PaginatedTokenPickResponse>), AppError> {
-    let limit = query.limit;
-    let page = query.page;
+) -> Result<
+    (
+        StatusCode,
+        Json<CursorPaginatedResponse<Vec<TokenPickResponse>>>,
+    ),
+    AppError,
+> {
+    let limit = page_size(query.limit);
 
-    let picks = app_state
+    let (picks, next_cursor) = app_state
         .token_service
         .list_token_picks_group(TokenGroupQuery {
             group_ids: Some(vec![group_id]),
             limit,
-            page,
+            cursor: query.cursor,
             order_by: query.order_by,
             order_direction: query.order_direction,
             get_all: None,
             user_id: None,
         })
         .await?;
-    let total = picks.1;
-    let total_pages = ((total as f64) / (limit as f64)).ceil() as u32;
-    let picks = picks.0.into_values().next().unwrap_or(vec![]);
+    let picks = picks.into_values().next().unwrap_or(vec![]);
     Ok((
         StatusCode::OK,
-        Json(PaginatedTokenPickResponse {
+        Json(CursorPaginatedResponse {
             items: picks,
-            total,
-            page,
             limit,
-            total_pages,
+            next_cursor,
         }),
     ))
 }
@@ -162,7 +168,8 @@ pub(super) async fn get_group_picks(
     path = "/{id}/members",
     operation_id = "getGroupMembers",
     responses(
-        (status = 200, description = "Group members retrieved successfully", body = PaginatedGroupMembersResponse),
+        (status = 200, description = "Group members retrieved successfully", body = CursorPaginatedResponse<Vec<ProfileDetailsResponse>>),
+        (status = 400, description = "Invalid cursor", body = ErrorPayload),
         (status = 404, description = "Group not found", body = ErrorPayload),
         (status = 500, description = "Internal server error", body = ErrorPayload)
     ),
@@ -172,24 +179,31 @@ pub(super) async fn get_group_members(
     State(app_state): State<Arc<AppState>>,
     Path(group_id): Path<i64>,
     Query(query): Query<GroupMembersQuery>,
-) -> Result<(StatusCode, Json<PaginatedGroupMembersResponse>), AppError> {
-    let limit = query.limit;
-    let page = query.page;
+) -> Result<
+    (
+        StatusCode,
+        Json<CursorPaginatedResponse<Vec<ProfileDetailsResponse>>>,
+    ),
+    AppError,
+> {
+    let limit = page_size(query.limit);
 
     let res = app_state
         .group_service
-        .list_group_members(group_id, limit, page, query.order_by, query.username)
+        .list_group_members(
+            group_id,
+            limit,
+            query.cursor,
+            query.order_by,
+            query.username,
+        )
         .await?;
-    let total = res.members.len();
-    let total_pages = ((res.total as f64) / (limit as f64)).ceil() as u32;
     Ok((
         StatusCode::OK,
-        Json(PaginatedGroupMembersResponse {
+        Json(CursorPaginatedResponse {
             items: res.members,
-            total,
-            page,
             limit,
-            total_pages,
+            next_cursor: res.next_cursor,
         }),
     ))
 }
@@ -288,7 +302,7 @@ pub(super) async fn leaderboard(
             app_state.group_service.list_group_members(
                 group.id,
                 0,
-                0,
+                None,
                 params.sort,
                 params.username.clone(),
             )
@@ -341,3 +355,36 @@ pub async fn get_group_leaderboard(
 
     Ok((StatusCode::OK, Json(picks)))
 }
+
+/// Simulate copy-trading a group: buying a fixed amount of every token called in it and selling
+/// it on a take-profit, a stop-loss or after 30 days. `groupId` of the query is ignored.
+#[utoipa::path(
+    get,
+    tag = GROUP_TAG,
+    path = "/{id}/simulation",
+    responses(
+        (status = 200, description = "Simulation run successfully", body = SimulationResponse),
+        (status = 400, description = "Invalid simulation parameters", body = ErrorPayload),
+        (status = 404, description = "Group not found", body = ErrorPayload),
+        (status = 500, description = "Internal server error", body = ErrorPayload),
+    ),
+    params(
+        ("id" = i64, Path, description = "Group ID"),
+        SimulationQuery
+    )
+)]
+pub async fn simulate_group(
+    State(app_state): State<Arc<AppState>>,
+    Path(group_id): Path<i64>,
+    Query(query): Query<SimulationQuery>,
+) -> Result<(StatusCode, Json<SimulationResponse>), AppError> {
+    if !app_state.group_service.group_exists(group_id).await? {
+        return Err(AppError::NotFound("Group not found".to_string()));
+    }
+
+    let simulation = app_state
+        .simulation_service
+        .simulate_group(group_id, &query)
+        .await?;
+    Ok((StatusCode::OK, Json(simulation)))
+}
@@ -18,6 +18,15 @@ static API_KEY: Lazy<String> = Lazy::new(|| {
     })
 });
 
+pub static ADMIN_API_KEY_HEADER: Lazy<HeaderName> =
+    Lazy::new(|| HeaderName::from_static("x-admin-api-key"));
+static ADMIN_API_KEY: Lazy<Option<String>> = Lazy::new(|| {
+    env::var("ADMIN_API_KEY").ok().or_else(|| {
+        warn!("ADMIN_API_KEY not found in environment, admin endpoints are disabled");
+        None
+    })
+});
+
 pub async fn verify_api_key(request: Request<Body>, next: Next) -> Result<Response, AppError> {
     let api_key = request
         .headers()
@@ -30,3 +39,19 @@ pub async fn verify_api_key(request: Request<Body>, next: Next) -> Result<Respon
         None => Err(AppError::Unauthorized("Missing API key".to_string())),
     }
 }
+
+pub async fn verify_admin_api_key(
+    request: Request<Body>,
+    next: Next,
+) -> Result<Response, AppError> {
+    let api_key = request
+        .headers()
+        .get(ADMIN_API_KEY_HEADER.as_str())
+        .and_then(|header| header.to_str().ok());
+
+    match (api_key, ADMIN_API_KEY.as_deref()) {
+        (Some(key), Some(admin_key)) if key == admin_key => Ok(next.run(request).await),
+        (Some(_), _) => Err(AppError::Unauthorized("Invalid admin API key".to_string())),
+        (None, _) => Err(AppError::Unauthorized("Missing admin API key".to_string())),
+    }
+}
@@ -2,17 +2,20 @@ use std::sync::Arc;
 
 use axum::middleware;
 use axum::Router;
-use middlewares::security::verify_api_key;
+use middlewares::security::{verify_admin_api_key, verify_api_key};
 use utoipa::OpenApi;
 use utoipa_axum::{router::OpenApiRouter, routes};
 use utoipa_scalar::{Scalar, Servable};
 
 use crate::AppState;
 
+pub mod admin_handlers;
 pub mod api_models;
+pub mod feed_handlers;
 pub mod group_handlers;
 pub mod middlewares;
 pub mod profile_handlers;
+pub mod season_handlers;
 pub mod token_handlers;
 pub mod user_handlers;
 
@@ -22,7 +25,10 @@ pub mod user_handlers;
         (name = "users", description = "User management API"),
         (name = "token-picks", description = "Token pick management API"),
         (name = "groups", description = "Group management API"),
-        (name = "profiles", description = "Profile management API")
+        (name = "profiles", description = "Profile management API"),
+        (name = "seasons", description = "Season leaderboards API"),
+        (name = "feed", description = "Activity feed API"),
+        (name = "admin", description = "Administrative API")
     ),
     modifiers(&SecurityAddon),
     components(
@@ -59,12 +65,25 @@ pub fn setup_routes() -> Router<Arc<AppState>> {
             token_handlers::create_token_pick,
             token_handlers::delete_token_pick
         ))
-        .routes(routes!(token_handlers::list_group_token_picks));
+        .routes(routes!(token_handlers::list_group_token_picks))
+        .routes(routes!(token_handlers::get_token_pick_history))
+        .routes(routes!(token_handlers::export_token_picks))
+        .routes(routes!(token_handlers::search_tokens))
+        .routes(routes!(token_handlers::get_token_detail));
 
     let profile_router = OpenApiRouter::new()
         .routes(routes!(profile_handlers::get_profile))
         .routes(routes!(profile_handlers::get_profile_picks_and_stats))
-        .routes(routes!(profile_handlers::leaderboard));
+        .routes(routes!(profile_handlers::leaderboard))
+        .routes(routes!(profile_handlers::compare_profiles))
+        .routes(routes!(profile_handlers::get_rank_history))
+        .routes(routes!(profile_handlers::simulate_profile));
+
+    let season_router = OpenApiRouter::new()
+        .routes(routes!(season_handlers::list_seasons))
+        .routes(routes!(season_handlers::get_season_leaderboard));
+
+    let feed_router = OpenApiRouter::new().routes(routes!(feed_handlers::get_feed));
 
     let user_router = OpenApiRouter::new()
         .routes(routes!(user_handlers::follow_user))
@@ -85,7 +104,30 @@ pub fn setup_routes() -> Router<Arc<AppState>> {
         .routes(routes!(group_handlers::get_group_members))
         .routes(routes!(group_handlers::get_group_picks))
         .routes(routes!(group_handlers::get_group_leaderboard))
+        .routes(routes!(group_handlers::simulate_group))
         .routes(routes!(group_handlers::leaderboard));
+
+    let admin_router = OpenApiRouter::new()
+        .routes(routes!(admin_handlers::backfill_token_picks))
+        .routes(routes!(admin_handlers::import_token_picks))
+        .routes(routes!(admin_handlers::get_token_picks_import))
+        .routes(routes!(admin_handlers::list_deleted_token_picks))
+        .routes(routes!(admin_handlers::restore_token_pick))
+        .routes(routes!(
+            admin_handlers::list_qualification_policies,
+            admin_handlers::create_qualification_policy
+        ))
+        .routes(routes!(admin_handlers::create_season))
+        .routes(routes!(
+            admin_handlers::list_leaderboard_min_picks,
+            admin_handlers::set_leaderboard_min_picks
+        ))
+        .routes(routes!(
+            admin_handlers::set_user_pick_limit,
+            admin_handlers::delete_user_pick_limit
+        ))
+        .route_layer(middleware::from_fn(verify_admin_api_key));
+
     let user_router = OpenApiRouter::with_openapi(api_doc.clone()).nest("/users", user_router);
 
     let profile_router =
@@ -93,13 +135,23 @@ pub fn setup_routes() -> Router<Arc<AppState>> {
 
     let token_router = OpenApiRouter::with_openapi(api_doc.clone()).nest("/tokens", token_router);
 
+    let season_router =
+        OpenApiRouter::with_openapi(api_doc.clone()).nest("/seasons", season_router);
+
+    let feed_router = OpenApiRouter::with_openapi(api_doc.clone()).nest("/feed", feed_router);
+
     let group_router = OpenApiRouter::with_openapi(api_doc.clone()).nest("/groups", group_router);
 
+    let admin_router = OpenApiRouter::with_openapi(api_doc.clone()).nest("/admin", admin_router);
+
     let router = OpenApiRouter::new()
         .merge(user_router)
         .merge(profile_router)
         .merge(token_router)
-        .merge(group_router);
+        .merge(season_router)
+        .merge(feed_router)
+        .merge(group_router)
+        .merge(admin_router);
 
     let (api_router, api_openapi) = OpenApiRouter::new()
         .nest("/api/v1", router)
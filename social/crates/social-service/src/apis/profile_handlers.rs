@@ -1,10 +1,16 @@
 use crate::{
-    apis::api_models::query::ProfileLeaderboardQuery,
+    apis::api_models::query::{
+        ProfileCompareQuery, ProfileLeaderboardQuery, RankHistoryQuery, SimulationQuery,
+    },
     models::{
-        profiles::ProfileDetailsResponse,
-        token_picks::{ProfilePicksAndStatsQuery, TokenPickResponse},
+        profiles::{ProfileComparison, ProfileDetailsResponse},
+        rank_history::RankHistoryPoint,
+        simulations::SimulationResponse,
+        token_picks::{CallType, ProfilePicksAndStatsQuery, TokenPickResponse},
+        tokens::Chain,
         user_stats::UserStats,
     },
+    services::profile_service::ProfileService,
     utils::{
         errors::{app_error::AppError, error_payload::ErrorPayload},
         time::{default_time_period, TimePeriod},
@@ -12,7 +18,7 @@ use crate::{
     AppState,
 };
 use axum::{
-    extract::{Query, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     Json,
 };
@@ -20,7 +26,7 @@ use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use utoipa::ToSchema;
 
-use super::api_models::response::LeaderboardResponse;
+use super::api_models::response::CursorPaginatedResponse;
 
 pub const TAG: &str = "profiles";
 
@@ -58,6 +64,8 @@ pub struct ProfileQuery {
     #[serde(default = "default_time_period")]
     pub picked_after: TimePeriod,
     pub group_ids: Option<Vec<i64>>,
+    pub chain: Option<Chain>,
+    pub call_type: Option<CallType>,
 }
 
 #[derive(Deserialize, Serialize, ToSchema)]
@@ -93,6 +101,29 @@ pub(super) async fn get_profile_picks_and_stats(
     ))
 }
 
+/// Compare the stats of several users over the same time period and group, with the tokens at
+/// least two of them called and who called them first
+#[utoipa::path(
+    get,
+    tag = TAG,
+    path = "/compare",
+    operation_id = "compareProfiles",
+    responses(
+        (status = 200, description = "Profiles compared successfully", body = ProfileComparison),
+        (status = 400, description = "Invalid number of users", body = ErrorPayload),
+        (status = 404, description = "User not found", body = ErrorPayload),
+        (status = 500, description = "Internal server error", body = ErrorPayload)
+    ),
+    params(ProfileCompareQuery)
+)]
+pub(super) async fn compare_profiles(
+    State(app_state): State<Arc<AppState>>,
+    Query(params): Query<ProfileCompareQuery>,
+) -> Result<(StatusCode, Json<ProfileComparison>), AppError> {
+    let comparison = app_state.profile_service.compare_profiles(&params).await?;
+    Ok((StatusCode::OK, Json(comparison)))
+}
+
 /// Get leaderboard
 #[utoipa::path(
     get,
@@ -100,7 +131,7 @@ pub(super) async fn get_profile_picks_and_stats(
     path = "/leaderboard",
     operation_id = "getLeaderboard",
     responses(
-        (status = 200, description = "Leaderboard retrieved successfully", body = LeaderboardResponse),
+        (status = 200, description = "Leaderboard retrieved successfully", body = CursorPaginatedResponse<Vec<ProfileDetailsResponse>>),
         (status = 400, description = "Invalid request parameters", body = ErrorPayload),
         (status = 500, description = "Internal server error", body = ErrorPayload)
     ),
@@ -109,7 +140,13 @@ pub(super) async fn get_profile_picks_and_stats(
 pub(super) async fn leaderboard(
     State(app_state): State<Arc<AppState>>,
     Query(params): Query<ProfileLeaderboardQuery>,
-) -> Result<(StatusCode, Json<LeaderboardResponse>), AppError> {
+) -> Result<
+    (
+        StatusCode,
+        Json<CursorPaginatedResponse<Vec<ProfileDetailsResponse>>>,
+    ),
+    AppError,
+> {
     if params.username.is_none() && params.following {
         return Err(AppError::BadRequest(
             "Cannot use following without username".to_string(),
@@ -124,6 +161,75 @@ pub(super) async fn leaderboard(
         }
     }
 
-    let leaderboard = app_state.profile_service.list_profiles(&params).await?;
-    Ok((StatusCode::OK, leaderboard.into()))
+    let (profiles, next_cursor) = app_state
+        .profile_service
+        .list_profiles_page(&params)
+        .await?;
+    Ok((
+        StatusCode::OK,
+        Json(CursorPaginatedResponse {
+            items: profiles,
+            limit: ProfileService::page_size(&params),
+            next_cursor,
+        }),
+    ))
+}
+
+/// Get the ranks of a user on the daily or hourly snapshots of a global leaderboard
+#[utoipa::path(
+    get,
+    tag = TAG,
+    path = "/{username}/rank-history",
+    operation_id = "getRankHistory",
+    responses(
+        (status = 200, description = "Rank history retrieved successfully", body = [RankHistoryPoint]),
+        (status = 400, description = "Rank history is not kept for the leaderboard", body = ErrorPayload),
+        (status = 404, description = "User not found", body = ErrorPayload),
+        (status = 500, description = "Internal server error", body = ErrorPayload)
+    ),
+    params(
+        ("username" = String, Path, description = "Username"),
+        RankHistoryQuery
+    )
+)]
+pub(super) async fn get_rank_history(
+    State(app_state): State<Arc<AppState>>,
+    Path(username): Path<String>,
+    Query(query): Query<RankHistoryQuery>,
+) -> Result<(StatusCode, Json<Vec<RankHistoryPoint>>), AppError> {
+    let history = app_state
+        .rank_history_service
+        .get_rank_history(&username, &query)
+        .await?;
+    Ok((StatusCode::OK, Json(history)))
+}
+
+/// Simulate copy-trading a user: buying a fixed amount of every token they called and selling it
+/// on a take-profit, a stop-loss or after 30 days
+#[utoipa::path(
+    get,
+    tag = TAG,
+    path = "/{username}/simulation",
+    operation_id = "simulateProfile",
+    responses(
+        (status = 200, description = "Simulation run successfully", body = SimulationResponse),
+        (status = 400, description = "Invalid simulation parameters", body = ErrorPayload),
+        (status = 404, description = "User not found", body = ErrorPayload),
+        (status = 500, description = "Internal server error", body = ErrorPayload)
+    ),
+    params(
+        ("username" = String, Path, description = "Username"),
+        SimulationQuery
+    )
+)]
+pub(super) async fn simulate_profile(
+    State(app_state): State<Arc<AppState>>,
+    Path(username): Path<String>,
+    Query(query): Query<SimulationQuery>,
+) -> Result<(StatusCode, Json<SimulationResponse>), AppError> {
+    let simulation = app_state
+        .simulation_service
+        .simulate_user(&username, &query)
+        .await?;
+    Ok((StatusCode::OK, Json(simulation)))
 }
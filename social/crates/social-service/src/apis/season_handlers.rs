@@ -0,0 +1,64 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use std::sync::Arc;
+
+use crate::{
+    models::seasons::{Season, SeasonLeaderboardResponse},
+    utils::errors::{app_error::AppError, error_payload::ErrorPayload},
+    AppState,
+};
+
+use super::api_models::query::SeasonLeaderboardQuery;
+
+pub const TAG: &str = "seasons";
+
+/// List every season, latest first
+#[utoipa::path(
+    get,
+    tag = TAG,
+    path = "/",
+    operation_id = "listSeasons",
+    responses(
+        (status = 200, description = "Seasons retrieved successfully", body = Vec<Season>),
+        (status = 500, description = "Internal server error", body = ErrorPayload)
+    )
+)]
+pub(super) async fn list_seasons(
+    State(app_state): State<Arc<AppState>>,
+) -> Result<(StatusCode, Json<Vec<Season>>), AppError> {
+    let seasons = app_state.season_service.list_seasons().await?;
+    Ok((StatusCode::OK, Json(seasons)))
+}
+
+/// Get the user and group leaderboards of a season. Standings are final once the season closed,
+/// and computed from the current picks before. Users are paginated with a cursor.
+#[utoipa::path(
+    get,
+    tag = TAG,
+    path = "/{id}/leaderboard",
+    operation_id = "getSeasonLeaderboard",
+    responses(
+        (status = 200, description = "Season leaderboard retrieved successfully", body = SeasonLeaderboardResponse),
+        (status = 400, description = "Invalid cursor", body = ErrorPayload),
+        (status = 404, description = "Season not found", body = ErrorPayload),
+        (status = 500, description = "Internal server error", body = ErrorPayload)
+    ),
+    params(
+        ("id" = i64, Path, description = "Season ID"),
+        SeasonLeaderboardQuery
+    )
+)]
+pub(super) async fn get_season_leaderboard(
+    State(app_state): State<Arc<AppState>>,
+    Path(id): Path<i64>,
+    Query(query): Query<SeasonLeaderboardQuery>,
+) -> Result<(StatusCode, Json<SeasonLeaderboardResponse>), AppError> {
+    let leaderboard = app_state
+        .season_service
+        .get_season_leaderboard(id, &query)
+        .await?;
+    Ok((StatusCode::OK, Json(leaderboard)))
+}
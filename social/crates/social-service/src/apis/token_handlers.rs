@@ -1,34 +1,45 @@
 use axum::{
-    extract::{Query, State},
-    http::StatusCode,
+    body::Body,
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
     Json,
 };
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use crate::{
     apis::api_models::{
-        query::TokenQuery, request::TokenGroupQuery, response::PaginatedTokenPickResponse,
+        query::{PickHistoryQuery, TokenQuery, TokenSearchQuery},
+        request::TokenGroupQuery,
+    },
+    models::{
+        token_picks::TokenPickResponse,
+        tokens::{Chain, TokenPickRequest},
+    },
+    utils::{
+        cursor::page_size,
+        errors::{app_error::AppError, error_payload::ErrorPayload},
     },
-    models::{token_picks::TokenPickResponse, tokens::TokenPickRequest},
-    utils::errors::{app_error::AppError, error_payload::ErrorPayload},
     AppState,
 };
 
 use super::api_models::{
-    request::{DeleteTokenPickRequest, PaginatedTokenPickGroupResponse},
-    response::TokenPickResponseWithMetadata,
+    request::{DeleteTokenPickRequest, ExportTokenPicksQuery},
+    response::{
+        CursorPaginatedResponse, PickExportRow, TokenDetailResponse, TokenPickHistoryResponse,
+        TokenPickResponseWithMetadata, TokenSearchResult,
+    },
 };
 
 pub const TAG: &str = "token-picks";
 
-/// List all token picks with pagination
+/// List all token picks, paginated by cursor
 #[utoipa::path(
     get,
     tag = TAG,
     path = "/picks",
     operation_id = "listTokenPicks",
     responses(
-        (status = 200, description = "Token picks retrieved successfully", body = PaginatedTokenPickResponse),
+        (status = 200, description = "Token picks retrieved successfully", body = CursorPaginatedResponse<Vec<TokenPickResponse>>),
         (status = 404, description = "No token picks found", body = ErrorPayload),
         (status = 500, description = "Internal server error", body = ErrorPayload)
     ),
@@ -37,27 +48,30 @@ pub const TAG: &str = "token-picks";
 pub(super) async fn list_token_picks(
     State(app_state): State<Arc<AppState>>,
     Query(query): Query<TokenQuery>,
-) -> Result<(StatusCode, Json<PaginatedTokenPickResponse>), AppError> {
-    let limit = query.limit;
-    let page = query.page;
+) -> Result<
+    (
+        StatusCode,
+        Json<CursorPaginatedResponse<Vec<TokenPickResponse>>>,
+    ),
+    AppError,
+> {
+    let limit = page_size(query.limit);
 
-    let (picks, total) = app_state
+    let (picks, next_cursor) = app_state
         .token_service
         .list_token_picks(query, Some(false))
         .await?;
 
-    let response = PaginatedTokenPickResponse {
-        items: picks.into_iter().map(|p| p.into()).collect(),
-        total,
-        page,
+    let response = CursorPaginatedResponse {
+        items: picks,
         limit,
-        total_pages: ((total as f64) / (limit as f64)).ceil() as u32,
+        next_cursor,
     };
 
     Ok((StatusCode::OK, Json(response)))
 }
 
-/// Create a new token pick
+/// Create a new token pick. Retries with the same idempotency key return the original response
 #[utoipa::path(
     post,
     tag = TAG,
@@ -67,7 +81,7 @@ pub(super) async fn list_token_picks(
     responses(
         (status = 200, description = "Token pick created successfully", body = TokenPickResponse),
         (status = 400, description = "Invalid request data", body = ErrorPayload),
-        (status = 409, description = "User reached maximum number of picks", body = ErrorPayload),
+        (status = 409, description = "User reached maximum number of picks or the request is already being processed", body = ErrorPayload),
         (status = 500, description = "Internal server error", body = ErrorPayload)
     )
 )]
@@ -86,7 +100,7 @@ pub(super) async fn create_token_pick(
     path = "/picks/group",
     operation_id = "listGroupTokenPicks",
     responses(
-        (status = 200, description = "Token picks by group retrieved successfully", body = PaginatedTokenPickGroupResponse),
+        (status = 200, description = "Token picks by group name retrieved successfully", body = CursorPaginatedResponse<HashMap<String, Vec<TokenPickResponse>>>),
         (status = 404, description = "No token picks found", body = ErrorPayload),
         (status = 500, description = "Internal server error", body = ErrorPayload)
     ),
@@ -95,27 +109,31 @@ pub(super) async fn create_token_pick(
 pub(super) async fn list_group_token_picks(
     State(app_state): State<Arc<AppState>>,
     Query(query): Query<TokenGroupQuery>,
-) -> Result<(StatusCode, Json<PaginatedTokenPickGroupResponse>), AppError> {
+) -> Result<
+    (
+        StatusCode,
+        Json<CursorPaginatedResponse<HashMap<String, Vec<TokenPickResponse>>>>,
+    ),
+    AppError,
+> {
     let limit = query.limit;
-    let page = query.page;
 
-    let (picks, total) = app_state
+    let (picks, next_cursor) = app_state
         .token_service
         .list_token_picks_group(query)
         .await?;
 
-    let response = PaginatedTokenPickGroupResponse {
+    let response = CursorPaginatedResponse {
         items: picks,
-        total,
-        page,
         limit,
-        total_pages: ((total as f64) / (limit as f64)).ceil() as u32,
+        next_cursor,
     };
 
     Ok((StatusCode::OK, Json(response)))
 }
 
-/// Delete a token pick
+/// Cancel a token pick. The pick is kept as deleted, and can only be cancelled within the
+/// cancellation window of its qualification policy.
 #[utoipa::path(
     delete,
     tag = TAG,
@@ -137,3 +155,114 @@ pub(super) async fn delete_token_pick(
     app_state.token_service.delete_token_pick(body).await?;
     Ok(StatusCode::OK)
 }
+
+/// Get the market cap history of a token pick
+#[utoipa::path(
+    get,
+    tag = TAG,
+    path = "/picks/{id}/history",
+    operation_id = "getTokenPickHistory",
+    responses(
+        (status = 200, description = "Token pick history retrieved successfully", body = TokenPickHistoryResponse),
+        (status = 400, description = "Invalid cursor", body = ErrorPayload),
+        (status = 404, description = "Token pick not found", body = ErrorPayload),
+        (status = 500, description = "Internal server error", body = ErrorPayload)
+    ),
+    params(
+        ("id" = i64, Path, description = "Token pick ID"),
+        PickHistoryQuery
+    )
+)]
+pub(super) async fn get_token_pick_history(
+    State(app_state): State<Arc<AppState>>,
+    Path(id): Path<i64>,
+    Query(query): Query<PickHistoryQuery>,
+) -> Result<(StatusCode, Json<TokenPickHistoryResponse>), AppError> {
+    let history = app_state
+        .token_service
+        .get_token_pick_history(id, &query)
+        .await?;
+    Ok((StatusCode::OK, Json(history)))
+}
+
+/// Export the picks of a user, of groups or of the whole platform as CSV, NDJSON or Parquet
+#[utoipa::path(
+    get,
+    tag = TAG,
+    path = "/picks/export",
+    operation_id = "exportTokenPicks",
+    responses(
+        (status = 200, description = "Token picks export, streamed as it is read", body = [PickExportRow], content_type = "text/csv"),
+        (status = 404, description = "User not found", body = ErrorPayload),
+        (status = 500, description = "Internal server error", body = ErrorPayload)
+    ),
+    params(ExportTokenPicksQuery)
+)]
+pub(super) async fn export_token_picks(
+    State(app_state): State<Arc<AppState>>,
+    Query(query): Query<ExportTokenPicksQuery>,
+) -> Result<(StatusCode, [(header::HeaderName, String); 2], Body), AppError> {
+    let format = query.format;
+    let picks = app_state.export_service.export_token_picks(query).await?;
+
+    let headers = [
+        (header::CONTENT_TYPE, format.content_type().to_string()),
+        (
+            header::CONTENT_DISPOSITION,
+            format!(
+                "attachment; filename=\"token_picks.{}\"",
+                format.extension()
+            ),
+        ),
+    ];
+
+    Ok((StatusCode::OK, headers, Body::from_stream(picks)))
+}
+
+/// Search tokens by symbol, name or address prefix, most picked first
+#[utoipa::path(
+    get,
+    tag = TAG,
+    path = "/search",
+    operation_id = "searchTokens",
+    responses(
+        (status = 200, description = "Matching tokens with their latest picks", body = [TokenSearchResult]),
+        (status = 400, description = "Search query too short", body = ErrorPayload),
+        (status = 500, description = "Internal server error", body = ErrorPayload)
+    ),
+    params(TokenSearchQuery)
+)]
+pub(super) async fn search_tokens(
+    State(app_state): State<Arc<AppState>>,
+    Query(query): Query<TokenSearchQuery>,
+) -> Result<(StatusCode, Json<Vec<TokenSearchResult>>), AppError> {
+    let results = app_state.token_service.search_tokens(query).await?;
+    Ok((StatusCode::OK, Json(results)))
+}
+
+/// Get a token with every pick of it across groups and statistics about its callers
+#[utoipa::path(
+    get,
+    tag = TAG,
+    path = "/{chain}/{address}",
+    operation_id = "getTokenDetail",
+    responses(
+        (status = 200, description = "Token retrieved successfully", body = TokenDetailResponse),
+        (status = 404, description = "Token not found", body = ErrorPayload),
+        (status = 500, description = "Internal server error", body = ErrorPayload)
+    ),
+    params(
+        ("chain" = Chain, Path, description = "Token chain, available options: `solana`, `ethereum`, `base`"),
+        ("address" = String, Path, description = "Token address")
+    )
+)]
+pub(super) async fn get_token_detail(
+    State(app_state): State<Arc<AppState>>,
+    Path((chain, address)): Path<(Chain, String)>,
+) -> Result<(StatusCode, Json<TokenDetailResponse>), AppError> {
+    let token = app_state
+        .token_service
+        .get_token_detail(&chain, &address)
+        .await?;
+    Ok((StatusCode::OK, Json(token)))
+}
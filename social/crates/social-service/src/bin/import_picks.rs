@@ -0,0 +1,50 @@
+//! Imports historical token picks from a CSV or JSONL file.
+//!
+//! Usage: `import_picks <file> [--format csv|jsonl] [--dry-run]`
+//!
+//! The format defaults to the file extension. The per-row report is printed as JSON.
+
+use std::{env, fs, process};
+
+use dotenv::dotenv;
+use social_service::{apis::api_models::request::ImportFormat, settings};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv().ok();
+    let args: Vec<String> = env::args().skip(1).collect();
+    let Some(path) = args.iter().find(|a| !a.starts_with("--")) else {
+        eprintln!("Usage: import_picks <file> [--format csv|jsonl] [--dry-run]");
+        process::exit(1);
+    };
+    let dry_run = args.iter().any(|a| a == "--dry-run");
+    let format = match args
+        .iter()
+        .position(|a| a == "--format")
+        .and_then(|i| args.get(i + 1))
+        .map(String::as_str)
+    {
+        Some("jsonl") => ImportFormat::Jsonl,
+        Some("csv") => ImportFormat::Csv,
+        Some(other) => {
+            eprintln!("Unsupported format {}", other);
+            process::exit(1);
+        }
+        None if path.ends_with(".jsonl") || path.ends_with(".ndjson") => ImportFormat::Jsonl,
+        None => ImportFormat::Csv,
+    };
+
+    let settings = settings::load_settings().expect("Failed to load settings");
+    social_service::init_tracing(&settings);
+    let body = fs::read_to_string(path)?;
+
+    let db = social_service::setup_database(&settings.database_url).await?;
+    let container = social_service::setup_services(db, &settings).await?;
+    let report = container
+        .import_service
+        .run_import(format, &body, dry_run)
+        .await?;
+
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    Ok(())
+}
@@ -10,13 +10,23 @@ use crate::{
         usergate::UserGateService,
     },
     repositories::{
-        group_repository::GroupRepository, token_repository::TokenRepository,
-        user_repository::UserRepository,
+        achievement_repository::AchievementRepository, group_repository::GroupRepository,
+        leaderboard_repository::LeaderboardRepository, points_repository::PointsRepository,
+        qualification_policy_repository::QualificationPolicyRepository,
+        rank_history_repository::RankHistoryRepository, season_repository::SeasonRepository,
+        token_repository::TokenRepository, user_repository::UserRepository,
+        user_stats_repository::UserStatsRepository,
     },
     services::{
-        group_service::GroupService, profile_service::ProfileService, redis_service::RedisService,
-        s3_service::S3Service, telegram_service::TeloxideTelegramBotApi,
+        achievement_service::AchievementService, backfill_service::BackfillService,
+        export_service::ExportService, feed_service::FeedService, group_service::GroupService,
+        import_service::ImportService, points_service::PointsService,
+        profile_service::ProfileService, qualification_policy_service::QualificationPolicyService,
+        rank_history_service::RankHistoryService, redis_service::RedisService,
+        s3_service::S3Service, season_service::SeasonService,
+        simulation_service::SimulationService, telegram_service::TeloxideTelegramBotApi,
         token_service::TokenService, user_service::UserService,
+        user_stats_service::UserStatsService,
     },
     settings::Settings,
 };
@@ -24,8 +34,19 @@ use crate::{
 pub struct ServiceContainer {
     pub user_service: Arc<UserService>,
     pub profile_service: Arc<ProfileService>,
+    pub user_stats_service: Arc<UserStatsService>,
+    pub points_service: Arc<PointsService>,
+    pub achievement_service: Arc<AchievementService>,
+    pub rank_history_service: Arc<RankHistoryService>,
+    pub season_service: Arc<SeasonService>,
+    pub simulation_service: Arc<SimulationService>,
+    pub feed_service: Arc<FeedService>,
     pub token_service: Arc<TokenService>,
     pub group_service: Arc<GroupService>,
+    pub backfill_service: Arc<BackfillService>,
+    pub qualification_policy_service: Arc<QualificationPolicyService>,
+    pub import_service: Arc<ImportService>,
+    pub export_service: Arc<ExportService>,
     pub redis_service: Arc<RedisService>,
     pub telegram_service: Arc<TeloxideTelegramBotApi>,
     pub rust_monorepo_service: Arc<RustMonorepoService>,
@@ -40,6 +61,7 @@ impl ServiceContainer {
         settings: &Settings,
         db: Arc<PgPool>,
     ) -> Result<Self, Box<dyn std::error::Error>> {
+        let environment = settings.environment.clone().unwrap_or("prod".to_string());
         let user_repository = Arc::new(UserRepository::new(db.clone()));
         let token_repository = Arc::new(TokenRepository::new(db.clone()));
         let redis_service = Arc::new(RedisService::new(&settings.redis_url).await?);
@@ -88,6 +110,30 @@ impl ServiceContainer {
             telegram_service.clone(),
             s3_service.clone(),
         ));
+        let qualification_policy_service = Arc::new(QualificationPolicyService::new(
+            Arc::new(QualificationPolicyRepository::new(db.clone())),
+            environment.clone(),
+        ));
+        let user_stats_service = Arc::new(UserStatsService::new(
+            Arc::new(UserStatsRepository::new(db.clone())),
+            token_repository.clone(),
+        ));
+        let points_service = Arc::new(PointsService::new(
+            Arc::new(PointsRepository::new(db.clone())),
+            token_repository.clone(),
+            qualification_policy_service.clone(),
+        ));
+        let rank_history_repository = Arc::new(RankHistoryRepository::new(db.clone()));
+        let achievement_service = Arc::new(AchievementService::new(
+            Arc::new(AchievementRepository::new(db.clone())),
+            token_repository.clone(),
+            rank_history_repository.clone(),
+        ));
+        let rank_history_service = Arc::new(RankHistoryService::new(
+            rank_history_repository,
+            user_stats_service.clone(),
+            user_service.clone(),
+        ));
         let token_service = Arc::new(TokenService::new(
             token_repository.clone(),
             rust_monorepo_service.clone(),
@@ -95,15 +141,64 @@ impl ServiceContainer {
             redis_service.clone(),
             birdeye_service.clone(),
             group_service.clone(),
+            qualification_policy_service.clone(),
+            user_stats_service.clone(),
+            points_service.clone(),
+            achievement_service.clone(),
+        ));
+
+        let backfill_service = Arc::new(BackfillService::new(
+            token_repository.clone(),
+            birdeye_service.clone(),
+        ));
+        let import_service = Arc::new(ImportService::new(
+            token_service.clone(),
+            user_service.clone(),
+            group_service.clone(),
+            qualification_policy_service.clone(),
+            backfill_service.clone(),
+            token_repository.clone(),
+            birdeye_service.clone(),
+            redis_service.clone(),
+        ));
+        let export_service = Arc::new(ExportService::new(
+            token_repository.clone(),
+            user_service.clone(),
+        ));
+
+        let season_service = Arc::new(SeasonService::new(
+            Arc::new(SeasonRepository::new(db.clone())),
+            token_repository.clone(),
+            group_service.clone(),
+            redis_service.clone(),
+            s3_service.clone(),
+        ));
+
+        let simulation_service = Arc::new(SimulationService::new(
+            token_repository.clone(),
+            user_repository.clone(),
+            birdeye_service.clone(),
+            redis_service.clone(),
+        ));
+
+        let feed_service = Arc::new(FeedService::new(
+            user_repository.clone(),
+            group_service.clone(),
+            token_repository.clone(),
         ));
 
         let profile_service = ProfileService::new(
             user_repository,
             token_repository,
+            Arc::new(LeaderboardRepository::new(db.clone())),
             rust_monorepo_service.clone(),
             birdeye_service.clone(),
             redis_service.clone(),
             token_service.clone(),
+            user_stats_service.clone(),
+            points_service.clone(),
+            achievement_service.clone(),
+            rank_history_service.clone(),
             Arc::new(CieloService::new(
                 settings.cielo_api_key.clone(),
                 redis_service.clone(),
@@ -124,15 +219,26 @@ impl ServiceContainer {
         Ok(Self {
             user_service,
             profile_service,
+            user_stats_service,
+            points_service,
+            achievement_service,
+            rank_history_service,
+            season_service,
+            simulation_service,
+            feed_service,
             token_service,
             group_service,
+            backfill_service,
+            qualification_policy_service,
+            import_service,
+            export_service,
             redis_service,
             telegram_service,
             rust_monorepo_service,
             token_data_service,
             s3_service,
             birdeye_service,
-            environment: settings.environment.clone().unwrap_or("prod".to_string()),
+            environment,
         })
     }
 }
@@ -0,0 +1,127 @@
+use std::sync::Arc;
+
+use futures::future::join_all;
+use tracing::{error, info, instrument, warn};
+
+use crate::{
+    container::ServiceContainer,
+    events::types::AchievementEventData,
+    models::achievements::Achievement,
+    utils::{errors::app_error::AppError, redis_keys::RedisKeys},
+};
+
+pub struct AchievementHandler {
+    services: Arc<ServiceContainer>,
+}
+
+impl AchievementHandler {
+    /// Every instance receives the notification, the lock is held long enough for one to send it
+    const ANNOUNCE_LOCK_TTL_SECONDS: u64 = 300;
+
+    pub fn new(services: Arc<ServiceContainer>) -> Self {
+        Self { services }
+    }
+
+    /// Tells the user and their followers on Telegram that the user earned an achievement
+    #[instrument(skip(self, data), fields(user_id = %data.user.id))]
+    pub(super) async fn announce_achievement(
+        &self,
+        data: &AchievementEventData,
+    ) -> Result<(), AppError> {
+        let Some(achievement) = Achievement::find(&data.achievement_id) else {
+            warn!(
+                "Achievement {} is not in the catalogue",
+                data.achievement_id
+            );
+            return Ok(());
+        };
+
+        let lock_key = format!(
+            "{}{}:{}",
+            RedisKeys::ANNOUNCE_ACHIEVEMENT_LOCK_KEY,
+            data.user.id,
+            achievement.id
+        );
+        let lock_acquired = self
+            .services
+            .redis_service
+            .set_nx(&lock_key, "1", Self::ANNOUNCE_LOCK_TTL_SECONDS)
+            .await
+            .map_err(|e| {
+                warn!("Failed to acquire Redis lock: {}", e);
+                AppError::InternalServerError()
+            })?;
+        if !lock_acquired {
+            info!("Another instance is announcing achievement {}", lock_key);
+            return Ok(());
+        }
+
+        let profile_link = self.profile_link(&data.user.username);
+        let telegram_service = self.services.telegram_service.clone();
+        let user_message = format!(
+            "🏅 <b>Achievement unlocked: {}</b>\n\n{}\n\n<b><a href=\"{}\">View your profile on Bullpen</a></b>",
+            achievement.name, achievement.description, profile_link
+        );
+        if let Err(e) = telegram_service
+            .send_message(data.user.telegram_id as u64, &user_message)
+            .await
+        {
+            error!(
+                "Failed to send telegram message to {}: {}",
+                data.user.telegram_id, e
+            );
+        }
+
+        let followers = self
+            .services
+            .user_service
+            .get_followers(&data.user.username)
+            .await?;
+        info!(
+            "Announcing achievement {} of {} to {} followers",
+            achievement.id,
+            data.user.username,
+            followers.len()
+        );
+        let follower_message = format!(
+            "🏅 <b>{} unlocked {}</b>\n\n{}\n\n<b><a href=\"{}\">View {} on Bullpen</a></b>",
+            data.user.username,
+            achievement.name,
+            achievement.description,
+            profile_link,
+            data.user.username
+        );
+        join_all(followers.into_iter().map(|follower| {
+            let telegram_service = telegram_service.clone();
+            let message = follower_message.clone();
+            async move {
+                if let Err(e) = telegram_service
+                    .send_message(follower.telegram_id as u64, &message)
+                    .await
+                {
+                    error!(
+                        "Failed to send telegram message to {}: {}",
+                        follower.telegram_id, e
+                    );
+                }
+            }
+        }))
+        .await;
+
+        Ok(())
+    }
+
+    fn profile_link(&self, username: &str) -> String {
+        let bot_username = self
+            .services
+            .telegram_service
+            .bot_info
+            .as_ref()
+            .and_then(|info| info.username.clone())
+            .unwrap_or("BullpenFiBot".to_string());
+        format!(
+            "https://t.me/{}/app?startapp=profile_{}",
+            bot_username, username
+        )
+    }
+}
@@ -1,5 +1,7 @@
+pub mod achievement;
 pub mod token_pick;
 
+use achievement::AchievementHandler;
 use async_trait::async_trait;
 use chrono::{DateTime, FixedOffset, Utc};
 use token_pick::TokenPickHandler;
@@ -7,7 +9,7 @@ use tracing::{debug, error, instrument};
 
 use crate::utils::errors::app_error::AppError;
 
-use super::types::TokenPickEventData;
+use super::types::{AchievementEventData, TokenPickEventData};
 
 #[async_trait]
 pub trait EventHandler: Send + Sync {
@@ -35,6 +37,27 @@ impl EventHandler for TokenPickHandler {
     }
 }
 
+#[async_trait]
+impl EventHandler for AchievementHandler {
+    #[instrument(skip(self, payload))]
+    async fn handle(&self, payload: &str) -> Result<(), AppError> {
+        match serde_json::from_str::<AchievementEventData>(payload) {
+            Ok(data) => {
+                debug!(
+                    "Processing achievement event {} for user {}",
+                    data.achievement_id, data.user.username
+                );
+                self.announce_achievement(&data).await?;
+                Ok(())
+            }
+            Err(e) => {
+                error!("Failed to parse achievement payload: {}", e);
+                Err(AppError::InternalServerError())
+            }
+        }
+    }
+}
+
 pub fn format_number_with_metric_prefix(num: f64) -> String {
     if num >= 1_000_000_000.0 {
         format!("{:.1}B", num / 1_000_000_000.0)
@@ -11,6 +11,7 @@ use crate::{
         ext_data_services_v1::token_data::types::TokenReportData,
         rust_monorepo::get_latest_w_metadata::LatestTokenMetadataResponse,
     },
+    models::tokens::Chain,
     utils::{errors::app_error::AppError, redis_keys::RedisKeys},
 };
 use futures::future::join_all;
@@ -62,22 +63,23 @@ impl TokenPickHandler {
             followers.len(),
             data.token_pick.id
         );
+        let chain = Chain::try_from(data.token_pick.token.chain.clone())?;
         let token_price_metadata = self
             .services
-            .rust_monorepo_service
-            .get_latest_w_metadata(&[data.token_pick.token.address.clone()])
+            .token_service
+            .get_latest_token_metadata(&chain, &[data.token_pick.token.address.clone()])
             .await?
             .into_iter()
             .next()
-            .unwrap()
+            .ok_or_else(|| AppError::NotFound("Token metadata not found".to_string()))?
             .1;
-        let token_report = if let Some(token_data_service) = &self.services.token_data_service {
-            token_data_service
+        // Rugcheck reports are only available for Solana tokens
+        let token_report = match &self.services.token_data_service {
+            Some(token_data_service) if !chain.is_evm() => token_data_service
                 .get_token_report(&[data.token_pick.token.address.clone()])
                 .await
-                .ok()
-        } else {
-            None
+                .ok(),
+            _ => None,
         };
         let rugcheck_report_data =
             token_report.and_then(|r| r.data.into_iter().next().and_then(|(_, d)| d));
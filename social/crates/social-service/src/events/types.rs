@@ -1,6 +1,7 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
+use uuid::Uuid;
 
 use crate::{models::token_picks::TokenPick, utils::errors::app_error::AppError};
 
@@ -12,10 +13,28 @@ pub struct TokenPickEventData {
     pub token_pick: TokenPick,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AchievementEventUser {
+    pub id: Uuid,
+    pub username: String,
+    pub telegram_id: i64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AchievementEventData {
+    pub event_date: DateTime<Utc>,
+    pub achievement_id: String,
+    pub earned_at: DateTime<Utc>,
+    pub token_pick_id: Option<i64>,
+    pub user: AchievementEventUser,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase", untagged)]
 pub enum EventData {
     TokenPick(TokenPickEventData),
+    Achievement(AchievementEventData),
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -28,6 +47,7 @@ pub struct EventMessage {
 #[derive(PartialEq, Eq, Debug, Hash)]
 pub enum Channel {
     TokenPick,
+    Achievement,
 }
 
 impl TryFrom<&str> for Channel {
@@ -36,6 +56,7 @@ impl TryFrom<&str> for Channel {
     fn try_from(value: &str) -> Result<Self, Self::Error> {
         match value {
             "social.token_picks" => Ok(Channel::TokenPick),
+            "social.user_achievements" => Ok(Channel::Achievement),
             _ => Err(AppError::InternalServerError()),
         }
     }
@@ -45,6 +66,7 @@ impl Display for Channel {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             Channel::TokenPick => write!(f, "social.token_picks"),
+            Channel::Achievement => write!(f, "social.user_achievements"),
         }
     }
 }
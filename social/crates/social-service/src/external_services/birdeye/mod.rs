@@ -1,6 +1,7 @@
 pub mod multi_price;
 pub mod multi_volume;
 pub mod ohlcv;
+pub mod token_overview;
 
 use reqwest::Client;
 
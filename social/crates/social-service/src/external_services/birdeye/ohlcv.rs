@@ -28,7 +28,7 @@ pub struct BirdeyeOHLCVItems {
     pub items: Vec<BirdeyeOHLCVItem>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Default)]
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
 pub struct BirdeyeOHLCVItem {
     #[serde(rename = "o")]
     pub open: Decimal,
@@ -48,14 +48,15 @@ pub struct BirdeyeOHLCVItem {
 }
 
 impl BirdeyeService {
-    pub async fn get_ohlcv_request(
+    /// Fetches the candles between `time_start` and `time_end`, oldest first.
+    pub async fn get_ohlcv_items_request(
         &self,
         chain: &str,
         address: &str,
         time_start: i64,
         time_end: i64,
         resolution: &str,
-    ) -> Result<BirdeyeOHLCVItem, AppError> {
+    ) -> Result<Vec<BirdeyeOHLCVItem>, AppError> {
         let query = BirdeyeOHLCVQuery {
             address: address.to_owned(),
             interval: resolution.to_owned(),
@@ -80,10 +81,24 @@ impl BirdeyeService {
             AppError::InternalServerError()
         })?;
 
-        let max_high = ohlcv_response
-            .data
-            .unwrap_or_default()
-            .items
+        let mut items = ohlcv_response.data.unwrap_or_default().items;
+        items.sort_by_key(|item| item.unix_time);
+
+        Ok(items)
+    }
+
+    /// Returns the candle with the highest `high` between `time_start` and `time_end`.
+    pub async fn get_ohlcv_request(
+        &self,
+        chain: &str,
+        address: &str,
+        time_start: i64,
+        time_end: i64,
+        resolution: &str,
+    ) -> Result<BirdeyeOHLCVItem, AppError> {
+        let max_high = self
+            .get_ohlcv_items_request(chain, address, time_start, time_end, resolution)
+            .await?
             .into_iter()
             .max_by_key(|item| item.high)
             .unwrap_or_default();
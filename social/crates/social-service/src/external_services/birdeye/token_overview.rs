@@ -0,0 +1,139 @@
+use chrono::Utc;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, error};
+
+use crate::{
+    external_services::rust_monorepo::get_latest_w_metadata::{
+        BirdEyeMetadataDataProperty, LatestTokenMetadataResponse, TokenInfoProperty,
+    },
+    models::tokens::Chain,
+    utils::errors::app_error::AppError,
+};
+
+use super::BirdeyeService;
+
+pub const BIRDEYE_TOKEN_OVERVIEW_URL: &str = "https://public-api.birdeye.so/defi/token_overview";
+
+#[derive(Serialize, Debug)]
+pub struct BirdeyeTokenOverviewQuery {
+    pub address: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct BirdeyeTokenOverviewResponse {
+    pub success: bool,
+    pub data: Option<BirdeyeTokenOverview>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BirdeyeTokenOverview {
+    #[serde(default)]
+    pub address: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub symbol: String,
+    #[serde(default)]
+    pub decimals: usize,
+    #[serde(rename = "logoURI")]
+    pub logo_uri: Option<String>,
+    pub price: Option<Decimal>,
+    pub supply: Option<Decimal>,
+    #[serde(alias = "marketCap")]
+    pub mc: Option<Decimal>,
+    pub liquidity: Option<Decimal>,
+    pub holder: Option<Decimal>,
+    #[serde(rename = "v24hUSD")]
+    pub v_24h_usd: Option<Decimal>,
+    pub price_change_1h_percent: Option<Decimal>,
+    pub price_change_4h_percent: Option<Decimal>,
+    pub price_change_12h_percent: Option<Decimal>,
+    pub price_change_24h_percent: Option<Decimal>,
+}
+
+impl BirdeyeTokenOverview {
+    /// Maps the overview into the same shape the rust monorepo returns for Solana tokens.
+    pub fn into_latest_metadata(self, chain: Chain) -> LatestTokenMetadataResponse {
+        let now = Utc::now().timestamp();
+        let price = self.price.unwrap_or_default();
+        let supply = self.supply.unwrap_or_default();
+        let market_cap = if supply.is_zero() {
+            self.mc.unwrap_or_default()
+        } else {
+            price * supply
+        };
+
+        let mut metadata = BirdEyeMetadataDataProperty::default();
+        metadata.name = self.name.clone();
+        metadata.symbol = self.symbol.clone();
+        metadata.decimals = self.decimals;
+        metadata.logo_uri = self.logo_uri.clone();
+        metadata.liquidity = self.liquidity;
+        metadata.supply = self.supply;
+        metadata.mc = self.mc;
+        metadata.holder = self.holder;
+        metadata.v_24h_usd = self.v_24h_usd;
+        metadata.price_change_1h_percent = self.price_change_1h_percent;
+        metadata.price_change_4h_percent = self.price_change_4h_percent;
+        metadata.price_change_12h_percent = self.price_change_12h_percent;
+        metadata.price_change_24h_percent = self.price_change_24h_percent;
+
+        LatestTokenMetadataResponse {
+            address: self.address,
+            price,
+            price_fetched_at_unix_time: now,
+            market_cap,
+            metadata_fetched_at_unix_time: now,
+            metadata,
+            token_info: TokenInfoProperty {
+                supply,
+                name: self.name,
+                symbol: self.symbol,
+                image_url: self.logo_uri,
+            },
+            chain,
+        }
+    }
+}
+
+impl BirdeyeService {
+    pub async fn get_token_overview_request(
+        &self,
+        chain: &str,
+        address: &str,
+    ) -> Result<Option<BirdeyeTokenOverview>, AppError> {
+        debug!(
+            "Fetching token overview for address: {}, chain: {}",
+            address, chain
+        );
+        let response = self
+            .client
+            .get(BIRDEYE_TOKEN_OVERVIEW_URL)
+            .header("X-API-KEY", &self.api_key)
+            .header("x-chain", chain)
+            .query(&BirdeyeTokenOverviewQuery {
+                address: address.to_owned(),
+            })
+            .send()
+            .await?;
+
+        let json_str = response.text().await?;
+        let overview_response: BirdeyeTokenOverviewResponse = serde_json::from_str(&json_str)
+            .map_err(|e| {
+                error!("Error deserializing token overview {}: {}", json_str, e);
+                AppError::InternalServerError()
+            })?;
+
+        Ok(overview_response
+            .data
+            .filter(|data| data.price.is_some())
+            .map(|mut data| {
+                if data.address.is_empty() {
+                    data.address = address.to_owned();
+                }
+                data
+            }))
+    }
+}
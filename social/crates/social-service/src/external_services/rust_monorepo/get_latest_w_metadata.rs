@@ -2,6 +2,8 @@ use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
+use crate::models::tokens::Chain;
+
 #[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct LatestTokenMetadataResponse {
@@ -12,6 +14,9 @@ pub struct LatestTokenMetadataResponse {
     pub metadata_fetched_at_unix_time: i64,
     pub metadata: BirdEyeMetadataDataProperty,
     pub token_info: TokenInfoProperty,
+    /// Only Solana tokens are served by the rust monorepo, other chains fill this in themselves.
+    #[serde(default)]
+    pub chain: Chain,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default, ToSchema)]
@@ -1,3 +1,6 @@
+pub mod pick_snapshots;
+pub mod rank_history;
+pub mod seasons;
 pub mod token_picks;
 
 use std::sync::Arc;
@@ -7,16 +10,46 @@ use tracing::error;
 use crate::container::ServiceContainer;
 
 pub async fn start_background_jobs(app_state: Arc<ServiceContainer>) {
+    let token_picks_state = app_state.clone();
     tokio::spawn(async move {
         let mut interval = interval(Duration::from_secs(600)); // 10 minutes
         interval.tick().await; // Add immediate first tick
 
         loop {
-            if let Err(e) = token_picks::process_token_picks_job(&app_state).await {
+            if let Err(e) = token_picks::process_token_picks_job(&token_picks_state).await {
                 error!("Error processing token picks: {}", e);
             }
 
             interval.tick().await; // Move tick to end of loop
         }
     });
+
+    let pick_snapshots_state = app_state.clone();
+    tokio::spawn(async move {
+        let mut interval = interval(Duration::from_secs(3600)); // 1 hour, compacts once a day
+
+        loop {
+            interval.tick().await;
+
+            if let Err(e) = pick_snapshots::compact_pick_snapshots_job(&pick_snapshots_state).await
+            {
+                error!("Error compacting token pick snapshots: {}", e);
+            }
+        }
+    });
+
+    tokio::spawn(async move {
+        let mut interval = interval(Duration::from_secs(3600)); // 1 hour
+
+        loop {
+            interval.tick().await;
+
+            if let Err(e) = rank_history::snapshot_rank_history_job(&app_state).await {
+                error!("Error snapshotting leaderboard ranks: {}", e);
+            }
+            if let Err(e) = seasons::close_seasons_job(&app_state).await {
+                error!("Error closing seasons: {}", e);
+            }
+        }
+    });
 }
@@ -0,0 +1,39 @@
+use std::sync::Arc;
+
+use tracing::{debug, info, instrument, warn};
+
+use crate::{container::ServiceContainer, utils::errors::app_error::AppError};
+
+/// Kept after a successful compaction, so only one instance compacts per day
+const COMPACTION_LOCK_TTL: u64 = 60 * 60 * 23; // 23 hours
+
+/// Downsamples and expires the pick snapshots, once a day across instances
+#[instrument(skip(app_state), fields(job_id = %uuid::Uuid::new_v4()))]
+pub async fn compact_pick_snapshots_job(app_state: &Arc<ServiceContainer>) -> Result<(), AppError> {
+    let lock_key = format!("{}-pick-snapshots-compaction-lock", app_state.environment);
+    let lock_acquired = app_state
+        .redis_service
+        .set_nx(&lock_key, "1", COMPACTION_LOCK_TTL)
+        .await
+        .map_err(|e| {
+            warn!("Failed to acquire Redis lock: {}", e);
+            AppError::RedisError(e)
+        })?;
+
+    if !lock_acquired {
+        debug!("Token pick snapshots were already compacted today");
+        return Ok(());
+    }
+
+    info!("Compacting token pick snapshots");
+    let result = app_state.token_service.compact_pick_snapshots().await;
+
+    // Let the next run retry a failed compaction
+    if result.is_err() {
+        if let Err(e) = app_state.redis_service.delete_cached(&lock_key).await {
+            debug!(error = ?e, "Failed to release pick snapshots compaction lock");
+        }
+    }
+
+    result
+}
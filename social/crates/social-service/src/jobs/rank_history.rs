@@ -0,0 +1,35 @@
+use std::sync::Arc;
+
+use tracing::{debug, info, instrument, warn};
+
+use crate::{container::ServiceContainer, utils::errors::app_error::AppError};
+
+const SNAPSHOT_LOCK_TTL: u64 = 300; // 5 minutes
+
+/// Snapshots the leaderboard ranks, on one instance at a time
+#[instrument(skip(app_state), fields(job_id = %uuid::Uuid::new_v4()))]
+pub async fn snapshot_rank_history_job(app_state: &Arc<ServiceContainer>) -> Result<(), AppError> {
+    let lock_key = format!("{}-rank-history-lock", app_state.environment);
+    let lock_acquired = app_state
+        .redis_service
+        .set_nx(&lock_key, "1", SNAPSHOT_LOCK_TTL)
+        .await
+        .map_err(|e| {
+            warn!("Failed to acquire Redis lock: {}", e);
+            AppError::RedisError(e)
+        })?;
+
+    if !lock_acquired {
+        debug!("Another instance is currently snapshotting leaderboard ranks");
+        return Ok(());
+    }
+
+    info!("Snapshotting leaderboard ranks");
+    let result = app_state.rank_history_service.snapshot_leaderboards().await;
+
+    if let Err(e) = app_state.redis_service.delete_cached(&lock_key).await {
+        debug!(error = ?e, "Failed to release rank history lock");
+    }
+
+    result
+}
@@ -0,0 +1,34 @@
+use std::sync::Arc;
+
+use tracing::{debug, instrument, warn};
+
+use crate::{container::ServiceContainer, utils::errors::app_error::AppError};
+
+const CLOSE_SEASONS_LOCK_TTL: u64 = 300; // 5 minutes
+
+/// Freezes the standings of the seasons that ended, on one instance at a time
+#[instrument(skip(app_state), fields(job_id = %uuid::Uuid::new_v4()))]
+pub async fn close_seasons_job(app_state: &Arc<ServiceContainer>) -> Result<(), AppError> {
+    let lock_key = format!("{}-close-seasons-lock", app_state.environment);
+    let lock_acquired = app_state
+        .redis_service
+        .set_nx(&lock_key, "1", CLOSE_SEASONS_LOCK_TTL)
+        .await
+        .map_err(|e| {
+            warn!("Failed to acquire Redis lock: {}", e);
+            AppError::RedisError(e)
+        })?;
+
+    if !lock_acquired {
+        debug!("Another instance is currently closing seasons");
+        return Ok(());
+    }
+
+    let result = app_state.season_service.close_ended_seasons().await;
+
+    if let Err(e) = app_state.redis_service.delete_cached(&lock_key).await {
+        debug!(error = ?e, "Failed to release close seasons lock");
+    }
+
+    result
+}
@@ -13,8 +13,8 @@ use crate::{
     apis::api_models::request::TokenValueDataRequest,
     container::ServiceContainer,
     models::{
-        token_picks::{TokenPick, TokenPickResponse},
-        tokens::Token,
+        token_picks::{TokenPick, TokenPickResponse, TokenPickSnapshot},
+        tokens::{Chain, Token},
     },
     utils::{errors::app_error::AppError, redis_keys::RedisKeys, time::TimePeriod},
 };
@@ -44,6 +44,9 @@ pub async fn process_token_picks_job(app_state: &Arc<ServiceContainer>) -> Resul
     info!("Starting token picks processing");
 
     let result = async {
+        // Pick up policy versions created on other instances
+        app_state.qualification_policy_service.refresh().await?;
+
         let since = if app_state.environment == "staging" {
             Utc::now() - chrono::Duration::days(2)
         } else {
@@ -51,55 +54,81 @@ pub async fn process_token_picks_job(app_state: &Arc<ServiceContainer>) -> Resul
         };
         let tokens = app_state.token_service.get_all_tokens(since).await?;
 
-        let addresses: Vec<_> = tokens.keys().cloned().collect();
-        info!(token_count = addresses.len(), "Retrieved tokens to process");
+        info!(token_count = tokens.len(), "Retrieved tokens to process");
+
+        // Birdeye batch endpoints are per chain, so chunk each chain separately
+        let addresses_by_chain: HashMap<Chain, Vec<String>> =
+            tokens
+                .keys()
+                .fold(HashMap::new(), |mut acc, (chain, address)| {
+                    acc.entry(chain.clone()).or_default().push(address.clone());
+                    acc
+                });
 
         let semaphore = Arc::new(Semaphore::new(4));
-        let chunks: Vec<_> = addresses
-            .par_chunks(BATCH_SIZE as usize)
-            .map(|c| c.to_vec())
+        let chunks: Vec<_> = addresses_by_chain
+            .into_iter()
+            .flat_map(|(chain, addresses)| {
+                addresses
+                    .par_chunks(BATCH_SIZE as usize)
+                    .map(|c| (chain.clone(), c.to_vec()))
+                    .collect::<Vec<_>>()
+            })
             .collect();
 
         debug!(chunk_count = chunks.len(), "Created processing chunks");
 
-        let futures = chunks.into_iter().enumerate().map(|(idx, address_batch)| {
-            let app_state = Arc::clone(&app_state);
-            let semaphore = Arc::clone(&semaphore);
-            let tokens = tokens.clone();
-            let span = Span::current();
-
-            async move {
-                let _guard = span.enter();
-                debug!(
-                    chunk_idx = idx,
-                    size = address_batch.len(),
-                    "Processing chunk"
-                );
-
-                let _permit = semaphore.acquire().await.map_err(|_| {
-                    warn!("Failed to acquire semaphore");
-                    AppError::InternalServerError()
-                })?;
-
-                let chunk_start = Instant::now();
-                let result = process_address_batch(&app_state, &address_batch, &tokens).await;
-
-                let duration = chunk_start.elapsed().as_secs_f64();
-                debug!(
-                    chunk_idx = idx,
-                    duration = duration,
-                    "Finished processing chunk"
-                );
-
-                result
-            }
-        });
+        let futures = chunks
+            .into_iter()
+            .enumerate()
+            .map(|(idx, (chain, address_batch))| {
+                let app_state = Arc::clone(&app_state);
+                let semaphore = Arc::clone(&semaphore);
+                let tokens = tokens.clone();
+                let span = Span::current();
+
+                async move {
+                    let _guard = span.enter();
+                    debug!(
+                        chunk_idx = idx,
+                        chain = %chain.to_string(),
+                        size = address_batch.len(),
+                        "Processing chunk"
+                    );
+
+                    let _permit = semaphore.acquire().await.map_err(|_| {
+                        warn!("Failed to acquire semaphore");
+                        AppError::InternalServerError()
+                    })?;
+
+                    let chunk_start = Instant::now();
+                    let result =
+                        process_address_batch(&app_state, &chain, &address_batch, &tokens).await;
+
+                    let duration = chunk_start.elapsed().as_secs_f64();
+                    debug!(
+                        chunk_idx = idx,
+                        duration = duration,
+                        "Finished processing chunk"
+                    );
+
+                    result
+                }
+            });
 
         futures::stream::iter(futures)
             .buffer_unordered(4)
             .collect::<Vec<_>>()
             .await;
 
+        if let Err(e) = app_state
+            .user_stats_service
+            .refresh_stale_user_stats()
+            .await
+        {
+            warn!("Failed to refresh stale user stats: {}", e);
+        }
+
         Ok(())
     }
     .await;
@@ -125,8 +154,9 @@ pub async fn process_token_picks_job(app_state: &Arc<ServiceContainer>) -> Resul
 #[instrument(skip(app_state, tokens, address_batch), fields(batch_size = address_batch.len()))]
 async fn process_address_batch(
     app_state: &Arc<ServiceContainer>,
+    chain: &Chain,
     address_batch: &[String],
-    tokens: &HashMap<String, Vec<TokenPick>>,
+    tokens: &HashMap<(Chain, String), Vec<TokenPick>>,
 ) -> Result<(), AppError> {
     let start = Instant::now();
 
@@ -137,6 +167,7 @@ async fn process_address_batch(
             .get_token_value_data(TokenValueDataRequest {
                 address: address_batch.to_vec(),
                 time_period: Some(TimePeriod::Day),
+                chain: chain.clone(),
             })
             .await?;
 
@@ -160,9 +191,19 @@ async fn process_address_batch(
             AppError::InternalServerError()
         })?;
 
+    let observed_at = Utc::now().fixed_offset();
+    let mut snapshots = Vec::new();
     let processing_futures = latest_token_info.into_iter().map(|(address, metadata)| {
-        let picks = tokens.get(&address).unwrap();
+        let picks = tokens.get(&(chain.clone(), address)).unwrap();
         let supply = picks.iter().find_map(|pick| pick.supply_at_call).unwrap();
+        snapshots.extend(picks.iter().map(|pick| TokenPickSnapshot {
+            token_pick_id: pick.id,
+            price: metadata.price,
+            market_cap: metadata.price * supply,
+            liquidity: Some(metadata.liquidity),
+            volume_24h: Some(metadata.volume),
+            observed_at,
+        }));
         process_token_picks(
             app_state,
             picks,
@@ -173,6 +214,16 @@ async fn process_address_batch(
         )
     });
 
+    let processing_futures: Vec<_> = processing_futures.collect();
+
+    if let Err(e) = app_state
+        .token_service
+        .save_pick_snapshots(&snapshots)
+        .await
+    {
+        warn!("Failed to save token pick snapshots: {}", e);
+    }
+
     let results: Vec<_> = futures::future::join_all(processing_futures)
         .await
         .into_par_iter()
@@ -210,18 +261,24 @@ async fn process_token_picks(
 
     let results = futures::future::join_all(pick_futures).await;
 
-    let cache_futures = results.iter().filter_map(|r| {
-        let pick = r.as_ref().unwrap().0.clone();
-        if TokenPick::is_qualified(
+    let mut qualified_picks = Vec::new();
+    for (pick, _) in results.iter().flatten() {
+        let policy = app_state
+            .qualification_policy_service
+            .get_policy(pick.qualification_policy_id)
+            .await;
+        if policy.is_qualified(
             pick.market_cap_at_call,
             pick.token.liquidity,
             pick.token.volume_24h,
         ) {
-            Some(update_pick_stats(app_state, pick))
-        } else {
-            None
+            qualified_picks.push(pick.clone());
         }
-    });
+    }
+
+    let cache_futures = qualified_picks
+        .into_iter()
+        .map(|pick| update_pick_stats(app_state, pick));
 
     futures::future::join_all(cache_futures).await;
 
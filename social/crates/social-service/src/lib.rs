@@ -7,13 +7,16 @@ use axum::{
 };
 use container::ServiceContainer;
 use events::{
-    handlers::{token_pick::TokenPickHandler, EventHandler},
+    handlers::{achievement::AchievementHandler, token_pick::TokenPickHandler, EventHandler},
     listeners::PostgresEventListener,
     types::Channel,
 };
 use services::{
-    group_service::GroupService, profile_service::ProfileService, s3_service::S3Service,
-    token_service::TokenService, user_service::UserService,
+    backfill_service::BackfillService, export_service::ExportService, feed_service::FeedService,
+    group_service::GroupService, import_service::ImportService, profile_service::ProfileService,
+    qualification_policy_service::QualificationPolicyService,
+    rank_history_service::RankHistoryService, s3_service::S3Service, season_service::SeasonService,
+    simulation_service::SimulationService, token_service::TokenService, user_service::UserService,
 };
 use settings::Settings;
 use sqlx::postgres::{PgPool, PgPoolOptions};
@@ -38,6 +41,14 @@ pub struct AppState {
     pub token_service: Arc<TokenService>,
     pub group_service: Arc<GroupService>,
     pub s3_service: Arc<S3Service>,
+    pub backfill_service: Arc<BackfillService>,
+    pub qualification_policy_service: Arc<QualificationPolicyService>,
+    pub import_service: Arc<ImportService>,
+    pub export_service: Arc<ExportService>,
+    pub rank_history_service: Arc<RankHistoryService>,
+    pub season_service: Arc<SeasonService>,
+    pub simulation_service: Arc<SimulationService>,
+    pub feed_service: Arc<FeedService>,
 }
 
 pub async fn setup_database(database_url: &str) -> Result<Arc<PgPool>, sqlx::Error> {
@@ -77,6 +88,14 @@ pub async fn setup_router(
             token_service: Arc::clone(&container.token_service),
             group_service: Arc::clone(&container.group_service),
             s3_service: Arc::clone(&container.s3_service),
+            backfill_service: Arc::clone(&container.backfill_service),
+            qualification_policy_service: Arc::clone(&container.qualification_policy_service),
+            import_service: Arc::clone(&container.import_service),
+            export_service: Arc::clone(&container.export_service),
+            rank_history_service: Arc::clone(&container.rank_history_service),
+            season_service: Arc::clone(&container.season_service),
+            simulation_service: Arc::clone(&container.simulation_service),
+            feed_service: Arc::clone(&container.feed_service),
         })),
         Arc::new(container),
     ))
@@ -119,7 +138,11 @@ pub async fn start_event_listeners(
     let mut handlers = HashMap::new();
     handlers.insert(
         Channel::TokenPick,
-        Box::new(TokenPickHandler::new(services)) as Box<dyn EventHandler>,
+        Box::new(TokenPickHandler::new(services.clone())) as Box<dyn EventHandler>,
+    );
+    handlers.insert(
+        Channel::Achievement,
+        Box::new(AchievementHandler::new(services)) as Box<dyn EventHandler>,
     );
 
     let mut listener = PostgresEventListener::new(settings, handlers).await?;
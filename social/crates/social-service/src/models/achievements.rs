@@ -0,0 +1,266 @@
+use chrono::{DateTime, FixedOffset, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::utils::time::TimePeriod;
+
+use super::token_picks::{hit_streaks, PickMilestone, TokenPick};
+
+/// What a user has to do to earn an [Achievement]
+#[derive(Debug, Clone, Copy)]
+pub enum AchievementCriteria {
+    /// A pick reached the milestone
+    Milestone(PickMilestone),
+    /// Consecutive picks, in call order, hit 2x
+    HitStreak(usize),
+    /// A pick called under `max_market_cap_at_call` whose token later reached
+    /// `min_highest_market_cap`
+    EarlyCall {
+        max_market_cap_at_call: i64,
+        min_highest_market_cap: i64,
+    },
+    /// Ranked within `max_rank` on a daily snapshot of the global leaderboard of the time period
+    LeaderboardRank {
+        time_period: TimePeriod,
+        max_rank: i32,
+    },
+}
+
+/// An achievement of the catalogue, earned once per user
+#[derive(Debug, Clone, Copy)]
+pub struct Achievement {
+    /// Stable identifier, stored with the achievements users earned
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub criteria: AchievementCriteria,
+}
+
+/// Every achievement users can earn
+pub const ACHIEVEMENTS: [Achievement; 6] = [
+    Achievement {
+        id: "first_hit",
+        name: "First Hit",
+        description: "Called a token that reached 2x",
+        criteria: AchievementCriteria::Milestone(PickMilestone::X2),
+    },
+    Achievement {
+        id: "first_10x",
+        name: "First 10x",
+        description: "Called a token that reached 10x",
+        criteria: AchievementCriteria::Milestone(PickMilestone::X10),
+    },
+    Achievement {
+        id: "first_50x",
+        name: "First 50x",
+        description: "Called a token that reached 50x",
+        criteria: AchievementCriteria::Milestone(PickMilestone::X50),
+    },
+    Achievement {
+        id: "hit_streak_5",
+        name: "On Fire",
+        description: "5 hits in a row",
+        criteria: AchievementCriteria::HitStreak(5),
+    },
+    Achievement {
+        id: "early_call_50m",
+        name: "Early Bird",
+        description: "Called a token under 1M market cap that reached 50M",
+        criteria: AchievementCriteria::EarlyCall {
+            max_market_cap_at_call: 1_000_000,
+            min_highest_market_cap: 50_000_000,
+        },
+    },
+    Achievement {
+        id: "top_10_weekly",
+        name: "Weekly Top 10",
+        description: "Ranked in the top 10 of the weekly leaderboard",
+        criteria: AchievementCriteria::LeaderboardRank {
+            time_period: TimePeriod::Week,
+            max_rank: 10,
+        },
+    },
+];
+
+impl Achievement {
+    pub fn find(id: &str) -> Option<&'static Achievement> {
+        ACHIEVEMENTS.iter().find(|a| a.id == id)
+    }
+
+    /// When and with which pick the picks of a user earned the achievement first, among picks
+    /// sorted by call date. Leaderboard achievements are not earned by picks.
+    pub fn earned_by_picks(
+        &self,
+        picks: &[TokenPick],
+        now: DateTime<FixedOffset>,
+    ) -> Option<(DateTime<FixedOffset>, i64)> {
+        match self.criteria {
+            AchievementCriteria::Milestone(milestone) => picks
+                .iter()
+                .filter_map(|p| Some((*p.milestone_hits.get(&milestone)?, p.id)))
+                .min(),
+            AchievementCriteria::HitStreak(length) => hit_streaks(picks, now)
+                .into_iter()
+                .filter(|streak| streak.len() >= length)
+                .filter_map(|streak| {
+                    let completed_by = &streak[..length];
+                    let earned_at = completed_by.iter().filter_map(|p| p.hit_date).max()?;
+                    Some((earned_at, completed_by.last()?.id))
+                })
+                .min(),
+            AchievementCriteria::EarlyCall {
+                max_market_cap_at_call,
+                min_highest_market_cap,
+            } => picks
+                .iter()
+                .filter(|p| p.market_cap_at_call < Decimal::from(max_market_cap_at_call))
+                .filter(|p| {
+                    p.highest_market_cap.unwrap_or_default()
+                        >= Decimal::from(min_highest_market_cap)
+                })
+                .filter_map(|p| Some((p.highest_market_cap_date?, p.id)))
+                .min(),
+            AchievementCriteria::LeaderboardRank { .. } => None,
+        }
+    }
+}
+
+/// An achievement a user earned, as stored
+#[derive(Debug, Clone, FromRow)]
+pub struct UserAchievement {
+    pub user_id: Uuid,
+    pub achievement_id: String,
+    pub earned_at: DateTime<Utc>,
+    /// The pick that earned the achievement, empty for leaderboard achievements
+    pub token_pick_id: Option<i64>,
+    /// Whether the achievement is announced on Telegram when saved
+    pub announce: bool,
+}
+
+/// An achievement a user earned, with its catalogue entry
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EarnedAchievement {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub earned_at: DateTime<Utc>,
+    /// The pick that earned the achievement, empty for leaderboard achievements
+    pub token_pick_id: Option<i64>,
+}
+
+impl EarnedAchievement {
+    /// Achievements no longer in the catalogue are left out
+    pub fn from_user_achievement(achievement: UserAchievement) -> Option<Self> {
+        let definition = Achievement::find(&achievement.achievement_id)?;
+        Some(Self {
+            id: definition.id.to_string(),
+            name: definition.name.to_string(),
+            description: definition.description.to_string(),
+            earned_at: achievement.earned_at,
+            token_pick_id: achievement.token_pick_id,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::token_picks::tests::date;
+
+    /// A pick called on day `id` that reached `multiplier` a day later
+    fn pick(id: i64, multiplier: i64) -> TokenPick {
+        let mut pick = TokenPick {
+            id,
+            call_date: date(id),
+            ..Default::default()
+        };
+        pick.record_milestones(Decimal::from(multiplier), date(id + 1));
+        pick
+    }
+
+    fn achievement(id: &str) -> &'static Achievement {
+        Achievement::find(id).unwrap()
+    }
+
+    #[test]
+    fn test_earned_by_picks_milestone() {
+        let picks = vec![pick(1, 1), pick(2, 12), pick(3, 2)];
+
+        assert_eq!(
+            achievement("first_hit").earned_by_picks(&picks, date(30)),
+            Some((date(3), 2))
+        );
+        assert_eq!(
+            achievement("first_10x").earned_by_picks(&picks, date(30)),
+            Some((date(3), 2))
+        );
+        assert_eq!(
+            achievement("first_50x").earned_by_picks(&picks, date(30)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_earned_by_picks_hit_streak() {
+        let mut picks: Vec<TokenPick> = (1..=6).map(|id| pick(id, 2)).collect();
+        let streak = achievement("hit_streak_5");
+
+        // Completed by the fifth hit, when it hit 2x
+        assert_eq!(streak.earned_by_picks(&picks, date(30)), Some((date(6), 5)));
+
+        picks[2] = pick(3, 1);
+        assert_eq!(streak.earned_by_picks(&picks, date(30)), None);
+    }
+
+    #[test]
+    fn test_earned_by_picks_hit_streak_waits_for_unsettled_picks() {
+        let picks = vec![
+            pick(1, 2),
+            pick(2, 1),
+            pick(3, 2),
+            pick(4, 2),
+            pick(5, 2),
+            pick(6, 2),
+        ];
+        let streak = achievement("hit_streak_5");
+
+        // Pick 2 can still hit 2x and complete a streak of 6
+        assert_eq!(streak.earned_by_picks(&picks, date(5)), None);
+        assert_eq!(streak.earned_by_picks(&picks, date(30)), None);
+    }
+
+    #[test]
+    fn test_earned_by_picks_early_call() {
+        let early_call = TokenPick {
+            id: 1,
+            market_cap_at_call: Decimal::from(500_000),
+            highest_market_cap: Some(Decimal::from(60_000_000)),
+            highest_market_cap_date: Some(date(4)),
+            ..Default::default()
+        };
+        let late_call = TokenPick {
+            id: 2,
+            market_cap_at_call: Decimal::from(2_000_000),
+            highest_market_cap: Some(Decimal::from(90_000_000)),
+            highest_market_cap_date: Some(date(2)),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            achievement("early_call_50m").earned_by_picks(&[early_call, late_call], date(30)),
+            Some((date(4), 1))
+        );
+    }
+
+    #[test]
+    fn test_earned_by_picks_leaderboard_rank() {
+        assert_eq!(
+            achievement("top_10_weekly").earned_by_picks(&[pick(1, 12)], date(30)),
+            None
+        );
+    }
+}
@@ -0,0 +1,70 @@
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::token_picks::{PickMilestone, TokenPickResponse};
+
+/// Something that happened around the viewer, tagged by its `type`
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FeedEvent {
+    /// A user the viewer follows made a pick
+    FollowedPick { pick: TokenPickResponse },
+    /// A pick of a user the viewer follows reached a milestone
+    MilestoneHit {
+        pick: TokenPickResponse,
+        milestone: PickMilestone,
+    },
+    /// A user the viewer does not follow picked in one of the viewer's groups
+    GroupPick { pick: TokenPickResponse },
+}
+
+/// An event of the feed with when it happened
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedItem {
+    /// Identifies the event across every event type
+    pub id: String,
+    pub occurred_at: DateTime<FixedOffset>,
+    #[serde(flatten)]
+    pub event: FeedEvent,
+}
+
+impl FeedItem {
+    /// The pick the event is about
+    pub fn pick(&self) -> &TokenPickResponse {
+        match &self.event {
+            FeedEvent::FollowedPick { pick }
+            | FeedEvent::MilestoneHit { pick, .. }
+            | FeedEvent::GroupPick { pick } => pick,
+        }
+    }
+
+    pub fn followed_pick(pick: TokenPickResponse) -> Self {
+        FeedItem {
+            id: format!("followed_pick:{}", pick.id),
+            occurred_at: pick.call_date,
+            event: FeedEvent::FollowedPick { pick },
+        }
+    }
+
+    pub fn milestone_hit(
+        pick: TokenPickResponse,
+        milestone: PickMilestone,
+        hit_at: DateTime<FixedOffset>,
+    ) -> Self {
+        FeedItem {
+            id: format!("milestone_hit:{}:{}", pick.id, milestone.to_string()),
+            occurred_at: hit_at,
+            event: FeedEvent::MilestoneHit { pick, milestone },
+        }
+    }
+
+    pub fn group_pick(pick: TokenPickResponse) -> Self {
+        FeedItem {
+            id: format!("group_pick:{}", pick.id),
+            occurred_at: pick.call_date,
+            event: FeedEvent::GroupPick { pick },
+        }
+    }
+}
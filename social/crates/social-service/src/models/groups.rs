@@ -4,6 +4,24 @@ use sqlx::{types::Json, FromRow};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
+use crate::utils::time::TimePeriod;
+
+/// Picks a user can make per period in a group without its own limits
+pub const DEFAULT_MAX_PICKS_PER_USER: u32 = 5;
+
+/// Picks a user can make per day across every group, unless overridden for the user
+pub const DEFAULT_MAX_DAILY_PICKS_PER_USER: u32 = 5;
+
+/// Override of the daily pick limit of a user across every group
+#[derive(Debug, Serialize, Deserialize, Clone, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UserPickLimit {
+    pub user_id: Uuid,
+    /// Picks the user can make per day across every group, unlimited if null
+    pub max_daily_picks: Option<i32>,
+    pub updated_at: DateTime<Utc>,
+}
+
 #[derive(Clone, Debug, FromRow)]
 pub struct Group {
     pub id: i64,
@@ -67,6 +85,33 @@ pub struct GroupUser {
 pub struct GroupSettings {
     pub privacy: GroupPrivacy,
     pub twitter_metadata: TwitterMetadata,
+    #[serde(default)]
+    pub pick_limits: PickLimits,
+}
+
+/// Rate limits for picks made in a group
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PickLimits {
+    /// Picks a user can make in the group per period, unlimited if null
+    pub max_picks_per_user: Option<u32>,
+    /// Picks the whole group can make per period, unlimited if null
+    pub max_picks_per_group: Option<u32>,
+    /// Rolling window the pick counts apply to
+    pub period: TimePeriod,
+    /// Minimum seconds between two picks of the same user in the group
+    pub user_cooldown_seconds: Option<u32>,
+}
+
+impl Default for PickLimits {
+    fn default() -> Self {
+        Self {
+            max_picks_per_user: Some(DEFAULT_MAX_PICKS_PER_USER),
+            max_picks_per_group: None,
+            period: TimePeriod::Day,
+            user_cooldown_seconds: None,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default, ToSchema)]
@@ -1,6 +1,13 @@
+pub mod achievements;
+pub mod feed;
 pub mod groups;
 pub mod picks;
+pub mod points;
 pub mod profiles;
+pub mod qualification_policies;
+pub mod rank_history;
+pub mod seasons;
+pub mod simulations;
 pub mod tiers;
 pub mod token_picks;
 pub mod tokens;
@@ -0,0 +1,268 @@
+use chrono::{DateTime, FixedOffset};
+use serde_json::json;
+use uuid::Uuid;
+
+use super::{
+    qualification_policies::QualificationPolicy,
+    token_picks::{hit_streaks, PickMilestone, StreakPick, TokenPick},
+};
+
+/// Consecutive hits, in call order, that earn a [PointsRule::HitStreak]
+pub const HIT_STREAK_LENGTH: usize = 3;
+
+/// What users earn or lose points for. Each rule is awarded at most once per pick.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PointsRule {
+    /// The pick met its qualification policy
+    QualifiedPick,
+    Hit2x,
+    Hit5x,
+    Hit10x,
+    /// The pick completed a run of [HIT_STREAK_LENGTH] consecutive hits
+    HitStreak,
+    /// The pick never hit 2x and its token fell under the bust thresholds
+    Bust,
+}
+
+impl PointsRule {
+    pub fn points(&self) -> i64 {
+        match self {
+            PointsRule::QualifiedPick => 10,
+            PointsRule::Hit2x => 50,
+            PointsRule::Hit5x => 150,
+            PointsRule::Hit10x => 400,
+            PointsRule::HitStreak => 100,
+            PointsRule::Bust => -25,
+        }
+    }
+
+    fn for_milestone(milestone: PickMilestone) -> Option<Self> {
+        match milestone {
+            PickMilestone::X2 => Some(PointsRule::Hit2x),
+            PickMilestone::X5 => Some(PointsRule::Hit5x),
+            PickMilestone::X10 => Some(PointsRule::Hit10x),
+            _ => None,
+        }
+    }
+}
+
+impl ToString for PointsRule {
+    fn to_string(&self) -> String {
+        match self {
+            PointsRule::QualifiedPick => "qualified_pick".to_string(),
+            PointsRule::Hit2x => "hit_2x".to_string(),
+            PointsRule::Hit5x => "hit_5x".to_string(),
+            PointsRule::Hit10x => "hit_10x".to_string(),
+            PointsRule::HitStreak => "hit_streak".to_string(),
+            PointsRule::Bust => "bust".to_string(),
+        }
+    }
+}
+
+/// Points a user earned or lost for a pick, recorded as a `point_transactions` row
+#[derive(Debug, Clone)]
+pub struct PointsAward {
+    pub user_id: Uuid,
+    pub rule: PointsRule,
+    pub token_pick_id: i64,
+    pub details: serde_json::Value,
+}
+
+impl PointsAward {
+    fn new(user_id: Uuid, rule: PointsRule, pick: &TokenPick, details: serde_json::Value) -> Self {
+        Self {
+            user_id,
+            rule,
+            token_pick_id: pick.id,
+            details,
+        }
+    }
+
+    /// Identifies the pick the award is for, so each rule is awarded once per pick
+    pub fn context(&self) -> String {
+        format!("token_pick:{}", self.token_pick_id)
+    }
+
+    pub fn points(&self) -> i64 {
+        self.rule.points()
+    }
+
+    /// Every award the picks of a user are eligible for. `picks` must be sorted by call date and
+    /// `policy_of` returns the qualification policy each pick was created under.
+    pub fn evaluate<'a>(
+        user_id: Uuid,
+        picks: &[TokenPick],
+        policy_of: impl Fn(i64) -> &'a QualificationPolicy,
+        now: DateTime<FixedOffset>,
+    ) -> Vec<PointsAward> {
+        let mut awards = Vec::new();
+
+        for pick in picks {
+            let policy = policy_of(pick.qualification_policy_id);
+            let token = &pick.token;
+            let market_cap = token.market_cap.unwrap_or_default();
+
+            if policy.is_qualified(market_cap, token.liquidity, token.volume_24h) {
+                awards.push(Self::new(
+                    user_id,
+                    PointsRule::QualifiedPick,
+                    pick,
+                    json!({ "marketCap": market_cap, "policyId": policy.id }),
+                ));
+            }
+
+            for (milestone, hit_at) in &pick.milestone_hits {
+                if let Some(rule) = PointsRule::for_milestone(*milestone) {
+                    awards.push(Self::new(
+                        user_id,
+                        rule,
+                        pick,
+                        json!({ "milestone": milestone.to_string(), "hitAt": hit_at }),
+                    ));
+                }
+            }
+
+            if pick.hit_date.is_none()
+                && pick.is_settled(now)
+                && policy.is_bust(market_cap, token.liquidity, token.volume_24h)
+            {
+                awards.push(Self::new(
+                    user_id,
+                    PointsRule::Bust,
+                    pick,
+                    json!({
+                        "marketCap": market_cap,
+                        "liquidity": token.liquidity,
+                        "volume24h": token.volume_24h,
+                    }),
+                ));
+            }
+        }
+
+        for streak in hit_streaks(picks, now) {
+            for (length, pick) in (1..).zip(streak) {
+                if length % HIT_STREAK_LENGTH == 0 {
+                    awards.push(Self::new(
+                        user_id,
+                        PointsRule::HitStreak,
+                        pick,
+                        json!({ "streak": length }),
+                    ));
+                }
+            }
+        }
+
+        awards
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal::Decimal;
+
+    use super::*;
+    use crate::models::token_picks::tests::date;
+
+    /// A pick called on day `id` on a token with `market_cap`, `liquidity` and `volume_24h`
+    fn pick(id: i64, market_cap: i64, liquidity: i64, volume_24h: i64) -> TokenPick {
+        let mut pick = TokenPick {
+            id,
+            call_date: date(id),
+            market_cap_at_call: Decimal::from(100_000),
+            ..Default::default()
+        };
+        pick.token.market_cap = Some(Decimal::from(market_cap));
+        pick.token.liquidity = Some(Decimal::from(liquidity));
+        pick.token.volume_24h = Some(Decimal::from(volume_24h));
+        pick
+    }
+
+    fn hit(id: i64, multiplier: i64) -> TokenPick {
+        let mut pick = pick(id, 500_000, 50_000, 100_000);
+        pick.record_milestones(Decimal::from(multiplier), date(id));
+        pick
+    }
+
+    fn rules(awards: &[PointsAward], pick_id: i64) -> Vec<PointsRule> {
+        awards
+            .iter()
+            .filter(|a| a.token_pick_id == pick_id)
+            .map(|a| a.rule)
+            .collect()
+    }
+
+    fn evaluate(picks: &[TokenPick], now: DateTime<FixedOffset>) -> Vec<PointsAward> {
+        let policy = QualificationPolicy::default();
+        PointsAward::evaluate(Uuid::nil(), picks, |_| &policy, now)
+    }
+
+    #[test]
+    fn test_evaluate_qualified_pick_and_milestones() {
+        let awards = evaluate(&[hit(1, 6)], date(2));
+
+        assert_eq!(
+            rules(&awards, 1),
+            vec![
+                PointsRule::QualifiedPick,
+                PointsRule::Hit2x,
+                PointsRule::Hit5x
+            ]
+        );
+        assert_eq!(awards.iter().map(|a| a.points()).sum::<i64>(), 210);
+        assert_eq!(awards[0].context(), "token_pick:1");
+    }
+
+    #[test]
+    fn test_evaluate_unqualified_pick() {
+        // Liquidity under 4% of the volume
+        let awards = evaluate(&[pick(1, 500_000, 1_000, 100_000)], date(2));
+        assert!(rules(&awards, 1).is_empty());
+    }
+
+    #[test]
+    fn test_evaluate_bust_once_settled() {
+        let bust = pick(1, 10_000, 1_000, 1_000);
+
+        assert!(rules(&evaluate(&[bust.clone()], date(2)), 1).is_empty());
+        assert_eq!(
+            rules(&evaluate(&[bust], date(30)), 1),
+            vec![PointsRule::Bust]
+        );
+    }
+
+    #[test]
+    fn test_evaluate_hit_streaks() {
+        let picks: Vec<TokenPick> = (1..=7)
+            .map(|id| {
+                if id == 4 {
+                    pick(id, 500_000, 50_000, 100_000)
+                } else {
+                    hit(id, 2)
+                }
+            })
+            .collect();
+        let awards = evaluate(&picks, date(30));
+
+        let streaks: Vec<i64> = awards
+            .iter()
+            .filter(|a| a.rule == PointsRule::HitStreak)
+            .map(|a| a.token_pick_id)
+            .collect();
+        // Picks 1 to 3 and 5 to 7 each complete a streak, the miss of pick 4 resets it
+        assert_eq!(streaks, vec![3, 7]);
+    }
+
+    #[test]
+    fn test_evaluate_streak_waits_for_unsettled_picks() {
+        let picks = vec![
+            hit(1, 2),
+            hit(2, 2),
+            pick(3, 500_000, 50_000, 100_000),
+            hit(4, 2),
+        ];
+
+        // Pick 3 can still hit, so pick 4 does not extend or break the streak yet
+        let awards = evaluate(&picks, date(5));
+        assert!(awards.iter().all(|a| a.rule != PointsRule::HitStreak));
+    }
+}
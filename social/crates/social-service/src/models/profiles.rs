@@ -1,15 +1,19 @@
-use crate::models::tiers::TiersType;
-use rust_decimal::Decimal;
+use crate::{models::tiers::TiersType, utils::math::wilson_interval};
+use chrono::{DateTime, FixedOffset, Utc};
+use rust_decimal::{prelude::FromPrimitive, Decimal};
 use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
-use super::user_stats::{BestPick, UserStats};
+use super::achievements::EarnedAchievement;
+use super::tokens::Token;
+use super::user_stats::{BestPick, MilestoneStats, UserStats};
 
 pub struct Profile;
 /// A summary of a user's picks (calls).
 #[derive(Deserialize, Serialize, ToSchema, Default, Debug)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct ProfilePickSummary {
     /// The sum total of all of a user's picks expressed as an integer.
     pub total_picks: i32,
@@ -19,10 +23,26 @@ pub struct ProfilePickSummary {
     pub pick_returns: Decimal,
     /// Average return of a user's picks expressed as a multiple.
     pub average_pick_return: Decimal,
+    /// Average maximum drawdown of a user's picks expressed as a percentage.
+    pub average_drawdown: Decimal,
     /// Total realized PnL for that user's Bullpen wallet expressed as a dollar amount.
     pub realized_profit: Decimal,
     /// [BestPick] performing pick.
     pub best_pick: BestPick,
+    /// Hits and time to hit for every tracked milestone.
+    pub milestones: Vec<MilestoneStats>,
+    /// Number of a user's picks that were the first call of their token across every group.
+    pub original_calls: i32,
+    /// Percentage of a user's picks that were the first call of their token.
+    pub original_call_rate: Decimal,
+    /// Consecutive picks that hit 2x, up to the user's latest settled pick.
+    pub current_streak: i32,
+    /// Longest run of consecutive picks that hit 2x.
+    pub longest_streak: i32,
+    /// Percentage of recent weeks in which at least one of a user's picks hit 2x.
+    pub consistency_score: Decimal,
+    /// 95% confidence interval of the hit rate, narrowing as the user makes more picks.
+    pub hit_rate_interval: ConfidenceInterval,
 }
 
 impl From<UserStats> for ProfilePickSummary {
@@ -32,12 +52,55 @@ impl From<UserStats> for ProfilePickSummary {
             hit_rate: stats.hit_rate,
             pick_returns: stats.pick_returns,
             average_pick_return: stats.average_pick_return,
+            average_drawdown: stats.average_drawdown,
             realized_profit: stats.realized_profit,
             best_pick: stats.best_pick,
+            milestones: stats.milestones,
+            original_calls: stats.original_calls,
+            original_call_rate: stats.original_call_rate,
+            current_streak: stats.current_streak,
+            longest_streak: stats.longest_streak,
+            consistency_score: stats.consistency_score,
+            hit_rate_interval: ConfidenceInterval::wilson(stats.hits, stats.total_picks),
+        }
+    }
+}
+
+/// A 95% confidence interval of a percentage
+#[derive(Deserialize, Serialize, ToSchema, Default, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfidenceInterval {
+    pub lower: Decimal,
+    pub upper: Decimal,
+}
+
+impl ConfidenceInterval {
+    /// Wilson score interval of `successes` out of `samples`, as percentages
+    pub fn wilson(successes: i32, samples: i32) -> Self {
+        let (lower, upper) = wilson_interval(successes.max(0) as u64, samples.max(0) as u64);
+        let percentage = |v: f64| Decimal::from_f64(v * 100.0).unwrap_or_default().round_dp(2);
+
+        ConfidenceInterval {
+            lower: percentage(lower),
+            upper: percentage(upper),
         }
     }
 }
 
+/// The leaderboard [LeaderboardMinPicks] fall back to for sorts without their own
+pub const DEFAULT_LEADERBOARD: &str = "default";
+
+/// Fewest picks a user needs to be ranked on a leaderboard by a ranking mode other than raw
+#[derive(Debug, Serialize, Deserialize, Clone, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LeaderboardMinPicks {
+    /// The leaderboard sort, or `default` for sorts without their own minimum
+    pub leaderboard: String,
+    pub time_period: String,
+    pub min_picks: i32,
+    pub updated_at: DateTime<Utc>,
+}
+
 /// A summary of a user's tier.
 #[derive(Deserialize, Serialize, ToSchema, Default, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -50,6 +113,34 @@ pub struct ProfileTier {
     current_tier: TiersType,
     /// Next tier a user can reach.
     next_tier: TiersType,
+    /// Percentage of the way from the current tier to the next one, 100 at the last tier.
+    progress: Decimal,
+}
+
+impl ProfileTier {
+    pub fn from_points(total_points: i64) -> Self {
+        let points = total_points.max(0) as isize;
+        let current_tier = TiersType::get_current_tier(points);
+        let next_tier = TiersType::get_next_tier(points);
+        let tier_points = (next_tier as i64 - current_tier as i64).max(0);
+        let progress = if tier_points == 0 {
+            Decimal::ONE_HUNDRED
+        } else {
+            Decimal::from((points as i64 - current_tier as i64) * 100) / Decimal::from(tier_points)
+        };
+
+        Self {
+            total_points,
+            points_to_next_tier: (next_tier as i64 - points as i64).max(0),
+            current_tier,
+            next_tier,
+            progress: progress.round_dp(2),
+        }
+    }
+
+    pub fn current_tier(&self) -> TiersType {
+        self.current_tier
+    }
 }
 
 /// A user's profile details response.
@@ -70,6 +161,55 @@ pub struct ProfileDetailsResponse {
     pub bio: Option<String>,
     /// User's tier
     pub tier: ProfileTier,
+    /// Achievements the user earned, oldest first
+    pub achievements: Vec<EarnedAchievement>,
     /// Is the user following the authenticated user
     pub is_following: Option<bool>,
+    /// Rank of the user on the leaderboard, starting at 1
+    pub rank: Option<i32>,
+    /// Positions the user moved on the leaderboard since yesterday, negative when they dropped.
+    /// Empty when the user was not ranked then or the leaderboard is not snapshotted.
+    pub rank_change: Option<i32>,
+}
+
+/// A user in a [ProfileComparison], with the stats of their picks in the compared scope
+#[derive(Serialize, ToSchema, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ComparedProfile {
+    pub id: Uuid,
+    pub username: String,
+    pub avatar_url: Option<String>,
+    pub stats: UserStats,
+}
+
+/// The first call of a token by one of the compared users
+#[derive(Serialize, ToSchema, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CommonTokenCall {
+    pub user_id: Uuid,
+    pub username: String,
+    pub pick_id: i64,
+    pub call_date: DateTime<FixedOffset>,
+    pub market_cap_at_call: Decimal,
+    /// Highest multiplier the pick reached since the call
+    pub highest_multiplier: f32,
+}
+
+/// A token called by at least two of the compared users
+#[derive(Serialize, ToSchema, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CommonToken {
+    pub token: Token,
+    /// The first call of each user who called the token, earliest first
+    pub calls: Vec<CommonTokenCall>,
+}
+
+/// Stats of several users side by side over the same time period and group
+#[derive(Serialize, ToSchema, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileComparison {
+    /// The compared users, in the requested order
+    pub profiles: Vec<ComparedProfile>,
+    /// Tokens called by at least two of the users, most recently first called first
+    pub common_tokens: Vec<CommonToken>,
 }
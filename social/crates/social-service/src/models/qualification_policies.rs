@@ -0,0 +1,120 @@
+use chrono::{DateTime, Duration, FixedOffset, Utc};
+use rust_decimal::{prelude::FromPrimitive, Decimal};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+
+/// Id of the seeded policy, which every pick falls back to
+pub const DEFAULT_QUALIFICATION_POLICY_ID: i64 = 1;
+
+pub const DEFAULT_CANCELLATION_WINDOW_SECONDS: i32 = 60;
+
+/// Thresholds a token must meet for its pick to count on leaderboards and stats
+#[derive(Clone, Debug, FromRow, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct QualificationPolicy {
+    pub id: i64,
+    /// Environment the policy applies to, any environment if empty
+    pub environment: Option<String>,
+    /// Group the policy applies to, any group if empty
+    pub group_id: Option<i64>,
+    /// Version of the policy within its environment and group
+    pub version: i32,
+    /// Minimum FDV for a pick to qualify
+    pub min_market_cap: Decimal,
+    /// Minimum liquidity, as a ratio of the 24h volume, below the large cap threshold
+    pub min_liquidity_volume_ratio: Decimal,
+    /// FDV from which `large_cap_min_liquidity` applies instead of the volume ratio
+    pub large_cap_threshold: Decimal,
+    /// Minimum liquidity above the large cap threshold
+    pub large_cap_min_liquidity: Decimal,
+    /// Tokens below this 24h volume count as a bust
+    pub bust_min_volume: Decimal,
+    /// Tokens below this liquidity count as a bust
+    pub bust_min_liquidity: Decimal,
+    /// Tokens below this market cap count as a bust
+    pub bust_min_market_cap: Decimal,
+    /// How long after the call a pick can be cancelled
+    pub cancellation_window_seconds: i32,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Default for QualificationPolicy {
+    fn default() -> Self {
+        Self {
+            id: DEFAULT_QUALIFICATION_POLICY_ID,
+            environment: None,
+            group_id: None,
+            version: 1,
+            min_market_cap: Decimal::from(40_000),
+            min_liquidity_volume_ratio: Decimal::from_f32(0.04).unwrap(),
+            large_cap_threshold: Decimal::from(1_000_000),
+            large_cap_min_liquidity: Decimal::from(40_000),
+            bust_min_volume: Decimal::from(20_000),
+            bust_min_liquidity: Decimal::from(10_000),
+            bust_min_market_cap: Decimal::from(30_000),
+            cancellation_window_seconds: DEFAULT_CANCELLATION_WINDOW_SECONDS,
+            is_active: true,
+            created_at: DateTime::default(),
+        }
+    }
+}
+
+impl QualificationPolicy {
+    /// Whether a pick called at `call_date` can still be cancelled at `now`
+    pub fn can_cancel(&self, call_date: DateTime<FixedOffset>, now: DateTime<Utc>) -> bool {
+        now - Duration::seconds(self.cancellation_window_seconds.into()) <= call_date
+    }
+
+    pub fn is_qualified(
+        &self,
+        fdv: Decimal,
+        liquidity: Option<Decimal>,
+        volume_24h: Option<Decimal>,
+    ) -> bool {
+        if fdv <= self.min_market_cap {
+            return false;
+        };
+
+        match (liquidity, volume_24h) {
+            (Some(liq), Some(vol)) => {
+                if fdv < self.large_cap_threshold {
+                    liq >= vol * self.min_liquidity_volume_ratio
+                } else {
+                    liq >= self.large_cap_min_liquidity
+                }
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether a token fell under any of the bust thresholds. Missing metrics count as zero.
+    pub fn is_bust(
+        &self,
+        market_cap: Decimal,
+        liquidity: Option<Decimal>,
+        volume_24h: Option<Decimal>,
+    ) -> bool {
+        market_cap < self.bust_min_market_cap
+            || liquidity.unwrap_or_default() < self.bust_min_liquidity
+            || volume_24h.unwrap_or_default() < self.bust_min_volume
+    }
+
+    /// Whether the policy applies to `environment` and `group_id`, and how specific the match
+    /// is. A group match outranks an environment match.
+    pub fn specificity(&self, environment: &str, group_id: i64) -> Option<u8> {
+        let environment = match self.environment.as_deref() {
+            Some(env) if env.eq_ignore_ascii_case(environment) => 1,
+            Some(_) => return None,
+            None => 0,
+        };
+        let group = match self.group_id {
+            Some(id) if id == group_id => 2,
+            Some(_) => return None,
+            None => 0,
+        };
+
+        Some(group + environment)
+    }
+}
@@ -0,0 +1,65 @@
+use chrono::{DateTime, Duration, DurationRound, FixedOffset, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// How often leaderboard ranks are snapshotted
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, ToSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SnapshotResolution {
+    Hourly,
+    #[default]
+    Daily,
+}
+
+impl SnapshotResolution {
+    pub const ALL: [SnapshotResolution; 2] =
+        [SnapshotResolution::Hourly, SnapshotResolution::Daily];
+
+    pub fn interval(&self) -> Duration {
+        match self {
+            SnapshotResolution::Hourly => Duration::hours(1),
+            SnapshotResolution::Daily => Duration::days(1),
+        }
+    }
+
+    /// The date of the snapshot covering `now`, the start of its hour or day, so instances taking
+    /// it at the same time write the same snapshot
+    pub fn snapshot_at(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        now.duration_trunc(self.interval()).unwrap_or(now)
+    }
+}
+
+impl ToString for SnapshotResolution {
+    fn to_string(&self) -> String {
+        match self {
+            SnapshotResolution::Hourly => "hourly".to_string(),
+            SnapshotResolution::Daily => "daily".to_string(),
+        }
+    }
+}
+
+/// Rank of a user on a leaderboard snapshot
+#[derive(Debug, Clone, FromRow)]
+pub struct RankSnapshot {
+    pub user_id: Uuid,
+    pub rank: i32,
+    /// Value the leaderboard is sorted by
+    pub sort_value: Decimal,
+}
+
+/// A user's rank on a leaderboard snapshot
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RankHistoryPoint {
+    /// Date the snapshot was taken
+    pub snapshot_at: DateTime<FixedOffset>,
+    /// Rank of the user, starting at 1
+    pub rank: i32,
+    /// Number of users ranked on the snapshot
+    pub total_ranked: i32,
+    /// Value the leaderboard is sorted by
+    pub sort_value: Decimal,
+}
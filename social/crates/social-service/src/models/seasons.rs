@@ -0,0 +1,59 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+
+use super::profiles::{ProfileDetailsResponse, ProfilePickSummary};
+
+/// A named competition counting the picks made between its start and end dates
+#[derive(Clone, Debug, FromRow, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Season {
+    pub id: i64,
+    pub name: String,
+    /// Group the season is run in, every group if empty
+    pub group_id: Option<i64>,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    /// When the final standings were frozen, once the last picks of the season settled 7 days
+    /// after it ended. Empty until then.
+    pub closed_at: Option<DateTime<Utc>>,
+    /// Where the final standings are archived, empty while the season is running
+    pub standings_uri: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A group on a season leaderboard, with the stats of every pick made in it during the season
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SeasonGroupStanding {
+    /// Rank of the group on the leaderboard, starting at 1
+    pub rank: Option<i32>,
+    pub group_id: i64,
+    pub name: String,
+    pub logo_uri: Option<String>,
+    /// Number of users who picked in the group during the season
+    pub callers: i32,
+    pub pick_summary: ProfilePickSummary,
+}
+
+/// Users and groups of a season with their stats, as frozen when the season closes
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SeasonStandings {
+    pub users: Vec<ProfileDetailsResponse>,
+    pub groups: Vec<SeasonGroupStanding>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SeasonLeaderboardResponse {
+    pub season: Season,
+    /// Whether the standings are final, otherwise they are computed from the current picks
+    pub is_final: bool,
+    /// The page of users and every group
+    #[serde(flatten)]
+    pub standings: SeasonStandings,
+    /// Cursor of the next page of users, empty on the last page
+    pub next_cursor: Option<String>,
+}
@@ -0,0 +1,95 @@
+use chrono::{DateTime, FixedOffset};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Where simulated trades get their prices from
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, ToSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SimulationPriceSource {
+    /// Price snapshots recorded by the token picks job
+    #[default]
+    Snapshots,
+    /// Hourly Birdeye candles
+    Ohlcv,
+}
+
+impl ToString for SimulationPriceSource {
+    fn to_string(&self) -> String {
+        match self {
+            SimulationPriceSource::Snapshots => "snapshots".to_string(),
+            SimulationPriceSource::Ohlcv => "ohlcv".to_string(),
+        }
+    }
+}
+
+/// Why a simulated position was sold
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TradeExit {
+    TakeProfit,
+    StopLoss,
+    /// Held for the longest allowed holding period
+    HoldLimit,
+    /// Still held, valued at the latest price
+    Open,
+}
+
+/// A buy following a pick and its matching sell
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedTrade {
+    pub token_pick_id: i64,
+    pub token_symbol: String,
+    pub token_address: String,
+    pub call_date: DateTime<FixedOffset>,
+    pub entry_date: DateTime<FixedOffset>,
+    pub entry_price: Decimal,
+    /// Date of the sell, or of the latest price for open positions
+    pub exit_date: DateTime<FixedOffset>,
+    pub exit_price: Decimal,
+    pub exit: TradeExit,
+    /// Profit of the trade in USD, negative for a loss
+    pub pnl: Decimal,
+}
+
+/// Cumulative profit of the simulation after a trade is sold
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EquityPoint {
+    pub date: DateTime<FixedOffset>,
+    /// Profit in USD of every trade sold so far
+    pub pnl: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulationSummary {
+    pub trades: i32,
+    pub wins: i32,
+    pub losses: i32,
+    /// Percentage of trades sold for a profit
+    pub win_rate: Decimal,
+    /// Picks left out as no price was found after their entry date
+    pub skipped_picks: i32,
+    /// USD put in every trade
+    pub total_invested: Decimal,
+    /// USD value of every trade once sold, or at the latest price for open positions
+    pub final_value: Decimal,
+    pub total_pnl: Decimal,
+    /// Total profit as a percentage of the total invested
+    pub return_percent: Decimal,
+    /// Largest decline of the equity curve from a previous high, in USD
+    pub max_drawdown: Decimal,
+}
+
+/// Outcome of copy-trading a set of picks
+#[derive(Serialize, Deserialize, Debug, Default, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulationResponse {
+    pub summary: SimulationSummary,
+    /// Cumulative profit after each trade is sold, oldest first
+    pub equity_curve: Vec<EquityPoint>,
+    /// Trades in the order they were sold
+    pub trades: Vec<SimulatedTrade>,
+}
@@ -1,7 +1,5 @@
-use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
-use uuid::Uuid;
 
 pub const TIER_IRON: u64 = 0;
 pub const TIER_BRONZE: u64 = 500;
@@ -11,7 +9,9 @@ pub const TIER_PLATINUM: u64 = 15_000;
 pub const TIER_EMERALD: u64 = 40_000;
 pub const TIER_DIAMOND: u64 = 100_000;
 
-#[derive(Serialize, Deserialize, Debug, Clone, Copy, ToSchema, Default)]
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, ToSchema, Default, PartialEq, Eq, PartialOrd, Ord,
+)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum TiersType {
     #[default]
@@ -50,8 +50,16 @@ impl TiersType {
     }
 }
 
-pub struct UserMedal {
-    user_id: Uuid,
-    tier: TiersType,
-    earned_at: DateTime<Utc>,
+impl ToString for TiersType {
+    fn to_string(&self) -> String {
+        match self {
+            TiersType::Iron => "iron".to_string(),
+            TiersType::Bronze => "bronze".to_string(),
+            TiersType::Silver => "silver".to_string(),
+            TiersType::Gold => "gold".to_string(),
+            TiersType::Platinum => "platinum".to_string(),
+            TiersType::Emerald => "emerald".to_string(),
+            TiersType::Diamond => "diamond".to_string(),
+        }
+    }
 }
@@ -5,20 +5,116 @@ use crate::{
 
 use super::{
     groups::CreateOrUpdateGroup,
-    tokens::Token,
+    tokens::{Chain, Token},
     users::{User, UserResponse},
 };
-use chrono::{DateTime, FixedOffset, Utc};
+use chrono::{DateTime, Duration, FixedOffset, Utc};
 use rust_decimal::{
-    prelude::{FromPrimitive, ToPrimitive, Zero},
+    prelude::{ToPrimitive, Zero},
     Decimal,
 };
 use serde::{Deserialize, Serialize};
 use sqlx::{types::Json, FromRow};
+use std::collections::BTreeMap;
 use utoipa::{IntoParams, ToSchema};
 
 pub const HIT_MULTIPLIER: u8 = 2;
 
+/// How long a pick has to hit 2x before it counts as a miss
+pub const PICK_SETTLE_DAYS: i64 = 7;
+
+/// Multipliers tracked for every pick, each recorded the first time it is crossed
+#[derive(
+    Serialize,
+    Deserialize,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    ToSchema,
+    Default,
+)]
+pub enum PickMilestone {
+    #[default]
+    #[serde(rename = "2x")]
+    X2,
+    #[serde(rename = "3x")]
+    X3,
+    #[serde(rename = "5x")]
+    X5,
+    #[serde(rename = "10x")]
+    X10,
+    #[serde(rename = "50x")]
+    X50,
+}
+
+impl PickMilestone {
+    pub const ALL: [PickMilestone; 5] = [
+        PickMilestone::X2,
+        PickMilestone::X3,
+        PickMilestone::X5,
+        PickMilestone::X10,
+        PickMilestone::X50,
+    ];
+
+    pub fn multiplier(&self) -> Decimal {
+        match self {
+            PickMilestone::X2 => Decimal::from(2),
+            PickMilestone::X3 => Decimal::from(3),
+            PickMilestone::X5 => Decimal::from(5),
+            PickMilestone::X10 => Decimal::from(10),
+            PickMilestone::X50 => Decimal::from(50),
+        }
+    }
+}
+
+impl ToString for PickMilestone {
+    fn to_string(&self) -> String {
+        match self {
+            PickMilestone::X2 => "2x".to_string(),
+            PickMilestone::X3 => "3x".to_string(),
+            PickMilestone::X5 => "5x".to_string(),
+            PickMilestone::X10 => "10x".to_string(),
+            PickMilestone::X50 => "50x".to_string(),
+        }
+    }
+}
+
+/// Whether a pick was the first call of its token across every group, or a follow-up
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CallType {
+    Original,
+    FollowUp,
+}
+
+impl CallType {
+    /// SQL condition matching picks of this call type. Picks whose first call is not set yet are
+    /// original calls, as in [TokenPickResponse::is_original_call].
+    pub fn filter_expression(&self) -> &'static str {
+        match self {
+            CallType::Original => "COALESCE(tp.first_call_pick_id, tp.id) = tp.id",
+            CallType::FollowUp => "COALESCE(tp.first_call_pick_id, tp.id) <> tp.id",
+        }
+    }
+}
+
+impl ToString for CallType {
+    fn to_string(&self) -> String {
+        match self {
+            CallType::Original => "original".to_string(),
+            CallType::FollowUp => "follow_up".to_string(),
+        }
+    }
+}
+
+/// First time each [PickMilestone] was crossed
+pub type MilestoneHits = BTreeMap<PickMilestone, DateTime<FixedOffset>>;
+
 #[derive(Clone, Debug, FromRow, Serialize, Deserialize, Default)]
 pub struct TokenPick {
     pub id: i64,
@@ -34,30 +130,70 @@ pub struct TokenPick {
     pub supply_at_call: Option<Decimal>,
     pub call_date: DateTime<FixedOffset>,
     pub highest_market_cap: Option<Decimal>,
+    pub highest_market_cap_date: Option<DateTime<FixedOffset>>,
     pub highest_multiplier: Option<Decimal>,
     pub hit_date: Option<DateTime<FixedOffset>>,
+    #[sqlx(json)]
+    #[serde(default)]
+    pub milestone_hits: MilestoneHits,
+    pub lowest_market_cap: Option<Decimal>,
+    /// Largest decline from a previous high, as a percentage
+    pub max_drawdown: Option<Decimal>,
+    /// Qualification policy the pick was created under
+    pub qualification_policy_id: i64,
+    /// The earliest pick of the token across every group
+    pub first_call_pick_id: Option<i64>,
+    pub first_call_date: Option<DateTime<FixedOffset>>,
+    /// Whether the pick meets its qualification policy, empty when the query did not check it
+    #[sqlx(default)]
+    #[serde(default)]
+    pub is_qualified: Option<bool>,
 }
 
 impl TokenPick {
-    pub fn is_qualified(
-        fdv: Decimal,
-        liquidity: Option<Decimal>,
-        volume_24h: Option<Decimal>,
-    ) -> bool {
-        if fdv <= Decimal::from(40_000) {
+    /// Records every milestone crossed by `multiplier` that was not reached before, keeping
+    /// `hit_date` as the first time the pick hit 2x. Returns true if a new milestone was reached.
+    pub fn record_milestones(&mut self, multiplier: Decimal, at: DateTime<FixedOffset>) -> bool {
+        let mut reached = false;
+        for milestone in PickMilestone::ALL {
+            if multiplier >= milestone.multiplier() && !self.milestone_hits.contains_key(&milestone)
+            {
+                self.milestone_hits.insert(milestone, at);
+                reached = true;
+            }
+        }
+
+        if self.hit_date.is_none() {
+            self.hit_date = self.milestone_hits.get(&PickMilestone::X2).copied();
+        }
+
+        reached
+    }
+
+    /// Records `market_cap` as a low after the call, updating the lowest market cap and the
+    /// maximum drawdown from `peak`, the highest market cap seen before it. Returns true if
+    /// either changed.
+    pub fn record_low(&mut self, market_cap: Decimal, peak: Decimal) -> bool {
+        if market_cap <= Decimal::ZERO {
             return false;
-        };
+        }
 
-        match (liquidity, volume_24h) {
-            (Some(liq), Some(vol)) => {
-                if fdv < Decimal::from(1_000_000) {
-                    liq >= vol * Decimal::from_f32(0.04).unwrap()
-                } else {
-                    liq >= Decimal::from(40_000)
-                }
+        let mut updated = false;
+        if market_cap < self.lowest_market_cap.unwrap_or(self.market_cap_at_call) {
+            self.lowest_market_cap = Some(market_cap);
+            updated = true;
+        }
+
+        let peak = peak.max(self.market_cap_at_call);
+        if !peak.is_zero() {
+            let drawdown = ((peak - market_cap) / peak * Decimal::from(100)).round_dp(2);
+            if drawdown > self.max_drawdown.unwrap_or_default() {
+                self.max_drawdown = Some(drawdown);
+                updated = true;
             }
-            _ => false,
         }
+
+        updated
     }
 
     pub fn check_for_hit(&mut self, current_market_cap: Decimal) -> bool {
@@ -92,6 +228,93 @@ impl TokenPick {
     // }
 }
 
+/// A pick as counted in hit streaks, implemented by both the stored and the response picks so
+/// stats, points and achievements count streaks the same way
+pub trait StreakPick {
+    fn call_date(&self) -> DateTime<FixedOffset>;
+    fn hit_date(&self) -> Option<DateTime<FixedOffset>>;
+    /// Empty when unknown, in which case the pick is counted
+    fn is_qualified(&self) -> Option<bool>;
+
+    /// Whether the pick hit 2x or had [PICK_SETTLE_DAYS] to, so it can no longer turn into a hit
+    /// within the settling window
+    fn is_settled(&self, now: DateTime<FixedOffset>) -> bool {
+        self.hit_date().is_some() || self.call_date() + Duration::days(PICK_SETTLE_DAYS) <= now
+    }
+}
+
+impl StreakPick for TokenPick {
+    fn call_date(&self) -> DateTime<FixedOffset> {
+        self.call_date
+    }
+
+    fn hit_date(&self) -> Option<DateTime<FixedOffset>> {
+        self.hit_date
+    }
+
+    fn is_qualified(&self) -> Option<bool> {
+        self.is_qualified
+    }
+}
+
+impl<P: StreakPick> StreakPick for &P {
+    fn call_date(&self) -> DateTime<FixedOffset> {
+        (*self).call_date()
+    }
+
+    fn hit_date(&self) -> Option<DateTime<FixedOffset>> {
+        (*self).hit_date()
+    }
+
+    fn is_qualified(&self) -> Option<bool> {
+        (*self).is_qualified()
+    }
+}
+
+/// Qualified picks, among picks sorted by call date, up to the first one that has not settled,
+/// as it may still hit
+fn settled_streak_picks<P: StreakPick>(
+    picks: &[P],
+    now: DateTime<FixedOffset>,
+) -> impl DoubleEndedIterator<Item = &P> {
+    let qualified: Vec<&P> = picks
+        .iter()
+        .filter(|p| p.is_qualified() != Some(false))
+        .collect();
+    let settled = qualified
+        .iter()
+        .position(|p| !p.is_settled(now))
+        .unwrap_or(qualified.len());
+
+    qualified.into_iter().take(settled)
+}
+
+/// Runs of consecutive qualified picks that hit 2x, among picks sorted by call date
+pub fn hit_streaks<P: StreakPick>(picks: &[P], now: DateTime<FixedOffset>) -> Vec<Vec<&P>> {
+    let mut streaks = Vec::new();
+    let mut streak = Vec::new();
+    for pick in settled_streak_picks(picks, now) {
+        if pick.hit_date().is_some() {
+            streak.push(pick);
+        } else if !streak.is_empty() {
+            streaks.push(std::mem::take(&mut streak));
+        }
+    }
+    if !streak.is_empty() {
+        streaks.push(streak);
+    }
+
+    streaks
+}
+
+/// Length of the streak the latest settled qualified pick is part of, zero if it missed
+pub fn current_hit_streak<P: StreakPick>(picks: &[P], now: DateTime<FixedOffset>) -> usize {
+    settled_streak_picks(picks, now)
+        .rev()
+        .take_while(|p| p.hit_date().is_some())
+        .count()
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, Default, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct TokenPickResponse {
@@ -113,12 +336,77 @@ pub struct TokenPickResponse {
     pub current_multiplier: f32,
     /// The highest market cap the pick has reached
     pub highest_mc_post_call: Option<Decimal>,
+    /// Date the pick reached its highest market cap
+    pub highest_mc_post_call_date: Option<DateTime<FixedOffset>>,
     /// The multiplier of the pick
     pub highest_mult_post_call: f32,
     /// Date the pick hit
     pub hit_date: Option<DateTime<FixedOffset>>,
     /// Price at the time the pick was made
     pub price_at_call: Decimal,
+    /// Milestones the pick has reached
+    pub milestones: Vec<PickMilestoneHit>,
+    /// The lowest market cap the pick has reached
+    pub lowest_mc_post_call: Option<Decimal>,
+    /// Largest decline from a previous high after the call, as a percentage
+    pub max_drawdown: Option<Decimal>,
+    /// The qualification policy the pick is judged by
+    pub qualification_policy_id: i64,
+    /// Whether the pick was the first call of the token across every group
+    pub is_original_call: bool,
+    /// ID of the first pick of the token across every group
+    pub first_call_pick_id: Option<i64>,
+    /// Date the token was first called across every group
+    pub first_call_date: Option<DateTime<FixedOffset>>,
+    /// Minutes between the first call of the token and this pick
+    pub minutes_after_first_call: Option<i64>,
+    /// Whether the pick meets its qualification policy, empty when it was not checked
+    pub is_qualified: Option<bool>,
+}
+
+impl StreakPick for TokenPickResponse {
+    fn call_date(&self) -> DateTime<FixedOffset> {
+        self.call_date
+    }
+
+    fn hit_date(&self) -> Option<DateTime<FixedOffset>> {
+        self.hit_date
+    }
+
+    fn is_qualified(&self) -> Option<bool> {
+        self.is_qualified
+    }
+}
+
+/// A milestone reached by a pick
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PickMilestoneHit {
+    /// The milestone reached
+    pub milestone: PickMilestone,
+    /// Date the milestone was first reached
+    pub hit_at: DateTime<FixedOffset>,
+    /// Seconds between the call and the milestone being reached
+    pub seconds_to_hit: i64,
+}
+
+impl PickMilestoneHit {
+    pub fn from_hits(hits: &MilestoneHits, call_date: DateTime<FixedOffset>) -> Vec<Self> {
+        hits.iter()
+            .map(|(milestone, hit_at)| PickMilestoneHit {
+                milestone: *milestone,
+                hit_at: *hit_at,
+                seconds_to_hit: (*hit_at - call_date).num_seconds().max(0),
+            })
+            .collect()
+    }
+
+    pub fn to_hits(milestones: &[Self]) -> MilestoneHits {
+        milestones
+            .iter()
+            .map(|hit| (hit.milestone, hit.hit_at))
+            .collect()
+    }
 }
 
 impl From<TokenPick> for TokenPickResponse {
@@ -141,6 +429,17 @@ impl From<TokenPick> for TokenPickResponse {
                 .unwrap_or_default();
 
         Self {
+            milestones: PickMilestoneHit::from_hits(&pick.milestone_hits, pick.call_date),
+            lowest_mc_post_call: pick.lowest_market_cap.map(|mc| mc.round_dp(2)),
+            max_drawdown: pick.max_drawdown,
+            qualification_policy_id: pick.qualification_policy_id,
+            is_original_call: pick.first_call_pick_id.unwrap_or(pick.id) == pick.id,
+            first_call_pick_id: pick.first_call_pick_id,
+            first_call_date: pick.first_call_date,
+            minutes_after_first_call: pick
+                .first_call_date
+                .map(|first| (pick.call_date - first).num_minutes().max(0)),
+            is_qualified: pick.is_qualified,
             token: pick.token,
             highest_mult_post_call,
             call_date: pick.call_date,
@@ -148,6 +447,7 @@ impl From<TokenPick> for TokenPickResponse {
             id: pick.id,
             user: pick.user.map(|u| u.0.into()),
             highest_mc_post_call: pick.highest_market_cap.map(|mc| mc.round_dp(2)),
+            highest_mc_post_call_date: pick.highest_market_cap_date,
             hit_date: pick.hit_date,
             market_cap_at_call: pick.market_cap_at_call.round_dp(2),
             current_market_cap,
@@ -184,6 +484,7 @@ impl From<TokenPickRow> for TokenPickResponse {
             market_cap_at_call: row.market_cap_at_call.round_dp(2),
             token: Token {
                 address: row.token_address,
+                chain: row.token_chain,
                 ..Default::default()
             },
             ..Default::default()
@@ -197,11 +498,198 @@ pub struct ProfilePicksAndStatsQuery {
     pub multiplier: Option<u8>,
     pub picked_after: Option<TimePeriod>,
     pub group_ids: Option<Vec<i64>>,
+    pub chain: Option<Chain>,
+    pub call_type: Option<CallType>,
 }
 
 #[derive(Debug, sqlx::FromRow)]
 pub struct TokenPicksGroup {
     pub address: String,
+    pub chain: String,
     #[sqlx(json)]
     pub picks: Vec<TokenPick>,
 }
+
+/// A market cap observation of a pick, recorded by the token picks job
+#[derive(Clone, Debug, FromRow, Serialize, Deserialize, Default, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenPickSnapshot {
+    /// The pick ID
+    pub token_pick_id: i64,
+    /// The token price when observed
+    pub price: Decimal,
+    /// The token market cap when observed
+    pub market_cap: Decimal,
+    /// The token liquidity when observed
+    pub liquidity: Option<Decimal>,
+    /// The token volume 24h USD when observed
+    pub volume_24h: Option<Decimal>,
+    /// Date of the observation
+    pub observed_at: DateTime<FixedOffset>,
+}
+
+/// A change recorded in the audit trail of a pick
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PickAuditAction {
+    Deleted,
+    Restored,
+}
+
+impl ToString for PickAuditAction {
+    fn to_string(&self) -> String {
+        match self {
+            PickAuditAction::Deleted => "deleted".to_string(),
+            PickAuditAction::Restored => "restored".to_string(),
+        }
+    }
+}
+
+/// A soft deleted pick, with who deleted it and why
+#[derive(Clone, Debug, FromRow)]
+pub struct DeletedTokenPick {
+    #[sqlx(flatten)]
+    pub pick: TokenPick,
+    pub deleted_at: DateTime<FixedOffset>,
+    pub deleted_by: String,
+    pub deletion_reason: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DeletedTokenPickResponse {
+    pub pick: TokenPickResponse,
+    /// Date the pick was deleted
+    pub deleted_at: DateTime<FixedOffset>,
+    /// Who deleted the pick, `telegram:<user id>` when cancelled by its caller
+    pub deleted_by: String,
+    /// Why the pick was deleted
+    pub deletion_reason: Option<String>,
+}
+
+impl From<DeletedTokenPick> for DeletedTokenPickResponse {
+    fn from(deleted: DeletedTokenPick) -> Self {
+        Self {
+            pick: deleted.pick.into(),
+            deleted_at: deleted.deleted_at,
+            deleted_by: deleted.deleted_by,
+            deletion_reason: deleted.deletion_reason,
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// A fixed date `days` days after the reference date of the model tests
+    pub(crate) fn date(days: i64) -> DateTime<FixedOffset> {
+        (DateTime::<Utc>::from_timestamp(1_700_000_000, 0).unwrap() + Duration::days(days))
+            .fixed_offset()
+    }
+
+    #[test]
+    fn test_record_milestones_dates_each_milestone_once() {
+        let mut pick = TokenPick::default();
+
+        assert!(pick.record_milestones(Decimal::from(3), date(1)));
+        assert!(!pick.record_milestones(Decimal::from(4), date(2)));
+        assert!(pick.record_milestones(Decimal::from(12), date(3)));
+
+        assert_eq!(pick.milestone_hits.get(&PickMilestone::X2), Some(&date(1)));
+        assert_eq!(pick.milestone_hits.get(&PickMilestone::X3), Some(&date(1)));
+        assert_eq!(pick.milestone_hits.get(&PickMilestone::X5), Some(&date(3)));
+        assert_eq!(pick.milestone_hits.get(&PickMilestone::X10), Some(&date(3)));
+        assert_eq!(pick.milestone_hits.get(&PickMilestone::X50), None);
+        assert_eq!(pick.hit_date, Some(date(1)));
+    }
+
+    #[test]
+    fn test_record_milestones_below_2x() {
+        let mut pick = TokenPick::default();
+
+        assert!(!pick.record_milestones(Decimal::new(199, 2), date(1)));
+        assert!(pick.milestone_hits.is_empty());
+        assert_eq!(pick.hit_date, None);
+    }
+
+    #[test]
+    fn test_record_milestones_keeps_hit_date() {
+        let mut pick = TokenPick {
+            hit_date: Some(date(0)),
+            ..Default::default()
+        };
+
+        pick.record_milestones(Decimal::from(2), date(1));
+        assert_eq!(pick.hit_date, Some(date(0)));
+    }
+
+    /// A pick called on day `id`, hit a day later if `hit`
+    fn streak_pick(id: i64, hit: bool, is_qualified: Option<bool>) -> TokenPick {
+        TokenPick {
+            id,
+            call_date: date(id),
+            hit_date: hit.then(|| date(id + 1)),
+            is_qualified,
+            ..Default::default()
+        }
+    }
+
+    fn streak_ids(picks: &[TokenPick], now: DateTime<FixedOffset>) -> Vec<Vec<i64>> {
+        hit_streaks(picks, now)
+            .iter()
+            .map(|streak| streak.iter().map(|p| p.id).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_hit_streaks() {
+        let picks: Vec<TokenPick> = [true, true, false, true, true, true]
+            .into_iter()
+            .zip(1..)
+            .map(|(hit, id)| streak_pick(id, hit, Some(true)))
+            .collect();
+
+        assert_eq!(
+            streak_ids(&picks, date(30)),
+            vec![vec![1, 2], vec![4, 5, 6]]
+        );
+        assert_eq!(current_hit_streak(&picks, date(30)), 3);
+    }
+
+    #[test]
+    fn test_hit_streaks_skip_unqualified_picks() {
+        let picks = vec![
+            streak_pick(1, true, Some(true)),
+            streak_pick(2, false, Some(false)),
+            streak_pick(3, true, None),
+        ];
+
+        assert_eq!(streak_ids(&picks, date(30)), vec![vec![1, 3]]);
+        assert_eq!(current_hit_streak(&picks, date(30)), 2);
+    }
+
+    #[test]
+    fn test_hit_streaks_stop_at_unsettled_pick() {
+        let picks = vec![
+            streak_pick(1, true, Some(true)),
+            streak_pick(2, false, Some(true)),
+            streak_pick(3, true, Some(true)),
+        ];
+
+        // Pick 2 may still hit, so pick 3 is not counted yet
+        assert_eq!(streak_ids(&picks, date(5)), vec![vec![1]]);
+        assert_eq!(current_hit_streak(&picks, date(5)), 1);
+        assert_eq!(current_hit_streak(&picks, date(30)), 1);
+    }
+
+    #[test]
+    fn test_current_hit_streak_after_miss() {
+        let picks = vec![
+            streak_pick(1, true, Some(true)),
+            streak_pick(2, false, Some(true)),
+        ];
+
+        assert_eq!(current_hit_streak(&picks, date(30)), 0);
+    }
+}
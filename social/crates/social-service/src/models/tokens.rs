@@ -6,16 +6,19 @@ use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::{
-    apis::api_models::response::TokenValueDataResponse,
+    apis::api_models::response::{TokenPickResponseWithMetadata, TokenValueDataResponse},
     external_services::rust_monorepo::get_latest_w_metadata::LatestTokenMetadataResponse,
+    utils::errors::app_error::AppError,
 };
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, Default, ToSchema)]
+#[serde(rename_all = "lowercase")]
 #[repr(u8)]
 pub enum Chain {
     Ethereum,
     #[default]
     Solana,
+    Base,
 }
 
 impl ToString for Chain {
@@ -23,19 +26,60 @@ impl ToString for Chain {
         match self {
             Chain::Ethereum => "ethereum".to_string(),
             Chain::Solana => "solana".to_string(),
+            Chain::Base => "base".to_string(),
         }
     }
 }
 
-impl From<String> for Chain {
-    fn from(chain_type: String) -> Self {
+impl TryFrom<String> for Chain {
+    type Error = AppError;
+
+    fn try_from(chain_type: String) -> Result<Self, Self::Error> {
         match chain_type.to_lowercase().as_str() {
-            "ethereum" => Chain::Ethereum,
-            _ => Chain::Solana,
+            "ethereum" | "eth" => Ok(Chain::Ethereum),
+            "solana" | "sol" => Ok(Chain::Solana),
+            "base" => Ok(Chain::Base),
+            _ => Err(AppError::BadRequest(format!(
+                "Unsupported chain: {}",
+                chain_type
+            ))),
         }
     }
 }
 
+impl Chain {
+    /// Detects the chain of a token from its address format.
+    ///
+    /// EVM addresses (`0x` followed by 40 hex characters) are shared by every EVM chain, so
+    /// `hint` is used to pick between them and defaults to [Chain::Ethereum]. Returns `None`
+    /// when the address is not valid for any supported chain or the hint does not match it.
+    pub fn detect(address: &str, hint: Option<&str>) -> Option<Self> {
+        let address = address.trim();
+        let is_evm = address.len() == 42
+            && address.starts_with("0x")
+            && address[2..].chars().all(|c| c.is_ascii_hexdigit());
+        let is_solana = (32..=44).contains(&address.len())
+            && address
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() && !matches!(c, '0' | 'O' | 'I' | 'l'));
+
+        let hint = match hint.map(|h| Chain::try_from(h.to_string())).transpose() {
+            Ok(hint) => hint,
+            Err(_) => return None,
+        };
+        match (is_evm, is_solana, hint) {
+            (true, _, None) => Some(Chain::Ethereum),
+            (true, _, Some(chain)) if chain.is_evm() => Some(chain),
+            (_, true, None | Some(Chain::Solana)) => Some(Chain::Solana),
+            _ => None,
+        }
+    }
+
+    pub fn is_evm(&self) -> bool {
+        matches!(self, Chain::Ethereum | Chain::Base)
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, FromRow, Serialize, Deserialize, Default, ToSchema)]
 pub struct Token {
     /// The token address
@@ -86,7 +130,7 @@ impl From<LatestTokenMetadataResponse> for Token {
             token_info.address,
             token_info.token_info.name,
             token_info.token_info.symbol,
-            Chain::Solana.to_string(),
+            token_info.chain.to_string(),
             Some(token_info.market_cap),
             token_info.metadata.v_24h_usd,
             token_info.metadata.liquidity,
@@ -119,4 +163,50 @@ pub struct TokenPickRequest {
     #[serde(with = "chrono::serde::ts_seconds")]
     pub timestamp: DateTime<Utc>,
     pub address: String,
+    /// The token chain. Required to tell EVM chains apart, detected from the address otherwise.
+    pub chain: Option<String>,
+    /// Identifies the request across retries of the same user in the same chat. Derived from the
+    /// chat, message and token address when missing.
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
+}
+
+impl TokenPickRequest {
+    /// The key replays of this request are recognised by. Client keys are scoped to the chat and
+    /// user, so reusing a key can't replay the pick of another user. A message can call several
+    /// tokens, so the derived key includes the token address.
+    pub fn request_key(&self) -> String {
+        match &self.idempotency_key {
+            Some(key) => format!(
+                "{}:{}:key:{}",
+                self.telegram_chat_id, self.telegram_user_id, key
+            ),
+            None => format!(
+                "{}:{}:{}",
+                self.telegram_chat_id,
+                self.telegram_message_id,
+                self.address.trim()
+            ),
+        }
+    }
+
+    /// Identifies the pick the request makes, so a key replayed with another payload is rejected
+    pub fn payload_fingerprint(&self) -> String {
+        format!(
+            "{}:{}:{}:{}:{}:{}",
+            self.telegram_chat_id,
+            self.telegram_user_id,
+            self.telegram_message_id,
+            self.address.trim(),
+            self.chain.as_deref().unwrap_or_default().to_lowercase(),
+            self.timestamp.timestamp()
+        )
+    }
+}
+
+/// The response of a pick creation request, kept to replay it to the retries of the request
+#[derive(Serialize, Deserialize)]
+pub struct PickRequestReplay {
+    pub fingerprint: String,
+    pub response: TokenPickResponseWithMetadata,
 }
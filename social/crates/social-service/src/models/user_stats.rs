@@ -1,6 +1,19 @@
-use rust_decimal::Decimal;
+use std::collections::HashSet;
+
+use chrono::{DateTime, FixedOffset, Utc};
+use rust_decimal::{prelude::FromPrimitive, Decimal};
 use serde::{Deserialize, Serialize};
+use sqlx::{types::Json, FromRow};
 use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::token_picks::{current_hit_streak, hit_streaks, PickMilestone, TokenPickResponse};
+
+/// Group id of the [UserStatsRecord] counting the picks of every group
+pub const ALL_GROUPS: i64 = 0;
+
+/// Number of trailing weeks the consistency score is computed over
+pub const CONSISTENCY_WEEKS: i64 = 12;
 
 #[derive(Debug, Serialize, Deserialize, Default, ToSchema)]
 #[serde(rename_all = "camelCase")]
@@ -15,6 +28,8 @@ pub struct UserStats {
     pub pick_returns: Decimal,
     /// Average return of the user's picks.
     pub average_pick_return: Decimal,
+    /// Average maximum drawdown of the user's picks, as a percentage.
+    pub average_drawdown: Decimal,
     /// Total realized profit of the user's picks.
     pub realized_profit: Decimal,
     /// Total volume traded.
@@ -27,6 +42,274 @@ pub struct UserStats {
     pub best_pick: BestPick,
     /// Total busts.
     pub total_busts: i64,
+    /// [MilestoneStats] for every tracked milestone.
+    pub milestones: Vec<MilestoneStats>,
+    /// Number of picks that were the first call of their token across every group.
+    pub original_calls: i32,
+    /// Percentage of the user's picks that were the first call of their token.
+    pub original_call_rate: Decimal,
+    /// Consecutive qualified picks that hit 2x, up to the latest settled pick.
+    pub current_streak: i32,
+    /// Longest run of consecutive qualified picks that hit 2x.
+    pub longest_streak: i32,
+    /// Percentage of the last [CONSISTENCY_WEEKS] weeks, or of the weeks since the first pick if
+    /// fewer, with at least one pick that hit 2x.
+    pub consistency_score: Decimal,
+}
+
+impl UserStats {
+    /// Stats of the user's picks, counting only the first pick of each token in `picks` except
+    /// for the [HitStreakStats]. Stats kept outside of the picks (realized profit, volume traded
+    /// and busts) are left empty.
+    pub fn from_picks(picks: &[TokenPickResponse]) -> Self {
+        let mut seen_tokens = HashSet::new();
+        let first_picks: Vec<&TokenPickResponse> = picks
+            .iter()
+            .filter(|pick| seen_tokens.insert((&pick.token.chain, &pick.token.address)))
+            .collect();
+
+        let mut total_returns = Decimal::ZERO;
+        let mut total_drawdown = Decimal::ZERO;
+        let mut best_pick = None::<BestPick>;
+        let mut hits_2x = 0;
+        let mut hit_returns = Decimal::ZERO;
+        for pick in &first_picks {
+            if pick.highest_mult_post_call >= 2.0 {
+                hits_2x += 1;
+                hit_returns += Decimal::from_f32(pick.highest_mult_post_call).unwrap_or_default();
+            }
+
+            let current_return = calculate_return(
+                &pick.market_cap_at_call,
+                &pick.highest_mc_post_call.unwrap_or_default(),
+            );
+            total_returns += current_return;
+            total_drawdown += pick.max_drawdown.unwrap_or_default();
+
+            let new_best = BestPick {
+                token_symbol: pick.token.symbol.clone(),
+                token_address: pick.token.address.clone(),
+                multiplier: current_return,
+                logo_uri: pick.token.logo_uri.clone(),
+            };
+
+            best_pick = match best_pick {
+                Some(b) if current_return > b.multiplier => Some(new_best),
+                None => Some(new_best),
+                b => b,
+            };
+        }
+
+        let total_picks = first_picks.len() as i32;
+        let original_calls = first_picks.iter().filter(|p| p.is_original_call).count() as i32;
+        let original_call_rate = if total_picks > 0 {
+            Decimal::from(original_calls * 100) / Decimal::from(total_picks)
+        } else {
+            Decimal::ZERO
+        };
+        let total_hits = first_picks.iter().filter(|p| p.hit_date.is_some()).count() as i32;
+        let streaks = HitStreakStats::from_picks(picks, Utc::now().into());
+
+        let hit_rate = if total_picks > 0 && hits_2x > 0 {
+            Decimal::from(hits_2x * 100) / Decimal::from(total_picks)
+        } else {
+            Decimal::ZERO
+        };
+
+        let average_pick_return = if total_picks > 0 && !total_returns.is_zero() {
+            total_returns / Decimal::from(total_picks)
+        } else {
+            Decimal::ZERO
+        };
+        let average_drawdown = if total_picks > 0 {
+            total_drawdown / Decimal::from(total_picks)
+        } else {
+            Decimal::ZERO
+        };
+        let average_hit_return = if total_hits > 0 {
+            hit_returns / Decimal::from(total_hits)
+        } else {
+            Decimal::ZERO
+        };
+
+        UserStats {
+            total_picks,
+            hit_rate: hit_rate.round_dp(2),
+            pick_returns: total_returns.round_dp(2),
+            average_pick_return: average_pick_return.round_dp(2),
+            average_drawdown: average_drawdown.round_dp(2),
+            hits: total_hits,
+            misses: total_picks - total_hits,
+            best_pick: best_pick.unwrap_or_default(),
+            average_hit_return,
+            milestones: MilestoneStats::from_picks(&first_picks),
+            original_calls,
+            original_call_rate: original_call_rate.round_dp(2),
+            current_streak: streaks.current_streak,
+            longest_streak: streaks.longest_streak,
+            consistency_score: streaks.consistency_score.round_dp(2),
+            ..Default::default()
+        }
+    }
+}
+
+/// Hit streaks and weekly consistency of a user's picks
+#[derive(Debug, Default, PartialEq)]
+pub struct HitStreakStats {
+    pub current_streak: i32,
+    pub longest_streak: i32,
+    pub consistency_score: Decimal,
+}
+
+impl HitStreakStats {
+    /// Streaks are counted over every qualified pick in call date order, like points and
+    /// achievements, and stop before the first pick that has not settled, as it may still hit.
+    pub fn from_picks(picks: &[TokenPickResponse], now: DateTime<FixedOffset>) -> Self {
+        let mut picks: Vec<&TokenPickResponse> = picks.iter().collect();
+        picks.sort_by_key(|p| (p.call_date, p.id));
+
+        let current_streak = current_hit_streak(&picks, now) as i32;
+        let longest_streak = hit_streaks(&picks, now)
+            .iter()
+            .map(|streak| streak.len())
+            .max()
+            .unwrap_or_default() as i32;
+
+        // Weeks are counted back from `now`, week 0 being the last seven days
+        let picks: Vec<&TokenPickResponse> = picks
+            .into_iter()
+            .filter(|p| p.is_qualified != Some(false))
+            .collect();
+        let weeks_ago = |pick: &TokenPickResponse| (now - pick.call_date).num_weeks();
+        let weeks = picks.first().map_or(0, |first| {
+            (weeks_ago(first) + 1).clamp(0, CONSISTENCY_WEEKS)
+        });
+        let hit_weeks = picks
+            .iter()
+            .filter(|p| p.hit_date.is_some())
+            .map(|p| weeks_ago(p))
+            .filter(|week| (0..weeks).contains(week))
+            .collect::<HashSet<_>>()
+            .len();
+        let consistency_score = if weeks > 0 {
+            Decimal::from(hit_weeks * 100) / Decimal::from(weeks)
+        } else {
+            Decimal::ZERO
+        };
+
+        HitStreakStats {
+            current_streak,
+            longest_streak,
+            consistency_score,
+        }
+    }
+}
+
+/// [UserStats] of a user's picks in a time period and group scope, kept up to date as picks are
+/// processed so leaderboards do not recompute them
+#[derive(Debug, Clone, FromRow, Default)]
+pub struct UserStatsRecord {
+    pub user_id: Uuid,
+    /// The [TimePeriod](crate::utils::time::TimePeriod) the picks were made in
+    pub time_period: String,
+    /// The group the picks were made in, [ALL_GROUPS] for every group
+    pub group_id: i64,
+    pub total_picks: i32,
+    pub hits: i32,
+    pub hit_rate: Decimal,
+    pub average_hit_return: Decimal,
+    pub pick_returns: Decimal,
+    pub average_pick_return: Decimal,
+    pub average_drawdown: Decimal,
+    pub best_pick: Json<BestPick>,
+    pub milestones: Json<Vec<MilestoneStats>>,
+    pub original_calls: i32,
+    pub original_call_rate: Decimal,
+    pub current_streak: i32,
+    pub longest_streak: i32,
+    pub consistency_score: Decimal,
+    pub last_pick_date: Option<DateTime<FixedOffset>>,
+}
+
+impl UserStatsRecord {
+    pub fn new(
+        user_id: Uuid,
+        time_period: String,
+        group_id: i64,
+        picks: &[TokenPickResponse],
+    ) -> Self {
+        let stats = UserStats::from_picks(picks);
+        Self {
+            user_id,
+            time_period,
+            group_id,
+            total_picks: stats.total_picks,
+            hits: stats.hits,
+            hit_rate: stats.hit_rate,
+            average_hit_return: stats.average_hit_return,
+            pick_returns: stats.pick_returns,
+            average_pick_return: stats.average_pick_return,
+            average_drawdown: stats.average_drawdown,
+            best_pick: Json(stats.best_pick),
+            milestones: Json(stats.milestones),
+            original_calls: stats.original_calls,
+            original_call_rate: stats.original_call_rate,
+            current_streak: stats.current_streak,
+            longest_streak: stats.longest_streak,
+            consistency_score: stats.consistency_score,
+            last_pick_date: picks.iter().map(|p| p.call_date).max(),
+        }
+    }
+}
+
+impl From<UserStatsRecord> for UserStats {
+    fn from(record: UserStatsRecord) -> Self {
+        UserStats {
+            total_picks: record.total_picks,
+            hit_rate: record.hit_rate,
+            average_hit_return: record.average_hit_return,
+            pick_returns: record.pick_returns,
+            average_pick_return: record.average_pick_return,
+            average_drawdown: record.average_drawdown,
+            hits: record.hits,
+            misses: record.total_picks - record.hits,
+            best_pick: record.best_pick.0,
+            milestones: record.milestones.0,
+            original_calls: record.original_calls,
+            original_call_rate: record.original_call_rate,
+            current_streak: record.current_streak,
+            longest_streak: record.longest_streak,
+            consistency_score: record.consistency_score,
+            ..Default::default()
+        }
+    }
+}
+
+/// A [UserStatsRecord] with the profile of its user, as listed by leaderboards
+#[derive(Debug, FromRow)]
+pub struct UserStatsLeaderboardRow {
+    #[sqlx(flatten)]
+    pub stats: UserStatsRecord,
+    pub username: String,
+    pub image_uri: Option<String>,
+    pub bio: Option<String>,
+    /// Whether the viewer follows the user, empty without a viewer or for the viewer itself
+    pub is_following: Option<bool>,
+    /// Points the user accumulated, zero before their first award
+    pub total_points: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, ToSchema, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MilestoneStats {
+    /// The milestone.
+    pub milestone: PickMilestone,
+    /// Number of picks that reached the milestone.
+    pub hits: i32,
+    /// Percentage of the user's picks that reached the milestone.
+    pub hit_rate: Decimal,
+    /// Median seconds between the call and the milestone being reached.
+    pub median_seconds_to_hit: Option<i64>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Default, ToSchema, Clone)]
@@ -41,3 +324,54 @@ pub struct BestPick {
     /// Logo URI.
     pub logo_uri: Option<String>,
 }
+
+impl MilestoneStats {
+    /// Hits and median time to hit of every [PickMilestone] across `picks`
+    pub fn from_picks(picks: &[&TokenPickResponse]) -> Vec<Self> {
+        PickMilestone::ALL
+            .iter()
+            .map(|milestone| {
+                let mut seconds_to_hit: Vec<i64> = picks
+                    .iter()
+                    .filter_map(|pick| {
+                        pick.milestones
+                            .iter()
+                            .find(|hit| hit.milestone == *milestone)
+                            .map(|hit| hit.seconds_to_hit)
+                    })
+                    .collect();
+                seconds_to_hit.sort_unstable();
+
+                let hits = seconds_to_hit.len();
+                let median_seconds_to_hit = match hits {
+                    0 => None,
+                    n if n % 2 == 0 => {
+                        Some((seconds_to_hit[n / 2 - 1] + seconds_to_hit[n / 2]) / 2)
+                    }
+                    n => Some(seconds_to_hit[n / 2]),
+                };
+                let hit_rate = if picks.is_empty() {
+                    Decimal::ZERO
+                } else {
+                    Decimal::from(hits * 100) / Decimal::from(picks.len())
+                };
+
+                MilestoneStats {
+                    milestone: *milestone,
+                    hits: hits as i32,
+                    hit_rate: hit_rate.round_dp(2),
+                    median_seconds_to_hit,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Multiplier from the market cap at call to the highest one, zero when either is unknown
+fn calculate_return(market_cap_at_call: &Decimal, highest_market_cap: &Decimal) -> Decimal {
+    if market_cap_at_call.is_zero() || highest_market_cap.is_zero() {
+        Decimal::ZERO
+    } else {
+        highest_market_cap / market_cap_at_call
+    }
+}
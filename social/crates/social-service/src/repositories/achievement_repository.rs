@@ -0,0 +1,100 @@
+use std::sync::Arc;
+
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::models::achievements::UserAchievement;
+
+pub struct AchievementRepository {
+    db: Arc<PgPool>,
+}
+
+impl AchievementRepository {
+    pub fn new(db: Arc<PgPool>) -> Self {
+        AchievementRepository { db }
+    }
+
+    /// Users among `user_ids` whose achievements were evaluated before
+    pub async fn list_evaluated_user_ids(
+        &self,
+        user_ids: &[Uuid],
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar(
+            "SELECT user_id FROM social.achievement_evaluations WHERE user_id = ANY($1)",
+        )
+        .bind(user_ids)
+        .fetch_all(self.db.as_ref())
+        .await
+    }
+
+    /// Saves the achievements users had not earned yet and marks `user_ids` as evaluated.
+    /// Returns the newly earned achievements.
+    pub async fn save_user_achievements(
+        &self,
+        user_ids: &[Uuid],
+        achievements: &[UserAchievement],
+    ) -> Result<Vec<UserAchievement>, sqlx::Error> {
+        let mut tx = self.db.begin().await?;
+
+        let saved = sqlx::query_as::<_, UserAchievement>(
+            r#"
+            INSERT INTO social.user_achievements
+                (user_id, achievement_id, earned_at, token_pick_id, announce)
+            SELECT * FROM UNNEST(
+                $1::uuid[], $2::varchar[], $3::timestamptz[], $4::bigint[], $5::boolean[]
+            )
+            ON CONFLICT (user_id, achievement_id) DO NOTHING
+            RETURNING user_id, achievement_id, earned_at, token_pick_id, announce
+            "#,
+        )
+        .bind(achievements.iter().map(|a| a.user_id).collect::<Vec<_>>())
+        .bind(
+            achievements
+                .iter()
+                .map(|a| a.achievement_id.clone())
+                .collect::<Vec<_>>(),
+        )
+        .bind(achievements.iter().map(|a| a.earned_at).collect::<Vec<_>>())
+        .bind(
+            achievements
+                .iter()
+                .map(|a| a.token_pick_id)
+                .collect::<Vec<_>>(),
+        )
+        .bind(achievements.iter().map(|a| a.announce).collect::<Vec<_>>())
+        .fetch_all(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO social.achievement_evaluations (user_id)
+            SELECT * FROM UNNEST($1::uuid[])
+            ON CONFLICT (user_id) DO UPDATE SET evaluated_at = CURRENT_TIMESTAMP
+            "#,
+        )
+        .bind(user_ids)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(saved)
+    }
+
+    /// Achievements the user earned, oldest first
+    pub async fn list_user_achievements(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<UserAchievement>, sqlx::Error> {
+        sqlx::query_as::<_, UserAchievement>(
+            r#"
+            SELECT user_id, achievement_id, earned_at, token_pick_id, announce
+            FROM social.user_achievements
+            WHERE user_id = $1
+            ORDER BY earned_at ASC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(self.db.as_ref())
+        .await
+    }
+}
@@ -31,8 +31,8 @@ const GROUP_STATS_CTE: &str = r#"
                 END
             ) as average_returns
         FROM social.token_picks tp
-        JOIN social.tokens t ON tp.token_address = t.address
-        WHERE 1=1"#;
+        JOIN social.tokens t ON tp.token_address = t.address AND tp.token_chain = t.chain
+        WHERE tp.deleted_at IS NULL"#;
 
 const GROUP_SELECT_QUERY: &str = r#"
     SELECT
@@ -232,12 +232,12 @@ impl GroupRepository {
             .await
     }
 
+    /// Members of the group ordered by username, starting after `after_username`
     pub async fn list_group_members(
         &self,
         group_id: i64,
-        limit: u32,
-        page: u32,
-        get_all: bool,
+        limit: Option<u32>,
+        after_username: Option<&str>,
     ) -> Result<(Vec<GroupWithUsers>, String, i64), sqlx::Error> {
         // Get total count first
         let total: i64 = sqlx::query_scalar(
@@ -250,35 +250,25 @@ impl GroupRepository {
         .fetch_one(self.db.as_ref())
         .await?;
 
-        // Base query without pagination
-        let base_query = r#"SELECT DISTINCT ON (gu.user_id) gu.*, u.username, g.name
-            FROM social.group_users gu
-            JOIN public.user u ON gu.user_id = u.id
-            JOIN social.groups g ON gu.group_id = g.id
-            WHERE gu.group_id = $1
-            ORDER BY gu.user_id, gu.joined_at DESC"#;
-
-        // Add pagination only if get_all is false
-        let query = if get_all {
-            base_query.to_string()
-        } else {
-            format!("{} LIMIT $2 OFFSET $3", base_query)
-        };
-
-        // Execute query based on get_all parameter
-        let members = if get_all {
-            sqlx::query_as::<_, GroupWithUsers>(&query)
-                .bind(group_id)
-                .fetch_all(self.db.as_ref())
-                .await?
-        } else {
-            sqlx::query_as::<_, GroupWithUsers>(&query)
-                .bind(group_id)
-                .bind(limit as i64)
-                .bind(((page - 1) * limit) as i64)
-                .fetch_all(self.db.as_ref())
-                .await?
-        };
+        // A NULL limit returns every member
+        let members = sqlx::query_as::<_, GroupWithUsers>(
+            r#"SELECT * FROM (
+                SELECT DISTINCT ON (gu.user_id) gu.*, u.username, g.name
+                FROM social.group_users gu
+                JOIN public.user u ON gu.user_id = u.id
+                JOIN social.groups g ON gu.group_id = g.id
+                WHERE gu.group_id = $1
+                ORDER BY gu.user_id, gu.joined_at DESC
+            ) members
+            WHERE $2::text IS NULL OR username > $2
+            ORDER BY username
+            LIMIT $3"#,
+        )
+        .bind(group_id)
+        .bind(after_username)
+        .bind(limit.map(i64::from))
+        .fetch_all(self.db.as_ref())
+        .await?;
 
         let group_name = sqlx::query_scalar(r#"SELECT name FROM social.groups WHERE id = $1"#)
             .bind(group_id)
@@ -0,0 +1,73 @@
+use sqlx::PgPool;
+use std::sync::Arc;
+
+use crate::models::profiles::{LeaderboardMinPicks, DEFAULT_LEADERBOARD};
+
+pub struct LeaderboardRepository {
+    db: Arc<PgPool>,
+}
+
+impl LeaderboardRepository {
+    pub fn new(db: Arc<PgPool>) -> Self {
+        LeaderboardRepository { db }
+    }
+
+    pub async fn list_min_picks(&self) -> Result<Vec<LeaderboardMinPicks>, sqlx::Error> {
+        sqlx::query_as::<_, LeaderboardMinPicks>(
+            r#"
+            SELECT leaderboard, time_period, min_picks, updated_at
+            FROM social.leaderboard_min_picks
+            ORDER BY leaderboard, time_period
+            "#,
+        )
+        .fetch_all(self.db.as_ref())
+        .await
+    }
+
+    /// Minimum picks of the leaderboard in the time period, falling back to the default
+    /// leaderboard
+    pub async fn find_min_picks(
+        &self,
+        leaderboard: &str,
+        time_period: &str,
+    ) -> Result<Option<i32>, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"
+            SELECT min_picks
+            FROM social.leaderboard_min_picks
+            WHERE leaderboard IN ($1, $3)
+            AND time_period = $2
+            ORDER BY leaderboard = $3
+            LIMIT 1
+            "#,
+        )
+        .bind(leaderboard)
+        .bind(time_period)
+        .bind(DEFAULT_LEADERBOARD)
+        .fetch_optional(self.db.as_ref())
+        .await
+    }
+
+    pub async fn upsert_min_picks(
+        &self,
+        leaderboard: &str,
+        time_period: &str,
+        min_picks: i32,
+    ) -> Result<LeaderboardMinPicks, sqlx::Error> {
+        sqlx::query_as::<_, LeaderboardMinPicks>(
+            r#"
+            INSERT INTO social.leaderboard_min_picks (leaderboard, time_period, min_picks)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (leaderboard, time_period) DO UPDATE
+            SET min_picks = EXCLUDED.min_picks,
+                updated_at = CURRENT_TIMESTAMP
+            RETURNING leaderboard, time_period, min_picks, updated_at
+            "#,
+        )
+        .bind(leaderboard)
+        .bind(time_period)
+        .bind(min_picks)
+        .fetch_one(self.db.as_ref())
+        .await
+    }
+}
@@ -1,3 +1,10 @@
+pub mod achievement_repository;
 pub mod group_repository;
+pub mod leaderboard_repository;
+pub mod points_repository;
+pub mod qualification_policy_repository;
+pub mod rank_history_repository;
+pub mod season_repository;
 pub mod token_repository;
 pub mod user_repository;
+pub mod user_stats_repository;
@@ -0,0 +1,116 @@
+use std::{collections::HashMap, sync::Arc};
+
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::models::{points::PointsAward, tiers::TiersType};
+
+pub struct PointsRepository {
+    db: Arc<PgPool>,
+}
+
+impl PointsRepository {
+    pub fn new(db: Arc<PgPool>) -> Self {
+        PointsRepository { db }
+    }
+
+    /// Records the awards not recorded yet and adds their points to the users' totals, which
+    /// never go below zero. Returns the new totals of the users who were awarded points.
+    pub async fn award_points(
+        &self,
+        awards: &[PointsAward],
+    ) -> Result<HashMap<Uuid, i64>, sqlx::Error> {
+        if awards.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let totals = sqlx::query_as::<_, (Uuid, i64)>(
+            r#"
+            WITH awarded AS (
+                INSERT INTO social.point_transactions (
+                    user_id, points_earned, action_type, context, details
+                )
+                SELECT * FROM UNNEST($1::uuid[], $2::bigint[], $3::varchar[], $4::varchar[], $5::jsonb[])
+                ON CONFLICT (user_id, action_type, context) DO NOTHING
+                RETURNING user_id, points_earned
+            )
+            INSERT INTO social.user_points (user_id, total_points)
+            SELECT user_id, GREATEST(SUM(points_earned), 0)::bigint
+            FROM awarded
+            GROUP BY user_id
+            ON CONFLICT (user_id) DO UPDATE
+            SET total_points = GREATEST(
+                    social.user_points.total_points + (
+                        SELECT SUM(points_earned) FROM awarded WHERE awarded.user_id = EXCLUDED.user_id
+                    ),
+                    0
+                )::bigint,
+                updated_at = CURRENT_TIMESTAMP
+            RETURNING user_id, total_points
+            "#,
+        )
+        .bind(awards.iter().map(|a| a.user_id).collect::<Vec<_>>())
+        .bind(awards.iter().map(|a| a.points()).collect::<Vec<_>>())
+        .bind(awards.iter().map(|a| a.rule.to_string()).collect::<Vec<_>>())
+        .bind(awards.iter().map(|a| a.context()).collect::<Vec<_>>())
+        .bind(awards.iter().map(|a| a.details.clone()).collect::<Vec<_>>())
+        .fetch_all(self.db.as_ref())
+        .await?;
+
+        Ok(totals.into_iter().collect())
+    }
+
+    /// Records that the users reached the tiers. Returns the users who had not reached theirs
+    /// before.
+    pub async fn save_user_tiers(
+        &self,
+        tiers: &[(Uuid, TiersType)],
+    ) -> Result<Vec<(Uuid, String)>, sqlx::Error> {
+        if tiers.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        sqlx::query_as::<_, (Uuid, String)>(
+            r#"
+            INSERT INTO social.user_tiers (user_id, tier)
+            SELECT user_id, tier::social.tier_type
+            FROM UNNEST($1::uuid[], $2::text[]) AS t(user_id, tier)
+            ON CONFLICT (user_id, tier) DO NOTHING
+            RETURNING user_id, tier::text
+            "#,
+        )
+        .bind(
+            tiers
+                .iter()
+                .map(|(user_id, _)| *user_id)
+                .collect::<Vec<_>>(),
+        )
+        .bind(
+            tiers
+                .iter()
+                .map(|(_, tier)| tier.to_string())
+                .collect::<Vec<_>>(),
+        )
+        .fetch_all(self.db.as_ref())
+        .await
+    }
+
+    /// Total points of each user, users without points are left out
+    pub async fn list_user_points(
+        &self,
+        user_ids: &[Uuid],
+    ) -> Result<HashMap<Uuid, i64>, sqlx::Error> {
+        let points = sqlx::query_as::<_, (Uuid, i64)>(
+            r#"
+            SELECT user_id, total_points
+            FROM social.user_points
+            WHERE user_id = ANY($1)
+            "#,
+        )
+        .bind(user_ids)
+        .fetch_all(self.db.as_ref())
+        .await?;
+
+        Ok(points.into_iter().collect())
+    }
+}
@@ -0,0 +1,89 @@
+use sqlx::PgPool;
+use std::sync::Arc;
+
+use crate::{
+    apis::api_models::request::CreateQualificationPolicyRequest,
+    models::qualification_policies::QualificationPolicy,
+};
+
+pub struct QualificationPolicyRepository {
+    db: Arc<PgPool>,
+}
+
+impl QualificationPolicyRepository {
+    pub fn new(db: Arc<PgPool>) -> Self {
+        QualificationPolicyRepository { db }
+    }
+
+    pub async fn list_policies(&self) -> Result<Vec<QualificationPolicy>, sqlx::Error> {
+        sqlx::query_as::<_, QualificationPolicy>(
+            r#"
+            SELECT * FROM social.qualification_policies
+            ORDER BY environment NULLS FIRST, group_id NULLS FIRST, version DESC
+            "#,
+        )
+        .fetch_all(self.db.as_ref())
+        .await
+    }
+
+    /// Inserts the next version of the policy for the request's environment and group,
+    /// deactivating the previous one.
+    pub async fn create_policy(
+        &self,
+        policy: &CreateQualificationPolicyRequest,
+    ) -> Result<QualificationPolicy, sqlx::Error> {
+        let mut tx = self.db.begin().await?;
+
+        sqlx::query(
+            r#"
+            UPDATE social.qualification_policies
+            SET is_active = false
+            WHERE environment IS NOT DISTINCT FROM $1
+            AND group_id IS NOT DISTINCT FROM $2
+            "#,
+        )
+        .bind(&policy.environment)
+        .bind(policy.group_id)
+        .execute(&mut *tx)
+        .await?;
+
+        let policy = sqlx::query_as::<_, QualificationPolicy>(
+            r#"
+            INSERT INTO social.qualification_policies (
+                environment,
+                group_id,
+                version,
+                min_market_cap,
+                min_liquidity_volume_ratio,
+                large_cap_threshold,
+                large_cap_min_liquidity,
+                bust_min_volume,
+                bust_min_liquidity,
+                bust_min_market_cap,
+                cancellation_window_seconds
+            )
+            SELECT $1, $2, COALESCE(MAX(version), 0) + 1, $3, $4, $5, $6, $7, $8, $9, $10
+            FROM social.qualification_policies
+            WHERE environment IS NOT DISTINCT FROM $1
+            AND group_id IS NOT DISTINCT FROM $2
+            RETURNING *
+            "#,
+        )
+        .bind(&policy.environment)
+        .bind(policy.group_id)
+        .bind(policy.min_market_cap)
+        .bind(policy.min_liquidity_volume_ratio)
+        .bind(policy.large_cap_threshold)
+        .bind(policy.large_cap_min_liquidity)
+        .bind(policy.bust_min_volume)
+        .bind(policy.bust_min_liquidity)
+        .bind(policy.bust_min_market_cap)
+        .bind(policy.cancellation_window_seconds)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(policy)
+    }
+}
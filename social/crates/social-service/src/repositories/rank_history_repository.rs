@@ -0,0 +1,171 @@
+use std::{collections::HashMap, sync::Arc};
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::models::rank_history::{RankHistoryPoint, RankSnapshot};
+
+/// Identifies a leaderboard whose ranks are snapshotted
+pub struct LeaderboardKey<'a> {
+    pub resolution: &'a str,
+    pub sort: &'a str,
+    pub time_period: &'a str,
+}
+
+pub struct RankHistoryRepository {
+    db: Arc<PgPool>,
+}
+
+impl RankHistoryRepository {
+    pub fn new(db: Arc<PgPool>) -> Self {
+        RankHistoryRepository { db }
+    }
+
+    /// Saves the ranks of a leaderboard snapshot. A snapshot already taken at the same date is
+    /// kept as is.
+    pub async fn save_rank_snapshots(
+        &self,
+        leaderboard: &LeaderboardKey<'_>,
+        snapshot_at: DateTime<Utc>,
+        ranks: &[RankSnapshot],
+    ) -> Result<(), sqlx::Error> {
+        if ranks.is_empty() {
+            return Ok(());
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO social.leaderboard_rank_snapshots (
+                resolution, sort, time_period, snapshot_at, user_id, rank, total_ranked, sort_value
+            )
+            SELECT $1, $2, $3, $4, r.user_id, r.rank, $5, r.sort_value
+            FROM UNNEST($6::uuid[], $7::integer[], $8::numeric[]) AS r(user_id, rank, sort_value)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(leaderboard.resolution)
+        .bind(leaderboard.sort)
+        .bind(leaderboard.time_period)
+        .bind(snapshot_at)
+        .bind(ranks.len() as i32)
+        .bind(ranks.iter().map(|r| r.user_id).collect::<Vec<_>>())
+        .bind(ranks.iter().map(|r| r.rank).collect::<Vec<_>>())
+        .bind(ranks.iter().map(|r| r.sort_value).collect::<Vec<_>>())
+        .execute(self.db.as_ref())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Ranks of the latest snapshot of the leaderboard taken at or before `at`, by user
+    pub async fn list_ranks_at(
+        &self,
+        leaderboard: &LeaderboardKey<'_>,
+        at: DateTime<Utc>,
+    ) -> Result<HashMap<Uuid, i32>, sqlx::Error> {
+        let ranks = sqlx::query_as::<_, RankSnapshot>(
+            r#"
+            SELECT user_id, rank, sort_value
+            FROM social.leaderboard_rank_snapshots
+            WHERE resolution = $1
+            AND sort = $2
+            AND time_period = $3
+            AND snapshot_at = (
+                SELECT MAX(snapshot_at)
+                FROM social.leaderboard_rank_snapshots
+                WHERE resolution = $1
+                AND sort = $2
+                AND time_period = $3
+                AND snapshot_at <= $4
+            )
+            "#,
+        )
+        .bind(leaderboard.resolution)
+        .bind(leaderboard.sort)
+        .bind(leaderboard.time_period)
+        .bind(at)
+        .fetch_all(self.db.as_ref())
+        .await?;
+
+        Ok(ranks.into_iter().map(|r| (r.user_id, r.rank)).collect())
+    }
+
+    /// Ranks of the user on every snapshot of the leaderboard taken since `since`, oldest first
+    pub async fn list_rank_history(
+        &self,
+        user_id: Uuid,
+        leaderboard: &LeaderboardKey<'_>,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<RankHistoryPoint>, sqlx::Error> {
+        sqlx::query_as::<_, RankHistoryPoint>(
+            r#"
+            SELECT snapshot_at, rank, total_ranked, sort_value
+            FROM social.leaderboard_rank_snapshots
+            WHERE user_id = $1
+            AND resolution = $2
+            AND sort = $3
+            AND time_period = $4
+            AND snapshot_at >= $5
+            ORDER BY snapshot_at ASC
+            "#,
+        )
+        .bind(user_id)
+        .bind(leaderboard.resolution)
+        .bind(leaderboard.sort)
+        .bind(leaderboard.time_period)
+        .bind(since)
+        .fetch_all(self.db.as_ref())
+        .await
+    }
+
+    /// Date of the first snapshot of the leaderboard that ranked each user within `max_rank`,
+    /// users never ranked that high are left out
+    pub async fn list_first_ranked_within(
+        &self,
+        user_ids: &[Uuid],
+        leaderboard: &LeaderboardKey<'_>,
+        max_rank: i32,
+    ) -> Result<HashMap<Uuid, DateTime<Utc>>, sqlx::Error> {
+        let ranked = sqlx::query_as::<_, (Uuid, DateTime<Utc>)>(
+            r#"
+            SELECT user_id, MIN(snapshot_at)
+            FROM social.leaderboard_rank_snapshots
+            WHERE user_id = ANY($1)
+            AND resolution = $2
+            AND sort = $3
+            AND time_period = $4
+            AND rank <= $5
+            GROUP BY user_id
+            "#,
+        )
+        .bind(user_ids)
+        .bind(leaderboard.resolution)
+        .bind(leaderboard.sort)
+        .bind(leaderboard.time_period)
+        .bind(max_rank)
+        .fetch_all(self.db.as_ref())
+        .await?;
+
+        Ok(ranked.into_iter().collect())
+    }
+
+    pub async fn delete_rank_snapshots_before(
+        &self,
+        resolution: &str,
+        before: DateTime<Utc>,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM social.leaderboard_rank_snapshots
+            WHERE resolution = $1 AND snapshot_at < $2
+            "#,
+        )
+        .bind(resolution)
+        .bind(before)
+        .execute(self.db.as_ref())
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,120 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use sqlx::{types::Json, PgPool};
+
+use crate::{
+    apis::api_models::request::CreateSeasonRequest,
+    models::seasons::{Season, SeasonStandings},
+};
+
+pub struct SeasonRepository {
+    db: Arc<PgPool>,
+}
+
+impl SeasonRepository {
+    pub fn new(db: Arc<PgPool>) -> Self {
+        SeasonRepository { db }
+    }
+
+    pub async fn create_season(&self, season: &CreateSeasonRequest) -> Result<Season, sqlx::Error> {
+        sqlx::query_as::<_, Season>(
+            r#"
+            INSERT INTO social.seasons (name, group_id, starts_at, ends_at)
+            VALUES ($1, $2, $3, $4)
+            RETURNING *
+            "#,
+        )
+        .bind(&season.name)
+        .bind(season.group_id)
+        .bind(season.starts_at)
+        .bind(season.ends_at)
+        .fetch_one(self.db.as_ref())
+        .await
+    }
+
+    /// Every season, latest first
+    pub async fn list_seasons(&self) -> Result<Vec<Season>, sqlx::Error> {
+        sqlx::query_as::<_, Season>("SELECT * FROM social.seasons ORDER BY starts_at DESC, id DESC")
+            .fetch_all(self.db.as_ref())
+            .await
+    }
+
+    pub async fn get_season(&self, id: i64) -> Result<Option<Season>, sqlx::Error> {
+        sqlx::query_as::<_, Season>("SELECT * FROM social.seasons WHERE id = $1")
+            .bind(id)
+            .fetch_optional(self.db.as_ref())
+            .await
+    }
+
+    /// Seasons that ended before `ended_before` and whose standings were not frozen yet
+    pub async fn list_seasons_to_close(
+        &self,
+        ended_before: DateTime<Utc>,
+    ) -> Result<Vec<Season>, sqlx::Error> {
+        sqlx::query_as::<_, Season>(
+            r#"
+            SELECT * FROM social.seasons
+            WHERE closed_at IS NULL
+            AND ends_at <= $1
+            ORDER BY ends_at ASC
+            "#,
+        )
+        .bind(ended_before)
+        .fetch_all(self.db.as_ref())
+        .await
+    }
+
+    /// Freezes the final standings of the season and marks it closed
+    pub async fn close_season(
+        &self,
+        id: i64,
+        standings: &SeasonStandings,
+        standings_uri: &str,
+    ) -> Result<Season, sqlx::Error> {
+        let mut tx = self.db.begin().await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO social.season_standings (season_id, standings)
+            VALUES ($1, $2)
+            ON CONFLICT (season_id) DO UPDATE SET standings = EXCLUDED.standings
+            "#,
+        )
+        .bind(id)
+        .bind(Json(standings))
+        .execute(&mut *tx)
+        .await?;
+
+        let season = sqlx::query_as::<_, Season>(
+            r#"
+            UPDATE social.seasons
+            SET closed_at = CURRENT_TIMESTAMP, standings_uri = $2
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(standings_uri)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(season)
+    }
+
+    /// Final standings of a closed season
+    pub async fn get_season_standings(
+        &self,
+        id: i64,
+    ) -> Result<Option<SeasonStandings>, sqlx::Error> {
+        let standings = sqlx::query_scalar::<_, Json<SeasonStandings>>(
+            "SELECT standings FROM social.season_standings WHERE season_id = $1",
+        )
+        .bind(id)
+        .fetch_optional(self.db.as_ref())
+        .await?;
+
+        Ok(standings.map(|s| s.0))
+    }
+}
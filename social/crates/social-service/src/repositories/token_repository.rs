@@ -1,32 +1,58 @@
 use std::{collections::HashMap, sync::Arc};
 
 use chrono::{DateTime, FixedOffset, Utc};
+use futures::stream::BoxStream;
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
 use rust_decimal::Decimal;
 use serde::Deserialize;
-use sqlx::PgPool;
+use sqlx::{postgres::PgArguments, query::QueryAs, types::Json, PgPool, Postgres, Transaction};
 use tracing::{error, info};
 use uuid::Uuid;
 
 use crate::{
     apis::api_models::query::PickLeaderboardSort,
     models::{
-        token_picks::{TokenPick, TokenPickResponse, TokenPicksGroup},
+        token_picks::{
+            CallType, DeletedTokenPick, PickAuditAction, PickMilestone, PickMilestoneHit,
+            TokenPick, TokenPickResponse, TokenPickSnapshot, TokenPicksGroup,
+        },
         tokens::{Chain, Token},
     },
     utils::{errors::app_error::AppError, time::TimePeriod},
 };
 
-pub const QUALIFIED_TOKEN_PICKS_FILTER: &str = r#"
-    AND t.market_cap > 40000
-    AND CASE
-        WHEN t.market_cap < 1000000 THEN
-            t.liquidity >= (t.volume_24h * 0.04)
-        ELSE
-            t.liquidity >= 40000
-    END
-    AND t.liquidity IS NOT NULL
-    AND t.volume_24h IS NOT NULL
+/// Whether a pick meets the qualification policy it was created under, by the current metrics
+/// of its token
+macro_rules! qualified_token_pick {
+    () => {
+        r#"(
+        EXISTS (
+            SELECT 1
+            FROM social.qualification_policies qp
+            WHERE qp.id = tp.qualification_policy_id
+            AND t.market_cap > qp.min_market_cap
+            AND CASE
+                WHEN t.market_cap < qp.large_cap_threshold THEN
+                    t.liquidity >= (t.volume_24h * qp.min_liquidity_volume_ratio)
+                ELSE
+                    t.liquidity >= qp.large_cap_min_liquidity
+            END
+        )
+        AND t.liquidity IS NOT NULL
+        AND t.volume_24h IS NOT NULL
+    )"#
+    };
+}
+
+/// Whether the pick qualifies, as the `is_qualified` column
+const QUALIFIED_TOKEN_PICK_COLUMN: &str = concat!(qualified_token_pick!(), " AS is_qualified");
+
+/// Picks are judged by the qualification policy they were created under
+pub const QUALIFIED_TOKEN_PICKS_FILTER: &str = concat!("\n    AND ", qualified_token_pick!(), "\n");
+
+/// Excludes picks soft deleted by their caller or a moderator
+pub const ACTIVE_TOKEN_PICKS_FILTER: &str = r#"
+    AND tp.deleted_at IS NULL
 "#;
 
 pub const TOKEN_PICKS_FILTER_WITH_NULLS: &str = r#"
@@ -34,6 +60,19 @@ pub const TOKEN_PICKS_FILTER_WITH_NULLS: &str = r#"
     AND (COALESCE(tp.highest_market_cap, 0) > 0 OR COALESCE(tp.highest_multiplier, 0) > 0)
 "#;
 
+/// The caller of a pick, hidden for picks made in anonymous groups
+const PUBLIC_USER_JSON: &str = r#"
+CASE
+    WHEN g.settings->>'privacy' = 'anonymous' THEN NULL
+    ELSE row_to_json(u)
+END
+"#;
+
+/// Excludes picks made in anonymous groups, for listings that reveal who made the picks
+const PUBLIC_GROUP_PICKS_FILTER: &str = r#"
+    AND g.settings->>'privacy' IS DISTINCT FROM 'anonymous'
+"#;
+
 const GROUP_JSON_BUILDER: &str = r#"
 json_build_object(
 	'id', g.id,
@@ -44,8 +83,34 @@ json_build_object(
 )
 "#;
 
+/// Picks counted towards a rate limit, made since the given date
 pub enum UserPickLimitScope {
-    User(Uuid, TimePeriod),
+    User(Uuid, DateTime<Utc>),
+    UserInGroup(Uuid, i64, DateTime<Utc>),
+    Group(i64, DateTime<Utc>),
+}
+
+#[derive(Debug)]
+pub enum TokenPickScope {
+    Pick(i64),
+    User(Uuid),
+    Window(DateTime<Utc>, DateTime<Utc>),
+}
+
+/// An unpaginated token picks query, built once and kept alive while its rows are streamed
+pub struct TokenPicksQuery {
+    sql: String,
+    bind_values: Vec<QueryValue>,
+}
+
+impl TokenPicksQuery {
+    pub fn new(params: &ListTokenPicksParams, qualified: Option<bool>) -> Self {
+        let (mut sql, bind_values) =
+            TokenRepository::filtered_token_picks_query(Some(params), qualified);
+        sql += &TokenRepository::token_picks_order_clause(Some(params));
+
+        Self { sql, bind_values }
+    }
 }
 
 pub struct TokenRepository {
@@ -62,29 +127,63 @@ impl TokenRepository {
 pub struct ListTokenPicksParams {
     pub user_id: Option<Uuid>,
     pub picked_after: Option<DateTime<FixedOffset>>,
-    pub page: u32,
+    /// Sort key and id of the last pick of the previous page
+    pub after: Option<(String, i64)>,
     pub limit: u32,
     pub order_by: Option<PickLeaderboardSort>,
     pub order_direction: Option<String>,
     pub get_all: bool,
     pub group_ids: Option<Vec<i64>>,
     pub following: bool,
+    pub chain: Option<Chain>,
+    pub milestone: Option<PickMilestone>,
+    pub call_type: Option<CallType>,
+}
+
+impl ListTokenPicksParams {
+    /// The sort picks are listed by and whether it is descending, newest first by default
+    pub fn sort(&self) -> (PickLeaderboardSort, bool) {
+        match self.order_by {
+            Some(order_by) => (
+                order_by,
+                self.order_direction
+                    .as_deref()
+                    .is_some_and(|d| d.eq_ignore_ascii_case("desc")),
+            ),
+            None => (PickLeaderboardSort::Newest, true),
+        }
+    }
+
+    /// Identifies the order of the list, so its cursors are only accepted for the same order
+    pub fn cursor_sort(&self) -> String {
+        let (sort, descending) = self.sort();
+        format!(
+            "picks:{:?}:{}:{}",
+            sort,
+            descending,
+            self.milestone.unwrap_or_default().to_string()
+        )
+    }
+
+    fn cursor_expression(&self) -> String {
+        let (sort, _) = self.sort();
+        sort.cursor_expression(self.milestone.unwrap_or_default())
+    }
 }
 
 impl TokenRepository {
     pub async fn get_token(
         &self,
-        address: &String,
-        chain: Option<String>,
+        address: &str,
+        chain: &Chain,
     ) -> Result<Option<Token>, sqlx::Error> {
-        let chain = chain.unwrap_or(Chain::Solana.to_string());
         let query = r#"
             SELECT * FROM social.tokens WHERE address = $1 AND chain = $2
         "#;
 
         sqlx::query_as::<_, Token>(query)
             .bind(address)
-            .bind(chain)
+            .bind(chain.to_string())
             .fetch_optional(self.db.as_ref())
             .await
     }
@@ -203,11 +302,11 @@ impl TokenRepository {
         Ok(())
     }
 
-    pub async fn list_token_picks(
-        &self,
+    /// The filtered token picks query, without ordering or pagination, and its bind values
+    fn filtered_token_picks_query(
         params: Option<&ListTokenPicksParams>,
         qualified: Option<bool>,
-    ) -> Result<(Vec<TokenPick>, i64), sqlx::Error> {
+    ) -> (String, Vec<QueryValue>) {
         let mut base_query = format!(
             r#"
             SELECT tp.*,
@@ -216,14 +315,17 @@ impl TokenRepository {
                        WHEN g.settings->>'privacy' = 'anonymous' THEN NULL
                        ELSE row_to_json(u)
                    END AS user,
-                   row_to_json(g) AS group
+                   row_to_json(g) AS group,
+                   {cursor_key}
             FROM social.token_picks tp
-            JOIN social.tokens t ON tp.token_address = t.address
+            JOIN social.tokens t ON tp.token_address = t.address AND tp.token_chain = t.chain
             JOIN public.user u ON tp.user_id = u.id
             JOIN social.groups g ON tp.group_id = g.id
             WHERE 1=1
+            {ACTIVE_TOKEN_PICKS_FILTER}
             {TOKEN_PICKS_FILTER_WITH_NULLS}
-            "#
+            "#,
+            cursor_key = Self::cursor_key_column(params)
         );
 
         if qualified.unwrap_or(true) {
@@ -252,6 +354,32 @@ impl TokenRepository {
             if let Some(picked_after) = params.picked_after {
                 where_clauses.push(format!("tp.call_date >= ${bind_idx}"));
                 bind_values.push(QueryValue::Timestamp(picked_after));
+                bind_idx += 1;
+            }
+
+            // Add chain condition if present
+            if let Some(chain) = &params.chain {
+                where_clauses.push(format!("tp.token_chain = ${bind_idx}"));
+                bind_values.push(QueryValue::Text(chain.to_string()));
+                bind_idx += 1;
+            }
+
+            // Add milestone condition if present
+            if let Some(milestone) = &params.milestone {
+                where_clauses.push(format!("tp.milestone_hits ? ${bind_idx}"));
+                bind_values.push(QueryValue::Text(milestone.to_string()));
+                bind_idx += 1;
+            }
+
+            // Add group condition if present
+            if let Some(group_ids) = &params.group_ids {
+                where_clauses.push(format!("tp.group_id = ANY(${bind_idx})"));
+                bind_values.push(QueryValue::Int64Array(group_ids.clone()));
+            }
+
+            // Add call type condition if present
+            if let Some(call_type) = &params.call_type {
+                where_clauses.push(call_type.filter_expression().to_string());
             }
         }
 
@@ -261,72 +389,120 @@ impl TokenRepository {
             base_query += &where_clauses.join(" AND ");
         }
 
-        // Count query
-        let count_query = format!("SELECT COUNT(*) FROM ({}) AS filtered", base_query);
+        (base_query, bind_values)
+    }
 
-        // Add ordering and pagination to main query
-        if let Some(params) = params {
-            if let Some(order_by) = &params.order_by {
-                let direction = params.order_direction.as_deref().unwrap_or("ASC");
-                base_query += &format!(" ORDER BY {} {}", order_by.to_string(), direction);
-            } else {
-                base_query += " ORDER BY call_date DESC";
-            }
+    /// The sort key of each pick, returned so the last pick of a page can become a cursor
+    fn cursor_key_column(params: Option<&ListTokenPicksParams>) -> String {
+        let expression = params.map_or_else(
+            || ListTokenPicksParams::default().cursor_expression(),
+            |p| p.cursor_expression(),
+        );
+        format!("({expression})::text AS cursor_key")
+    }
 
-            // Only apply pagination if get_all is false
-            if !params.get_all {
-                let offset = (params.page - 1) * params.limit;
-                base_query += &format!(" LIMIT {} OFFSET {}", params.limit, offset);
-            }
-        }
+    /// Orders by the sort key, then by id so picks with the same key keep a stable order
+    fn token_picks_order_clause(params: Option<&ListTokenPicksParams>) -> String {
+        let default_params = ListTokenPicksParams::default();
+        let params = params.unwrap_or(&default_params);
+        let (_, descending) = params.sort();
+        let direction = if descending { "DESC" } else { "ASC" };
 
-        let mut tx = self.db.begin().await?;
+        format!(
+            " ORDER BY {} {direction}, tp.id {direction}",
+            params.cursor_expression()
+        )
+    }
 
-        // Execute count query with bindings
-        let mut count_builder = sqlx::query_scalar(&count_query);
-        for value in &bind_values {
-            count_builder = match value {
-                QueryValue::Timestamp(ts) => count_builder.bind(ts),
-                QueryValue::Uuid(uuid) => count_builder.bind(uuid),
-                QueryValue::Int64Array(arr) => count_builder.bind(arr),
-            };
+    /// Runs a filtered token picks query one keyset page at a time, or whole with `get_all`.
+    /// Returns the sort key and id of the last pick if more picks follow the page.
+    async fn fetch_token_picks_page(
+        &self,
+        mut query: String,
+        mut bind_values: Vec<QueryValue>,
+        params: Option<&ListTokenPicksParams>,
+    ) -> Result<(Vec<TokenPick>, Option<(String, i64)>), sqlx::Error> {
+        let limit = params.filter(|p| !p.get_all).map(|p| p.limit as usize);
+
+        if let Some((params, (key, id))) = params.and_then(|p| Some((p, p.after.clone()?))) {
+            let (sort, descending) = params.sort();
+            let bind_idx = bind_values.len() + 1;
+            query += &format!(
+                " AND ({}, tp.id) {} (${bind_idx}::{}, ${})",
+                params.cursor_expression(),
+                if descending { "<" } else { ">" },
+                sort.cursor_type(),
+                bind_idx + 1
+            );
+            bind_values.push(QueryValue::Text(key));
+            bind_values.push(QueryValue::Int64(id));
         }
-        let total: i64 = count_builder.fetch_one(&mut *tx).await?;
-
-        // Execute main query with bindings
-        let mut query_builder = sqlx::query_as::<_, TokenPick>(&base_query);
-        for value in &bind_values {
-            query_builder = match value {
-                QueryValue::Timestamp(ts) => query_builder.bind(ts),
-                QueryValue::Uuid(uuid) => query_builder.bind(uuid),
-                QueryValue::Int64Array(arr) => query_builder.bind(arr),
-            };
+
+        query += &Self::token_picks_order_clause(params);
+        if let Some(limit) = limit {
+            // One more pick than the page tells whether another page follows
+            query += &format!(" LIMIT {}", limit + 1);
         }
 
-        let picks = query_builder.fetch_all(&mut *tx).await?;
-        tx.commit().await?;
+        let mut rows =
+            bind_query_values(sqlx::query_as::<_, CursorTokenPick>(&query), &bind_values)
+                .fetch_all(self.db.as_ref())
+                .await?;
+
+        let next = match limit {
+            Some(limit) if rows.len() > limit => {
+                rows.truncate(limit);
+                rows.last().map(|r| (r.cursor_key.clone(), r.pick.id))
+            }
+            _ => None,
+        };
 
-        Ok((picks, total))
+        Ok((rows.into_iter().map(|r| r.pick).collect(), next))
+    }
+
+    pub async fn list_token_picks(
+        &self,
+        params: Option<&ListTokenPicksParams>,
+        qualified: Option<bool>,
+    ) -> Result<(Vec<TokenPick>, Option<(String, i64)>), sqlx::Error> {
+        let (query, bind_values) = Self::filtered_token_picks_query(params, qualified);
+        self.fetch_token_picks_page(query, bind_values, params)
+            .await
+    }
+
+    /// Streams every token pick matching the query, so exports don't hold them all in memory
+    pub fn stream_token_picks<'a>(
+        &'a self,
+        query: &'a TokenPicksQuery,
+    ) -> BoxStream<'a, Result<TokenPick, sqlx::Error>> {
+        bind_query_values(
+            sqlx::query_as::<_, TokenPick>(&query.sql),
+            &query.bind_values,
+        )
+        .fetch(self.db.as_ref())
     }
 
     pub async fn list_token_picks_group(
         &self,
         params: Option<&ListTokenPicksParams>,
-    ) -> Result<(Vec<TokenPick>, i64), sqlx::Error> {
+    ) -> Result<(Vec<TokenPick>, Option<(String, i64)>), sqlx::Error> {
         let mut base_query = format!(
             r#"
             SELECT tp.*,
                    row_to_json(t) AS token,
                    row_to_json(u) AS user,
-                   {GROUP_JSON_BUILDER} as group
+                   {GROUP_JSON_BUILDER} as group,
+                   {cursor_key}
             FROM social.token_picks tp
-            JOIN social.tokens t ON tp.token_address = t.address
+            JOIN social.tokens t ON tp.token_address = t.address AND tp.token_chain = t.chain
             JOIN public.user u ON tp.user_id = u.id
             JOIN social.groups g ON tp.group_id = g.id
             WHERE 1=1
+            {ACTIVE_TOKEN_PICKS_FILTER}
             {TOKEN_PICKS_FILTER_WITH_NULLS}
             {QUALIFIED_TOKEN_PICKS_FILTER}
-            "#
+            "#,
+            cursor_key = Self::cursor_key_column(params)
         );
 
         let mut bind_values = Vec::new();
@@ -347,54 +523,24 @@ impl TokenRepository {
             if let Some(user_id) = params.user_id {
                 base_query += &format!(" AND tp.user_id = ${bind_idx}");
                 bind_values.push(QueryValue::Uuid(user_id));
+                bind_idx += 1;
             }
-        }
-
-        // Count query
-        let count_query = format!("SELECT COUNT(*) FROM ({}) AS filtered", base_query);
-
-        // Add ordering and pagination
-        if let Some(params) = params {
-            if let Some(order_by) = &params.order_by {
-                let direction = params.order_direction.as_deref().unwrap_or("ASC");
-                base_query += &format!(" ORDER BY {} {}", order_by.to_string(), direction);
-            } else {
-                base_query += " ORDER BY call_date DESC";
+            if let Some(chain) = &params.chain {
+                base_query += &format!(" AND tp.token_chain = ${bind_idx}");
+                bind_values.push(QueryValue::Text(chain.to_string()));
+                bind_idx += 1;
             }
-
-            if !params.get_all {
-                let offset = (params.page - 1) * params.limit;
-                base_query += &format!(" LIMIT {} OFFSET {}", params.limit, offset);
+            if let Some(milestone) = &params.milestone {
+                base_query += &format!(" AND tp.milestone_hits ? ${bind_idx}");
+                bind_values.push(QueryValue::Text(milestone.to_string()));
+            }
+            if let Some(call_type) = &params.call_type {
+                base_query += &format!(" AND {}", call_type.filter_expression());
             }
         }
 
-        let mut tx = self.db.begin().await?;
-
-        // Execute count query with bindings
-        let mut count_builder = sqlx::query_scalar(&count_query);
-        for value in &bind_values {
-            count_builder = match value {
-                QueryValue::Uuid(uuid) => count_builder.bind(uuid),
-                QueryValue::Int64Array(arr) => count_builder.bind(arr),
-                QueryValue::Timestamp(ts) => count_builder.bind(ts),
-            };
-        }
-        let total: i64 = count_builder.fetch_one(&mut *tx).await?;
-
-        // Execute main query with bindings
-        let mut query_builder = sqlx::query_as::<_, TokenPick>(&base_query);
-        for value in &bind_values {
-            query_builder = match value {
-                QueryValue::Uuid(uuid) => query_builder.bind(uuid),
-                QueryValue::Int64Array(arr) => query_builder.bind(arr),
-                QueryValue::Timestamp(ts) => query_builder.bind(ts),
-            };
-        }
-
-        let picks = query_builder.fetch_all(&mut *tx).await?;
-        tx.commit().await?;
-
-        Ok((picks, total))
+        self.fetch_token_picks_page(base_query, bind_values, params)
+            .await
     }
 
     pub async fn get_token_pick_by_id(&self, id: i64) -> Result<Option<TokenPick>, sqlx::Error> {
@@ -405,7 +551,7 @@ impl TokenRepository {
                    row_to_json(u) AS user,
                    {GROUP_JSON_BUILDER} as group
             FROM social.token_picks tp
-            JOIN social.tokens t ON tp.token_address = t.address
+            JOIN social.tokens t ON tp.token_address = t.address AND tp.token_chain = t.chain
             JOIN public.user u ON tp.user_id = u.id
             JOIN social.groups g ON tp.group_id = g.id
             WHERE tp.id = $1
@@ -453,6 +599,31 @@ impl TokenRepository {
     }
 
     pub async fn save_token_pick(&self, pick: TokenPick) -> Result<TokenPick, sqlx::Error> {
+        self.insert_token_pick(pick, true).await
+    }
+
+    /// Saves a historical pick without announcing it to the followers of its caller
+    pub async fn save_imported_token_pick(
+        &self,
+        pick: TokenPick,
+    ) -> Result<TokenPick, sqlx::Error> {
+        self.insert_token_pick(pick, false).await
+    }
+
+    async fn insert_token_pick(
+        &self,
+        pick: TokenPick,
+        notify: bool,
+    ) -> Result<TokenPick, sqlx::Error> {
+        let mut tx = self.db.begin().await?;
+        if !notify {
+            // Checked by the notify trigger of the insert, reset when the transaction ends
+            sqlx::query("SELECT set_config('social.suppress_pick_notifications', 'on', true)")
+                .execute(&mut *tx)
+                .await?;
+        }
+        Self::lock_token_first_call(&mut tx, &pick.token.address, &pick.token.chain).await?;
+
         let query = r#"
 			INSERT INTO social.token_picks (
 				user_id,
@@ -465,9 +636,11 @@ impl TokenRepository {
 				supply_at_call,
 				call_date,
 				highest_market_cap,
-				hit_date
+				hit_date,
+				token_chain,
+				qualification_policy_id
 			)
-			VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+			VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
 			RETURNING id
 		"#;
         let result = sqlx::query_as::<_, From>(query)
@@ -482,8 +655,13 @@ impl TokenRepository {
             .bind(pick.call_date)
             .bind(pick.highest_market_cap)
             .bind(pick.hit_date)
-            .fetch_one(self.db.as_ref())
+            .bind(pick.token.chain.clone())
+            .bind(pick.qualification_policy_id)
+            .fetch_one(&mut *tx)
             .await?;
+
+        Self::update_first_call_in(&mut tx, &pick.token.address, &pick.token.chain).await?;
+        tx.commit().await?;
         if result.id == 1 {
             info!("Successfully saved token pick with id {}", pick.id);
         }
@@ -493,6 +671,61 @@ impl TokenRepository {
         Ok(token_pick.unwrap_or_default())
     }
 
+    /// Points every pick of the token at its earliest pick across every group. Picks can be
+    /// created with a past call date, so the first call is recomputed rather than assumed.
+    pub async fn update_first_call(&self, address: &str, chain: &str) -> Result<(), sqlx::Error> {
+        let mut tx = self.db.begin().await?;
+        Self::lock_token_first_call(&mut tx, address, chain).await?;
+        Self::update_first_call_in(&mut tx, address, chain).await?;
+        tx.commit().await
+    }
+
+    /// Serializes the first call updates of a token until the transaction ends, so concurrent
+    /// picks of the token agree on its first call
+    async fn lock_token_first_call(
+        tx: &mut Transaction<'_, Postgres>,
+        address: &str,
+        chain: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtext($1 || ':' || $2))")
+            .bind(address)
+            .bind(chain)
+            .execute(&mut **tx)
+            .await?;
+        Ok(())
+    }
+
+    async fn update_first_call_in(
+        tx: &mut Transaction<'_, Postgres>,
+        address: &str,
+        chain: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            UPDATE social.token_picks tp
+            SET first_call_pick_id = fc.id,
+                first_call_date = fc.call_date
+            FROM (
+                SELECT id, call_date
+                FROM social.token_picks
+                WHERE token_address = $1 AND token_chain = $2
+                AND deleted_at IS NULL
+                ORDER BY call_date, id
+                LIMIT 1
+            ) fc
+            WHERE tp.token_address = $1
+            AND tp.token_chain = $2
+            AND tp.first_call_pick_id IS DISTINCT FROM fc.id
+            "#,
+        )
+        .bind(address)
+        .bind(chain)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
     pub async fn update_highest_market_cap(
         &self,
         pick_id: i64,
@@ -519,25 +752,56 @@ impl TokenRepository {
         Ok(())
     }
 
-    pub async fn count_user_picks_in_period(
+    /// Call dates of the `limit` most recent picks in the scope, newest first. Deleted picks are
+    /// included, so cancelling a pick doesn't free up the limit.
+    pub async fn list_pick_dates_in_period(
         &self,
         scope: UserPickLimitScope,
-    ) -> Result<i64, AppError> {
+        limit: i64,
+    ) -> Result<Vec<DateTime<FixedOffset>>, AppError> {
         let query = match scope {
-            UserPickLimitScope::User(user_id, time_period) => sqlx::query_scalar(
+            UserPickLimitScope::User(user_id, since) => sqlx::query_scalar(
                 r#"
-                    SELECT COUNT(*)
+                    SELECT call_date
                     FROM social.token_picks
                     WHERE user_id = $1
                     AND call_date >= $2
+                    ORDER BY call_date DESC
+                    LIMIT $3
+                    "#,
+            )
+            .bind(user_id)
+            .bind(since),
+            UserPickLimitScope::UserInGroup(user_id, group_id, since) => sqlx::query_scalar(
+                r#"
+                    SELECT call_date
+                    FROM social.token_picks
+                    WHERE user_id = $1
+                    AND group_id = $2
+                    AND call_date >= $3
+                    ORDER BY call_date DESC
+                    LIMIT $4
                     "#,
             )
             .bind(user_id)
-            .bind(time_period.get_start_datetime().fixed_offset()),
+            .bind(group_id)
+            .bind(since),
+            UserPickLimitScope::Group(group_id, since) => sqlx::query_scalar(
+                r#"
+                    SELECT call_date
+                    FROM social.token_picks
+                    WHERE group_id = $1
+                    AND call_date >= $2
+                    ORDER BY call_date DESC
+                    LIMIT $3
+                    "#,
+            )
+            .bind(group_id)
+            .bind(since),
         };
 
-        let count = query.fetch_one(self.db.as_ref()).await?;
-        Ok(count)
+        let dates = query.bind(limit).fetch_all(self.db.as_ref()).await?;
+        Ok(dates)
     }
 
     pub async fn get_unprocessed_token_picks(
@@ -551,11 +815,11 @@ impl TokenRepository {
                    row_to_json(u) AS user,
                    {GROUP_JSON_BUILDER} as group
             FROM social.token_picks tp
-            JOIN social.tokens t ON tp.token_address = t.address
+            JOIN social.tokens t ON tp.token_address = t.address AND tp.token_chain = t.chain
             JOIN public.user u ON tp.user_id = u.id
             JOIN social.groups g ON tp.group_id = g.id
-            WHERE tp.highest_market_cap IS NULL
-               OR tp.hit_date IS NULL
+            WHERE (tp.highest_market_cap IS NULL OR tp.hit_date IS NULL)
+            {ACTIVE_TOKEN_PICKS_FILTER}
             ORDER BY tp.call_date DESC
             LIMIT $1
         "#,
@@ -570,10 +834,11 @@ impl TokenRepository {
     pub async fn get_all_tokens_with_picks_group_by_group_id(
         &self,
         since: DateTime<Utc>,
-    ) -> Result<HashMap<String, Vec<TokenPick>>, sqlx::Error> {
+    ) -> Result<HashMap<(Chain, String), Vec<TokenPick>>, AppError> {
         let query = format!(
             r#"
            SELECT t.address,
+                   t.chain,
                    json_agg(
                        json_build_object(
                            'id', tp.id,
@@ -588,16 +853,24 @@ impl TokenRepository {
                            'supply_at_call', tp.supply_at_call,
                            'call_date', tp.call_date,
                            'highest_market_cap', tp.highest_market_cap,
+                           'highest_market_cap_date', tp.highest_market_cap_date,
                            'highest_multiplier', tp.highest_multiplier,
-                           'hit_date', tp.hit_date
+                           'hit_date', tp.hit_date,
+                           'milestone_hits', tp.milestone_hits,
+                           'lowest_market_cap', tp.lowest_market_cap,
+                           'max_drawdown', tp.max_drawdown,
+                           'qualification_policy_id', tp.qualification_policy_id,
+                           'first_call_pick_id', tp.first_call_pick_id,
+                           'first_call_date', tp.first_call_date
                        )
                    ) as picks
             FROM social.tokens t
-            JOIN social.token_picks tp ON t.address = tp.token_address
+            JOIN social.token_picks tp ON t.address = tp.token_address AND t.chain = tp.token_chain
             JOIN public.user u ON tp.user_id = u.id
             JOIN social.groups g ON tp.group_id = g.id
             WHERE tp.call_date >= $1
-            GROUP BY t.address
+            {ACTIVE_TOKEN_PICKS_FILTER}
+            GROUP BY t.address, t.chain
         "#,
         );
 
@@ -606,12 +879,10 @@ impl TokenRepository {
             .fetch_all(self.db.as_ref())
             .await?;
 
-        let result = groups
+        groups
             .into_iter()
-            .map(|group| (group.address, group.picks))
-            .collect();
-
-        Ok(result)
+            .map(|group| Ok(((Chain::try_from(group.chain)?, group.address), group.picks)))
+            .collect()
     }
 
     pub async fn bulk_update_token_picks(
@@ -622,9 +893,14 @@ impl TokenRepository {
             return Ok(());
         }
 
+        // Picks can get a new low and drawdown without a new high
         let filtered_picks: Vec<_> = picks
             .iter()
-            .filter(|p| p.highest_mc_post_call.is_some())
+            .filter(|p| {
+                p.highest_mc_post_call.is_some()
+                    || p.lowest_mc_post_call.is_some()
+                    || p.max_drawdown.is_some()
+            })
             .collect();
 
         if filtered_picks.is_empty() {
@@ -633,15 +909,36 @@ impl TokenRepository {
 
         // Build the VALUES part of the query dynamically
         let value_placeholders: Vec<String> = (0..filtered_picks.len())
-            .map(|i| format!("(${},${},${})", i * 3 + 1, i * 3 + 2, i * 3 + 3))
+            .map(|i| {
+                format!(
+                    "(${}::numeric,${},${},${}::jsonb,${}::numeric,${}::numeric,${}::timestamptz)",
+                    i * 7 + 1,
+                    i * 7 + 2,
+                    i * 7 + 3,
+                    i * 7 + 4,
+                    i * 7 + 5,
+                    i * 7 + 6,
+                    i * 7 + 7
+                )
+            })
             .collect();
 
+        // Milestones already stored keep their first hit date. GREATEST and LEAST ignore nulls, so
+        // values a pick did not get keep the stored ones.
         let query = format!(
             r#"
             UPDATE social.token_picks AS t
-            SET highest_market_cap = v.highest_mc,
-                hit_date = v.hit_date
-            FROM (VALUES {}) AS v(highest_mc, hit_date, id)
+            SET highest_market_cap_date = CASE
+                    WHEN COALESCE(v.highest_mc, 0) > COALESCE(t.highest_market_cap, 0)
+                    THEN COALESCE(v.highest_mc_date, t.highest_market_cap_date)
+                    ELSE t.highest_market_cap_date
+                END,
+                highest_market_cap = GREATEST(t.highest_market_cap, v.highest_mc),
+                hit_date = COALESCE(t.hit_date, v.hit_date),
+                milestone_hits = v.milestone_hits || t.milestone_hits,
+                lowest_market_cap = LEAST(t.lowest_market_cap, v.lowest_mc),
+                max_drawdown = GREATEST(t.max_drawdown, v.max_drawdown)
+            FROM (VALUES {}) AS v(highest_mc, hit_date, id, milestone_hits, lowest_mc, max_drawdown, highest_mc_date)
             WHERE t.id = v.id
             "#,
             value_placeholders.join(",")
@@ -652,9 +949,13 @@ impl TokenRepository {
         // Bind all values in order
         for pick in filtered_picks {
             query_builder = query_builder
-                .bind(pick.highest_mc_post_call.unwrap())
+                .bind(pick.highest_mc_post_call)
                 .bind(pick.hit_date)
-                .bind(pick.id);
+                .bind(pick.id)
+                .bind(Json(PickMilestoneHit::to_hits(&pick.milestones)))
+                .bind(pick.lowest_mc_post_call)
+                .bind(pick.max_drawdown)
+                .bind(pick.highest_mc_post_call_date);
         }
 
         query_builder.execute(self.db.as_ref()).await?;
@@ -667,6 +968,7 @@ impl TokenRepository {
         group_id: i64,
         timeframe: &TimePeriod,
         limit: i64,
+        chain: Option<&Chain>,
     ) -> Result<Vec<TokenPick>, sqlx::Error> {
         let query = format!(
             r#"
@@ -675,11 +977,13 @@ impl TokenRepository {
                    row_to_json(u) AS user,
                    row_to_json(g) AS group
             FROM social.token_picks tp
-            JOIN social.tokens t ON tp.token_address = t.address
+            JOIN social.tokens t ON tp.token_address = t.address AND tp.token_chain = t.chain
             JOIN public.user u ON tp.user_id = u.id
             JOIN social.groups g ON tp.group_id = g.id
             WHERE tp.group_id = $1
             AND tp.call_date >= $2
+            AND ($4::varchar IS NULL OR tp.token_chain = $4)
+            {ACTIVE_TOKEN_PICKS_FILTER}
             {TOKEN_PICKS_FILTER_WITH_NULLS}
             {QUALIFIED_TOKEN_PICKS_FILTER}
             ORDER BY tp.highest_multiplier DESC
@@ -691,6 +995,7 @@ impl TokenRepository {
             .bind(group_id)
             .bind(timeframe.get_start_datetime().fixed_offset())
             .bind(limit)
+            .bind(chain.map(|c| c.to_string()))
             .fetch_all(self.db.as_ref())
             .await
     }
@@ -699,12 +1004,14 @@ impl TokenRepository {
         let query = r#"
         SELECT COUNT(*)
         FROM social.token_picks tp
-		JOIN social.tokens t ON tp.token_address = t.address
+		JOIN social.tokens t ON tp.token_address = t.address AND tp.token_chain = t.chain
+		JOIN social.qualification_policies qp ON tp.qualification_policy_id = qp.id
         WHERE tp.user_id = $1
+        AND tp.deleted_at IS NULL
         AND (tp.highest_market_cap < tp.market_cap_at_call * 2
-		OR t.volume_24h < 20000
-		OR t.liquidity < 10000
-		OR t.market_cap < 30000)
+		OR t.volume_24h < qp.bust_min_volume
+		OR t.liquidity < qp.bust_min_liquidity
+		OR t.market_cap < qp.bust_min_market_cap)
         "#;
 
         let count = sqlx::query_scalar(&query)
@@ -727,6 +1034,7 @@ impl TokenRepository {
 			WHERE telegram_message_id = $1
 			AND telegram_id = $2
 			AND group_id = $3
+			AND deleted_at IS NULL
 		"#,
         )
         .bind(telegram_message_id)
@@ -736,17 +1044,134 @@ impl TokenRepository {
         .await
     }
 
-    pub async fn delete_token_pick(&self, id: i64) -> Result<(), sqlx::Error> {
-        sqlx::query(r#"DELETE FROM social.token_picks WHERE id = $1"#)
-            .bind(id)
-            .execute(self.db.as_ref())
-            .await?;
+    /// Soft deletes the pick and records it in its audit trail. Returns false if the pick was
+    /// already deleted.
+    pub async fn delete_token_pick(
+        &self,
+        id: i64,
+        actor: &str,
+        reason: Option<&str>,
+    ) -> Result<bool, sqlx::Error> {
+        let mut tx = self.db.begin().await?;
+
+        let deleted = sqlx::query(
+            r#"
+            UPDATE social.token_picks
+            SET deleted_at = CURRENT_TIMESTAMP,
+                deleted_by = $2,
+                deletion_reason = $3
+            WHERE id = $1
+            AND deleted_at IS NULL
+            "#,
+        )
+        .bind(id)
+        .bind(actor)
+        .bind(reason)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected()
+            > 0;
+        if deleted {
+            Self::insert_pick_audit(&mut tx, id, PickAuditAction::Deleted, actor, reason).await?;
+        }
+
+        tx.commit().await?;
+        Ok(deleted)
+    }
+
+    /// Restores a soft deleted pick and records it in its audit trail. Returns false if the pick
+    /// was not deleted.
+    pub async fn restore_token_pick(
+        &self,
+        id: i64,
+        actor: &str,
+        reason: Option<&str>,
+    ) -> Result<bool, sqlx::Error> {
+        let mut tx = self.db.begin().await?;
+
+        let restored = sqlx::query(
+            r#"
+            UPDATE social.token_picks
+            SET deleted_at = NULL,
+                deleted_by = NULL,
+                deletion_reason = NULL
+            WHERE id = $1
+            AND deleted_at IS NOT NULL
+            "#,
+        )
+        .bind(id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected()
+            > 0;
+        if restored {
+            Self::insert_pick_audit(&mut tx, id, PickAuditAction::Restored, actor, reason).await?;
+        }
+
+        tx.commit().await?;
+        Ok(restored)
+    }
+
+    async fn insert_pick_audit(
+        tx: &mut Transaction<'_, Postgres>,
+        id: i64,
+        action: PickAuditAction,
+        actor: &str,
+        reason: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO social.token_pick_audit_log (token_pick_id, action, actor, reason)
+            VALUES ($1, $2, $3, $4)
+            "#,
+        )
+        .bind(id)
+        .bind(action.to_string())
+        .bind(actor)
+        .bind(reason)
+        .execute(&mut **tx)
+        .await?;
+
         Ok(())
     }
 
+    /// Soft deleted picks, most recently deleted first, starting after the deletion date and id
+    /// of the last pick of the previous page
+    pub async fn list_deleted_token_picks(
+        &self,
+        limit: i64,
+        after: Option<(DateTime<FixedOffset>, i64)>,
+    ) -> Result<Vec<DeletedTokenPick>, sqlx::Error> {
+        let (after_date, after_id) = after.unzip();
+        let query = format!(
+            r#"
+            SELECT tp.*,
+                   row_to_json(t) AS token,
+                   row_to_json(u) AS user,
+                   {GROUP_JSON_BUILDER} as group
+            FROM social.token_picks tp
+            JOIN social.tokens t ON tp.token_address = t.address AND tp.token_chain = t.chain
+            JOIN public.user u ON tp.user_id = u.id
+            JOIN social.groups g ON tp.group_id = g.id
+            WHERE tp.deleted_at IS NOT NULL
+            AND ($2::timestamptz IS NULL OR (tp.deleted_at, tp.id) < ($2, $3))
+            ORDER BY tp.deleted_at DESC, tp.id DESC
+            LIMIT $1
+            "#
+        );
+
+        sqlx::query_as::<_, DeletedTokenPick>(&query)
+            .bind(limit)
+            .bind(after_date)
+            .bind(after_id)
+            .fetch_all(self.db.as_ref())
+            .await
+    }
+
     pub async fn check_if_token_already_called_in_timeframe(
         &self,
         address: &str,
+        chain: &Chain,
         group_id: i64,
         call_date: DateTime<Utc>,
     ) -> Result<Option<TokenPick>, sqlx::Error> {
@@ -757,41 +1182,603 @@ impl TokenRepository {
                    row_to_json(u) AS user,
                    row_to_json(g) AS group
             FROM social.token_picks tp
-            JOIN social.tokens t ON tp.token_address = t.address
+            JOIN social.tokens t ON tp.token_address = t.address AND tp.token_chain = t.chain
             JOIN public.user u ON tp.user_id = u.id
             JOIN social.groups g ON tp.group_id = g.id
             WHERE tp.token_address = $1
             AND tp.group_id = $2
             AND tp.call_date >= $3
+            AND tp.token_chain = $4
+            AND tp.deleted_at IS NULL
 			LIMIT 1"#,
         )
         .bind(address)
         .bind(group_id)
         .bind(call_date)
+        .bind(chain.to_string())
         .fetch_optional(self.db.as_ref())
         .await
     }
 
+    /// Whether the token was already called in the group between `from` and `to`
+    pub async fn token_called_between(
+        &self,
+        address: &str,
+        chain: &Chain,
+        group_id: i64,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM social.token_picks
+                WHERE token_address = $1
+                AND token_chain = $2
+                AND group_id = $3
+                AND call_date BETWEEN $4 AND $5
+                AND deleted_at IS NULL
+            )
+            "#,
+        )
+        .bind(address)
+        .bind(chain.to_string())
+        .bind(group_id)
+        .bind(from)
+        .bind(to)
+        .fetch_one(self.db.as_ref())
+        .await
+    }
+
     pub async fn get_token_pick_by_address(
         &self,
         address: &str,
+        chain: &Chain,
     ) -> Result<Option<TokenPickRow>, sqlx::Error> {
         sqlx::query_as::<_, TokenPickRow>(
             r#"SELECT * FROM social.token_picks
 				WHERE token_address = $1
+				AND token_chain = $2
 				LIMIT 1"#,
         )
         .bind(address)
+        .bind(chain.to_string())
         .fetch_optional(self.db.as_ref())
         .await
     }
+
+    /// Tokens whose symbol, name or address starts with `prefix`, most picked and most recently
+    /// picked first
+    pub async fn search_tokens(
+        &self,
+        prefix: &str,
+        chain: Option<&Chain>,
+        limit: i64,
+    ) -> Result<Vec<TokenSearchRow>, sqlx::Error> {
+        let pattern = format!(
+            "{}%",
+            prefix
+                .to_lowercase()
+                .replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_")
+        );
+
+        sqlx::query_as::<_, TokenSearchRow>(
+            r#"
+            SELECT t.*,
+                   COUNT(tp.id) AS pick_count,
+                   MAX(tp.call_date) AS last_pick_date
+            FROM social.tokens t
+            LEFT JOIN social.token_picks tp ON tp.token_address = t.address
+                AND tp.token_chain = t.chain
+                AND tp.deleted_at IS NULL
+            WHERE (lower(t.symbol) LIKE $1 OR lower(t.name) LIKE $1 OR lower(t.address) LIKE $1)
+            AND ($2::text IS NULL OR t.chain = $2)
+            GROUP BY t.address, t.chain
+            ORDER BY pick_count DESC, last_pick_date DESC NULLS LAST, t.market_cap DESC NULLS LAST
+            LIMIT $3
+            "#,
+        )
+        .bind(pattern)
+        .bind(chain.map(|c| c.to_string()))
+        .bind(limit)
+        .fetch_all(self.db.as_ref())
+        .await
+    }
+
+    /// The latest `per_token` picks of each token, newest first
+    pub async fn list_latest_picks_of_tokens(
+        &self,
+        tokens: &[(String, String)],
+        per_token: i64,
+    ) -> Result<Vec<TokenPick>, sqlx::Error> {
+        let (addresses, chains): (Vec<String>, Vec<String>) = tokens.iter().cloned().unzip();
+
+        sqlx::query_as::<_, TokenPick>(&format!(
+            r#"
+            SELECT * FROM (
+                SELECT tp.*,
+                       row_to_json(t) AS token,
+                       CASE
+                           WHEN g.settings->>'privacy' = 'anonymous' THEN NULL
+                           ELSE row_to_json(u)
+                       END AS user,
+                       row_to_json(g) AS group,
+                       ROW_NUMBER() OVER (
+                           PARTITION BY tp.token_address, tp.token_chain
+                           ORDER BY tp.call_date DESC
+                       ) AS pick_rank
+                FROM social.token_picks tp
+                JOIN social.tokens t ON tp.token_address = t.address AND tp.token_chain = t.chain
+                JOIN public.user u ON tp.user_id = u.id
+                JOIN social.groups g ON tp.group_id = g.id
+                WHERE (tp.token_address, tp.token_chain) IN (
+                    SELECT * FROM UNNEST($1::text[], $2::text[])
+                )
+                {ACTIVE_TOKEN_PICKS_FILTER}
+                {TOKEN_PICKS_FILTER_WITH_NULLS}
+            ) ranked
+            WHERE pick_rank <= $3
+            ORDER BY call_date DESC
+            "#
+        ))
+        .bind(addresses)
+        .bind(chains)
+        .bind(per_token)
+        .fetch_all(self.db.as_ref())
+        .await
+    }
+
+    /// Every pick of a token across groups, oldest first
+    pub async fn list_picks_of_token(
+        &self,
+        address: &str,
+        chain: &Chain,
+    ) -> Result<Vec<TokenPick>, sqlx::Error> {
+        sqlx::query_as::<_, TokenPick>(
+            r#"
+            SELECT tp.*,
+                   row_to_json(t) AS token,
+                   CASE
+                       WHEN g.settings->>'privacy' = 'anonymous' THEN NULL
+                       ELSE row_to_json(u)
+                   END AS user,
+                   row_to_json(g) AS group
+            FROM social.token_picks tp
+            JOIN social.tokens t ON tp.token_address = t.address AND tp.token_chain = t.chain
+            JOIN public.user u ON tp.user_id = u.id
+            JOIN social.groups g ON tp.group_id = g.id
+            WHERE tp.token_address = $1
+            AND tp.token_chain = $2
+            AND tp.deleted_at IS NULL
+            ORDER BY tp.call_date, tp.id
+            "#,
+        )
+        .bind(address)
+        .bind(chain.to_string())
+        .fetch_all(self.db.as_ref())
+        .await
+    }
+
+    /// Who made each pick of a token, including picks made in anonymous groups
+    pub async fn list_token_callers(
+        &self,
+        address: &str,
+        chain: &Chain,
+    ) -> Result<Vec<TokenCallerRow>, sqlx::Error> {
+        sqlx::query_as::<_, TokenCallerRow>(
+            r#"
+            SELECT id AS pick_id, user_id, group_id
+            FROM social.token_picks
+            WHERE token_address = $1
+            AND token_chain = $2
+            AND deleted_at IS NULL
+            "#,
+        )
+        .bind(address)
+        .bind(chain.to_string())
+        .fetch_all(self.db.as_ref())
+        .await
+    }
+
+    pub async fn list_token_picks_in_scope(
+        &self,
+        scope: &TokenPickScope,
+    ) -> Result<Vec<TokenPick>, sqlx::Error> {
+        let condition = match scope {
+            TokenPickScope::Pick(_) => "tp.id = $1",
+            TokenPickScope::User(_) => "tp.user_id = $1",
+            TokenPickScope::Window(_, _) => "tp.call_date >= $1 AND tp.call_date < $2",
+        };
+        let query = format!(
+            r#"
+            SELECT tp.*,
+                   row_to_json(t) AS token,
+                   row_to_json(u) AS user,
+                   {GROUP_JSON_BUILDER} as group
+            FROM social.token_picks tp
+            JOIN social.tokens t ON tp.token_address = t.address AND tp.token_chain = t.chain
+            JOIN public.user u ON tp.user_id = u.id
+            JOIN social.groups g ON tp.group_id = g.id
+            WHERE {condition}
+            ORDER BY tp.call_date ASC
+            "#
+        );
+
+        let query_builder = sqlx::query_as::<_, TokenPick>(&query);
+        let query_builder = match scope {
+            TokenPickScope::Pick(id) => query_builder.bind(*id),
+            TokenPickScope::User(user_id) => query_builder.bind(*user_id),
+            TokenPickScope::Window(from, to) => query_builder.bind(*from).bind(*to),
+        };
+
+        query_builder.fetch_all(self.db.as_ref()).await
+    }
+
+    /// Active picks of the users made since `picked_after`, highest multiplier first
+    pub async fn list_token_picks_of_users(
+        &self,
+        user_ids: &[Uuid],
+        picked_after: DateTime<FixedOffset>,
+    ) -> Result<Vec<TokenPick>, sqlx::Error> {
+        self.list_token_picks_of_users_with(user_ids, picked_after, "row_to_json(u)")
+            .await
+    }
+
+    /// Like [Self::list_token_picks_of_users], without the caller of picks made in anonymous
+    /// groups, for picks shown to other users
+    pub async fn list_public_token_picks_of_users(
+        &self,
+        user_ids: &[Uuid],
+        picked_after: DateTime<FixedOffset>,
+    ) -> Result<Vec<TokenPick>, sqlx::Error> {
+        self.list_token_picks_of_users_with(user_ids, picked_after, PUBLIC_USER_JSON)
+            .await
+    }
+
+    async fn list_token_picks_of_users_with(
+        &self,
+        user_ids: &[Uuid],
+        picked_after: DateTime<FixedOffset>,
+        user_column: &str,
+    ) -> Result<Vec<TokenPick>, sqlx::Error> {
+        let query = format!(
+            r#"
+            SELECT tp.*,
+                   row_to_json(t) AS token,
+                   {user_column} AS user,
+                   {GROUP_JSON_BUILDER} as group,
+                   {QUALIFIED_TOKEN_PICK_COLUMN}
+            FROM social.token_picks tp
+            JOIN social.tokens t ON tp.token_address = t.address AND tp.token_chain = t.chain
+            JOIN public.user u ON tp.user_id = u.id
+            JOIN social.groups g ON tp.group_id = g.id
+            WHERE tp.user_id = ANY($1)
+            AND tp.call_date >= $2
+            {ACTIVE_TOKEN_PICKS_FILTER}
+            {TOKEN_PICKS_FILTER_WITH_NULLS}
+            ORDER BY COALESCE(tp.highest_multiplier, -1) DESC, tp.id DESC
+            "#
+        );
+
+        sqlx::query_as::<_, TokenPick>(&query)
+            .bind(user_ids)
+            .bind(picked_after)
+            .fetch_all(self.db.as_ref())
+            .await
+    }
+
+    /// Latest active picks of `user_ids` made at or before `before` outside anonymous groups,
+    /// newest first
+    pub async fn list_picks_of_users_before(
+        &self,
+        user_ids: &[Uuid],
+        before: DateTime<FixedOffset>,
+        limit: i64,
+    ) -> Result<Vec<TokenPick>, sqlx::Error> {
+        let query = format!(
+            r#"
+            SELECT tp.*,
+                   row_to_json(t) AS token,
+                   {PUBLIC_USER_JSON} AS user,
+                   {GROUP_JSON_BUILDER} as group
+            FROM social.token_picks tp
+            JOIN social.tokens t ON tp.token_address = t.address AND tp.token_chain = t.chain
+            JOIN public.user u ON tp.user_id = u.id
+            JOIN social.groups g ON tp.group_id = g.id
+            WHERE tp.user_id = ANY($1)
+            AND tp.call_date <= $2
+            {ACTIVE_TOKEN_PICKS_FILTER}
+            {PUBLIC_GROUP_PICKS_FILTER}
+            ORDER BY tp.call_date DESC, tp.id DESC
+            LIMIT $3
+            "#
+        );
+
+        sqlx::query_as::<_, TokenPick>(&query)
+            .bind(user_ids)
+            .bind(before)
+            .bind(limit)
+            .fetch_all(self.db.as_ref())
+            .await
+    }
+
+    /// Latest active picks made in `group_ids` at or before `before` by users other than
+    /// `excluded_user_ids`, newest first, without the caller in anonymous groups
+    pub async fn list_group_picks_before(
+        &self,
+        group_ids: &[i64],
+        excluded_user_ids: &[Uuid],
+        before: DateTime<FixedOffset>,
+        limit: i64,
+    ) -> Result<Vec<TokenPick>, sqlx::Error> {
+        let query = format!(
+            r#"
+            SELECT tp.*,
+                   row_to_json(t) AS token,
+                   {PUBLIC_USER_JSON} AS user,
+                   {GROUP_JSON_BUILDER} as group
+            FROM social.token_picks tp
+            JOIN social.tokens t ON tp.token_address = t.address AND tp.token_chain = t.chain
+            JOIN public.user u ON tp.user_id = u.id
+            JOIN social.groups g ON tp.group_id = g.id
+            WHERE tp.group_id = ANY($1)
+            AND tp.user_id <> ALL($2)
+            AND tp.call_date <= $3
+            {ACTIVE_TOKEN_PICKS_FILTER}
+            ORDER BY tp.call_date DESC, tp.id DESC
+            LIMIT $4
+            "#
+        );
+
+        sqlx::query_as::<_, TokenPick>(&query)
+            .bind(group_ids)
+            .bind(excluded_user_ids)
+            .bind(before)
+            .bind(limit)
+            .fetch_all(self.db.as_ref())
+            .await
+    }
+
+    /// Active picks of `user_ids` outside anonymous groups that reached a milestone at or before
+    /// `before`, by their latest such milestone, newest first
+    pub async fn list_milestone_picks_of_users_before(
+        &self,
+        user_ids: &[Uuid],
+        before: DateTime<FixedOffset>,
+        limit: i64,
+    ) -> Result<Vec<TokenPick>, sqlx::Error> {
+        let query = format!(
+            r#"
+            SELECT tp.*,
+                   row_to_json(t) AS token,
+                   {PUBLIC_USER_JSON} AS user,
+                   {GROUP_JSON_BUILDER} as group
+            FROM social.token_picks tp
+            JOIN social.tokens t ON tp.token_address = t.address AND tp.token_chain = t.chain
+            JOIN public.user u ON tp.user_id = u.id
+            JOIN social.groups g ON tp.group_id = g.id
+            CROSS JOIN LATERAL (
+                SELECT MAX(m.value::timestamptz) AS hit_at
+                FROM jsonb_each_text(tp.milestone_hits) m
+                WHERE m.value::timestamptz <= $2
+            ) last_hit
+            WHERE tp.user_id = ANY($1)
+            AND last_hit.hit_at IS NOT NULL
+            {ACTIVE_TOKEN_PICKS_FILTER}
+            {PUBLIC_GROUP_PICKS_FILTER}
+            ORDER BY last_hit.hit_at DESC, tp.id DESC
+            LIMIT $3
+            "#
+        );
+
+        sqlx::query_as::<_, TokenPick>(&query)
+            .bind(user_ids)
+            .bind(before)
+            .bind(limit)
+            .fetch_all(self.db.as_ref())
+            .await
+    }
+
+    /// Active picks made by users in `[from, to)`, in `group_id` or every group if empty
+    pub async fn list_token_picks_between(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        group_id: Option<i64>,
+    ) -> Result<Vec<TokenPick>, sqlx::Error> {
+        let query = format!(
+            r#"
+            SELECT tp.*,
+                   row_to_json(t) AS token,
+                   row_to_json(u) AS user,
+                   {GROUP_JSON_BUILDER} as group,
+                   {QUALIFIED_TOKEN_PICK_COLUMN}
+            FROM social.token_picks tp
+            JOIN social.tokens t ON tp.token_address = t.address AND tp.token_chain = t.chain
+            JOIN public.user u ON tp.user_id = u.id
+            JOIN social.groups g ON tp.group_id = g.id
+            WHERE tp.call_date >= $1
+            AND tp.call_date < $2
+            AND ($3::bigint IS NULL OR tp.group_id = $3)
+            {ACTIVE_TOKEN_PICKS_FILTER}
+            {TOKEN_PICKS_FILTER_WITH_NULLS}
+            ORDER BY COALESCE(tp.highest_multiplier, -1) DESC, tp.id DESC
+            "#
+        );
+
+        sqlx::query_as::<_, TokenPick>(&query)
+            .bind(from)
+            .bind(to)
+            .bind(group_id)
+            .fetch_all(self.db.as_ref())
+            .await
+    }
+
+    /// Overwrites the performance of a pick with values recomputed from its candles.
+    pub async fn save_backfilled_token_pick(&self, pick: &TokenPick) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            UPDATE social.token_picks
+            SET highest_market_cap = $1,
+                highest_market_cap_date = $2,
+                highest_multiplier = $3,
+                hit_date = $4,
+                milestone_hits = $5,
+                lowest_market_cap = $6,
+                max_drawdown = $7
+            WHERE id = $8
+            "#,
+        )
+        .bind(pick.highest_market_cap)
+        .bind(pick.highest_market_cap_date)
+        .bind(pick.highest_multiplier)
+        .bind(pick.hit_date)
+        .bind(Json(&pick.milestone_hits))
+        .bind(pick.lowest_market_cap)
+        .bind(pick.max_drawdown)
+        .bind(pick.id)
+        .execute(self.db.as_ref())
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn save_pick_snapshots(
+        &self,
+        snapshots: &[TokenPickSnapshot],
+    ) -> Result<(), sqlx::Error> {
+        if snapshots.is_empty() {
+            return Ok(());
+        }
+
+        let (pick_ids, prices, market_caps, liquidities, volumes, observed_ats): (
+            Vec<i64>,
+            Vec<Decimal>,
+            Vec<Decimal>,
+            Vec<Option<Decimal>>,
+            Vec<Option<Decimal>>,
+            Vec<DateTime<FixedOffset>>,
+        ) = snapshots.iter().fold(Default::default(), |mut acc, s| {
+            acc.0.push(s.token_pick_id);
+            acc.1.push(s.price);
+            acc.2.push(s.market_cap);
+            acc.3.push(s.liquidity);
+            acc.4.push(s.volume_24h);
+            acc.5.push(s.observed_at);
+            acc
+        });
+
+        sqlx::query(
+            r#"
+            INSERT INTO social.token_pick_snapshots
+                (token_pick_id, price, market_cap, liquidity, volume_24h, observed_at)
+            SELECT * FROM UNNEST($1::bigint[], $2::numeric[], $3::numeric[], $4::numeric[], $5::numeric[], $6::timestamptz[])
+            "#,
+        )
+        .bind(pick_ids)
+        .bind(prices)
+        .bind(market_caps)
+        .bind(liquidities)
+        .bind(volumes)
+        .bind(observed_ats)
+        .execute(self.db.as_ref())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Up to `limit` snapshots of a pick observed after `after`, oldest first
+    pub async fn list_pick_snapshots(
+        &self,
+        token_pick_id: i64,
+        after: Option<DateTime<FixedOffset>>,
+        limit: i64,
+    ) -> Result<Vec<TokenPickSnapshot>, sqlx::Error> {
+        sqlx::query_as::<_, TokenPickSnapshot>(
+            r#"
+            SELECT token_pick_id, price, market_cap, liquidity, volume_24h, observed_at
+            FROM social.token_pick_snapshots
+            WHERE token_pick_id = $1
+            AND ($2::timestamptz IS NULL OR observed_at > $2)
+            ORDER BY observed_at ASC
+            LIMIT $3
+            "#,
+        )
+        .bind(token_pick_id)
+        .bind(after)
+        .bind(limit)
+        .fetch_all(self.db.as_ref())
+        .await
+    }
+
+    /// Snapshots of every pick in `token_pick_ids`, oldest first
+    pub async fn list_pick_snapshots_of_picks(
+        &self,
+        token_pick_ids: &[i64],
+    ) -> Result<Vec<TokenPickSnapshot>, sqlx::Error> {
+        sqlx::query_as::<_, TokenPickSnapshot>(
+            r#"
+            SELECT token_pick_id, price, market_cap, liquidity, volume_24h, observed_at
+            FROM social.token_pick_snapshots
+            WHERE token_pick_id = ANY($1)
+            ORDER BY observed_at ASC
+            "#,
+        )
+        .bind(token_pick_ids)
+        .fetch_all(self.db.as_ref())
+        .await
+    }
+
+    /// Keeps only the latest snapshot per pick and hour for snapshots older than `before`.
+    pub async fn downsample_pick_snapshots(
+        &self,
+        before: DateTime<Utc>,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM social.token_pick_snapshots s
+            USING (
+                SELECT id,
+                       ROW_NUMBER() OVER (
+                           PARTITION BY token_pick_id, date_trunc('hour', observed_at)
+                           ORDER BY observed_at DESC
+                       ) AS rn
+                FROM social.token_pick_snapshots
+                WHERE observed_at < $1
+            ) ranked
+            WHERE s.id = ranked.id
+            AND ranked.rn > 1
+            "#,
+        )
+        .bind(before)
+        .execute(self.db.as_ref())
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    pub async fn delete_pick_snapshots_before(
+        &self,
+        before: DateTime<Utc>,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM social.token_pick_snapshots WHERE observed_at < $1")
+            .bind(before)
+            .execute(self.db.as_ref())
+            .await?;
+
+        Ok(result.rows_affected())
+    }
 }
 
 #[derive(Debug, sqlx::FromRow, Deserialize, Clone)]
 pub struct TokenPickRow {
     pub id: i64,
     pub group_id: i64,
+    pub user_id: Uuid,
     pub token_address: String,
+    pub token_chain: String,
     pub telegram_message_id: Option<i64>,
     pub telegram_id: Option<i64>,
     pub price_at_call: Decimal,
@@ -801,6 +1788,22 @@ pub struct TokenPickRow {
     pub highest_market_cap: Option<Decimal>,
     pub highest_multiplier: Option<Decimal>,
     pub hit_date: Option<DateTime<FixedOffset>>,
+    pub qualification_policy_id: i64,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct TokenSearchRow {
+    #[sqlx(flatten)]
+    pub token: Token,
+    pub pick_count: i64,
+    pub last_pick_date: Option<DateTime<FixedOffset>>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct TokenCallerRow {
+    pub pick_id: i64,
+    pub user_id: Uuid,
+    pub group_id: i64,
 }
 
 #[derive(Debug, sqlx::FromRow)]
@@ -808,8 +1811,34 @@ struct From {
     id: i64,
 }
 
+#[derive(sqlx::FromRow)]
+struct CursorTokenPick {
+    #[sqlx(flatten)]
+    pick: TokenPick,
+    cursor_key: String,
+}
+
 enum QueryValue {
     Timestamp(DateTime<FixedOffset>),
     Uuid(Uuid),
+    Int64(i64),
     Int64Array(Vec<i64>),
+    Text(String),
+}
+
+fn bind_query_values<'q, O>(
+    mut query: QueryAs<'q, Postgres, O, PgArguments>,
+    values: &'q [QueryValue],
+) -> QueryAs<'q, Postgres, O, PgArguments> {
+    for value in values {
+        query = match value {
+            QueryValue::Timestamp(ts) => query.bind(ts),
+            QueryValue::Uuid(uuid) => query.bind(uuid),
+            QueryValue::Int64(int) => query.bind(int),
+            QueryValue::Int64Array(arr) => query.bind(arr),
+            QueryValue::Text(text) => query.bind(text),
+        };
+    }
+
+    query
 }
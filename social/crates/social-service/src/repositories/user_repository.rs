@@ -1,4 +1,7 @@
-use crate::models::users::{SavedUser, User};
+use crate::models::{
+    groups::UserPickLimit,
+    users::{SavedUser, User},
+};
 use chrono::Utc;
 use sqlx::PgPool;
 use std::sync::Arc;
@@ -258,4 +261,50 @@ impl UserRepository {
         let user = self.find_by_telegram_user_id(user.telegram_id).await?;
         Ok(user)
     }
+
+    pub async fn find_pick_limit(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Option<UserPickLimit>, sqlx::Error> {
+        sqlx::query_as::<_, UserPickLimit>(
+            r#"
+            SELECT user_id, max_daily_picks, updated_at
+            FROM social.user_pick_limits
+            WHERE user_id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(self.db.as_ref())
+        .await
+    }
+
+    pub async fn upsert_pick_limit(
+        &self,
+        user_id: Uuid,
+        max_daily_picks: Option<i32>,
+    ) -> Result<UserPickLimit, sqlx::Error> {
+        sqlx::query_as::<_, UserPickLimit>(
+            r#"
+            INSERT INTO social.user_pick_limits (user_id, max_daily_picks)
+            VALUES ($1, $2)
+            ON CONFLICT (user_id) DO UPDATE
+            SET max_daily_picks = EXCLUDED.max_daily_picks,
+                updated_at = CURRENT_TIMESTAMP
+            RETURNING user_id, max_daily_picks, updated_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(max_daily_picks)
+        .fetch_one(self.db.as_ref())
+        .await
+    }
+
+    /// Returns whether the user had an override
+    pub async fn delete_pick_limit(&self, user_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM social.user_pick_limits WHERE user_id = $1")
+            .bind(user_id)
+            .execute(self.db.as_ref())
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
 }
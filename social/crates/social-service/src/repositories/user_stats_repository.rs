@@ -0,0 +1,192 @@
+use chrono::{DateTime, FixedOffset, Utc};
+use sqlx::PgPool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    models::user_stats::{UserStatsLeaderboardRow, UserStatsRecord},
+    repositories::token_repository::{ACTIVE_TOKEN_PICKS_FILTER, TOKEN_PICKS_FILTER_WITH_NULLS},
+};
+
+pub struct UserStatsRepository {
+    db: Arc<PgPool>,
+}
+
+impl UserStatsRepository {
+    pub fn new(db: Arc<PgPool>) -> Self {
+        UserStatsRepository { db }
+    }
+
+    /// Replaces every stats row of `user_ids` with `records`, so scopes the users no longer
+    /// picked in are dropped.
+    pub async fn replace_user_stats(
+        &self,
+        user_ids: &[Uuid],
+        records: &[UserStatsRecord],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.db.begin().await?;
+
+        sqlx::query("DELETE FROM social.user_stats WHERE user_id = ANY($1)")
+            .bind(user_ids)
+            .execute(&mut *tx)
+            .await?;
+
+        // Postgres accepts at most 65535 bind values per statement
+        const PARAMS_PER_ROW: usize = 18;
+        for chunk in records.chunks(u16::MAX as usize / PARAMS_PER_ROW) {
+            let value_indices: Vec<String> = (0..chunk.len())
+                .map(|i| {
+                    let start = i * PARAMS_PER_ROW + 1;
+                    let placeholders: Vec<String> = (start..start + PARAMS_PER_ROW)
+                        .map(|idx| format!("${idx}"))
+                        .collect();
+                    format!("({})", placeholders.join(","))
+                })
+                .collect();
+
+            let query = format!(
+                r#"
+                INSERT INTO social.user_stats (
+                    user_id,
+                    time_period,
+                    group_id,
+                    total_picks,
+                    hits,
+                    hit_rate,
+                    average_hit_return,
+                    pick_returns,
+                    average_pick_return,
+                    average_drawdown,
+                    best_pick,
+                    milestones,
+                    original_calls,
+                    original_call_rate,
+                    current_streak,
+                    longest_streak,
+                    consistency_score,
+                    last_pick_date
+                )
+                VALUES {}
+                "#,
+                value_indices.join(",")
+            );
+
+            let mut query_builder = sqlx::query(&query);
+            for record in chunk {
+                query_builder = query_builder
+                    .bind(record.user_id)
+                    .bind(&record.time_period)
+                    .bind(record.group_id)
+                    .bind(record.total_picks)
+                    .bind(record.hits)
+                    .bind(record.hit_rate)
+                    .bind(record.average_hit_return)
+                    .bind(record.pick_returns)
+                    .bind(record.average_pick_return)
+                    .bind(record.average_drawdown)
+                    .bind(&record.best_pick)
+                    .bind(&record.milestones)
+                    .bind(record.original_calls)
+                    .bind(record.original_call_rate)
+                    .bind(record.current_streak)
+                    .bind(record.longest_streak)
+                    .bind(record.consistency_score)
+                    .bind(record.last_pick_date);
+            }
+            query_builder.execute(&mut *tx).await?;
+        }
+
+        tx.commit().await
+    }
+
+    /// Stats of every user with picks in the time period and group, with their profiles.
+    /// `viewer_id` fills whether the viewer follows each user.
+    pub async fn list_user_stats(
+        &self,
+        time_period: &str,
+        group_id: i64,
+        viewer_id: Option<Uuid>,
+    ) -> Result<Vec<UserStatsLeaderboardRow>, sqlx::Error> {
+        sqlx::query_as::<_, UserStatsLeaderboardRow>(
+            r#"
+            SELECT us.*,
+                   u.username,
+                   u.image_uri,
+                   u.bio,
+                   CASE
+                       WHEN $3::uuid IS NULL OR $3 = us.user_id THEN NULL
+                       ELSE EXISTS (
+                           SELECT 1
+                           FROM social.user_follows uf
+                           WHERE uf.follower_id = $3 AND uf.followed_id = us.user_id
+                       )
+                   END AS is_following,
+                   COALESCE(up.total_points, 0) AS total_points
+            FROM social.user_stats us
+            JOIN public.user u ON us.user_id = u.id
+            LEFT JOIN social.user_points up ON us.user_id = up.user_id
+            WHERE us.time_period = $1
+            AND us.group_id = $2
+            "#,
+        )
+        .bind(time_period)
+        .bind(group_id)
+        .bind(viewer_id)
+        .fetch_all(self.db.as_ref())
+        .await
+    }
+
+    /// Users whose stats were not updated since `before`, least recently updated first
+    pub async fn list_stale_user_ids(
+        &self,
+        before: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"
+            SELECT user_id
+            FROM social.user_stats
+            WHERE time_period = 'all_time'
+            AND group_id = 0
+            AND updated_at < $1
+            ORDER BY updated_at
+            LIMIT $2
+            "#,
+        )
+        .bind(before)
+        .bind(limit)
+        .fetch_all(self.db.as_ref())
+        .await
+    }
+
+    /// Users with picks made since `picked_after` whose stats were never computed, among the
+    /// picks the stats are computed from
+    pub async fn list_user_ids_without_stats(
+        &self,
+        picked_after: DateTime<FixedOffset>,
+        limit: i64,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        let query = format!(
+            r#"
+            SELECT DISTINCT tp.user_id
+            FROM social.token_picks tp
+            JOIN social.tokens t ON tp.token_address = t.address AND tp.token_chain = t.chain
+            JOIN public.user u ON tp.user_id = u.id
+            JOIN social.groups g ON tp.group_id = g.id
+            WHERE tp.call_date >= $1
+            {ACTIVE_TOKEN_PICKS_FILTER}
+            {TOKEN_PICKS_FILTER_WITH_NULLS}
+            AND NOT EXISTS (
+                SELECT 1 FROM social.user_stats us WHERE us.user_id = tp.user_id
+            )
+            LIMIT $2
+            "#
+        );
+
+        sqlx::query_scalar(&query)
+            .bind(picked_after)
+            .bind(limit)
+            .fetch_all(self.db.as_ref())
+            .await
+    }
+}
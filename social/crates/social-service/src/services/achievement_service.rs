@@ -0,0 +1,175 @@
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    sync::Arc,
+};
+
+use chrono::{Duration, Utc};
+use tracing::{debug, info};
+use uuid::Uuid;
+
+use crate::{
+    apis::api_models::query::ProfileLeaderboardSort,
+    models::{
+        achievements::{AchievementCriteria, EarnedAchievement, UserAchievement, ACHIEVEMENTS},
+        rank_history::SnapshotResolution,
+        token_picks::TokenPick,
+    },
+    repositories::{
+        achievement_repository::AchievementRepository,
+        rank_history_repository::{LeaderboardKey, RankHistoryRepository},
+        token_repository::TokenRepository,
+    },
+    utils::{errors::app_error::AppError, time::TimePeriod},
+};
+
+/// Evaluates the [ACHIEVEMENTS] catalogue against the picks and leaderboard ranks of users.
+/// Achievements newly earned by users evaluated before are announced on Telegram by the
+/// `user_achievements` trigger, the first evaluation of a user saves their past achievements
+/// silently.
+pub struct AchievementService {
+    achievement_repository: Arc<AchievementRepository>,
+    token_repository: Arc<TokenRepository>,
+    rank_history_repository: Arc<RankHistoryRepository>,
+}
+
+impl AchievementService {
+    /// Users evaluated per batch
+    const EVALUATION_BATCH_SIZE: usize = 100;
+    /// Achievements earned longer ago than this are saved without being announced, such as
+    /// those revealed by a backfill
+    const ANNOUNCE_WITHIN_HOURS: i64 = 24;
+
+    pub fn new(
+        achievement_repository: Arc<AchievementRepository>,
+        token_repository: Arc<TokenRepository>,
+        rank_history_repository: Arc<RankHistoryRepository>,
+    ) -> Self {
+        Self {
+            achievement_repository,
+            token_repository,
+            rank_history_repository,
+        }
+    }
+
+    /// Saves every achievement the users earned that was not saved yet
+    pub async fn evaluate_user_achievements(&self, user_ids: &[Uuid]) -> Result<(), AppError> {
+        let user_ids: Vec<Uuid> = user_ids
+            .iter()
+            .copied()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+
+        for user_ids in user_ids.chunks(Self::EVALUATION_BATCH_SIZE) {
+            let now = Utc::now().into();
+            let picks = self
+                .token_repository
+                .list_token_picks_of_users(user_ids, TimePeriod::AllTime.to_date_time(now))
+                .await?;
+
+            let mut picks_by_user = HashMap::<Uuid, Vec<TokenPick>>::new();
+            for pick in picks {
+                if let Some(user_id) = pick.user.as_ref().map(|u| u.id) {
+                    picks_by_user.entry(user_id).or_default().push(pick);
+                }
+            }
+
+            let mut earned = Vec::new();
+            for (user_id, picks) in picks_by_user.iter_mut() {
+                picks.sort_by_key(|p| (p.call_date, p.id));
+                earned.extend(ACHIEVEMENTS.iter().filter_map(|achievement| {
+                    let (earned_at, pick_id) = achievement.earned_by_picks(picks, now)?;
+                    Some(UserAchievement {
+                        user_id: *user_id,
+                        achievement_id: achievement.id.to_string(),
+                        earned_at: earned_at.into(),
+                        token_pick_id: Some(pick_id),
+                        announce: false,
+                    })
+                }));
+            }
+
+            for achievement in &ACHIEVEMENTS {
+                let AchievementCriteria::LeaderboardRank {
+                    time_period,
+                    max_rank,
+                } = achievement.criteria
+                else {
+                    continue;
+                };
+                let resolution = SnapshotResolution::Daily.to_string();
+                let sort = ProfileLeaderboardSort::default().to_string();
+                let time_period = time_period.to_string();
+                let ranked = self
+                    .rank_history_repository
+                    .list_first_ranked_within(
+                        user_ids,
+                        &LeaderboardKey {
+                            resolution: &resolution,
+                            sort: &sort,
+                            time_period: &time_period,
+                        },
+                        max_rank,
+                    )
+                    .await?;
+                earned.extend(
+                    ranked
+                        .into_iter()
+                        .map(|(user_id, earned_at)| UserAchievement {
+                            user_id,
+                            achievement_id: achievement.id.to_string(),
+                            earned_at,
+                            token_pick_id: None,
+                            announce: false,
+                        }),
+                );
+            }
+
+            let evaluated: HashSet<Uuid> = self
+                .achievement_repository
+                .list_evaluated_user_ids(user_ids)
+                .await?
+                .into_iter()
+                .collect();
+            let announce_after = Utc::now() - Duration::hours(Self::ANNOUNCE_WITHIN_HOURS);
+            for achievement in &mut earned {
+                achievement.announce = evaluated.contains(&achievement.user_id)
+                    && achievement.earned_at >= announce_after;
+            }
+
+            let saved = self
+                .achievement_repository
+                .save_user_achievements(user_ids, &earned)
+                .await?;
+            for achievement in &saved {
+                info!(
+                    "User {} earned the {} achievement",
+                    achievement.user_id, achievement.achievement_id
+                );
+            }
+            debug!(
+                "Evaluated achievements of {} users, {} were earned",
+                user_ids.len(),
+                saved.len()
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Achievements the user earned, oldest first
+    pub async fn list_user_achievements(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<EarnedAchievement>, AppError> {
+        let achievements = self
+            .achievement_repository
+            .list_user_achievements(user_id)
+            .await?;
+
+        Ok(achievements
+            .into_iter()
+            .filter_map(EarnedAchievement::from_user_achievement)
+            .collect())
+    }
+}
@@ -0,0 +1,176 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use tracing::{debug, error, info, warn};
+
+use crate::{
+    external_services::birdeye::{ohlcv::BirdeyeOHLCVItem, BirdeyeService},
+    models::{token_picks::TokenPick, tokens::Chain},
+    repositories::token_repository::{TokenPickScope, TokenRepository},
+    utils::{errors::app_error::AppError, math::calculate_price_multiplier},
+};
+
+/// Recomputes pick performance by replaying Birdeye candles from the call date, catching the
+/// wicks and pumps the periodic job misses between its runs.
+pub struct BackfillService {
+    token_repository: Arc<TokenRepository>,
+    birdeye_service: Arc<BirdeyeService>,
+}
+
+impl BackfillService {
+    /// Picks younger than this are replayed with 1 minute candles, older ones with 5 minute candles
+    const ONE_MINUTE_RESOLUTION_HOURS: i64 = 24;
+    /// Birdeye returns at most this many candles per request
+    const MAX_CANDLES_PER_REQUEST: i64 = 1000;
+    /// Longest call date window a single backfill replays
+    const MAX_WINDOW_DAYS: i64 = 7;
+    /// Most picks a single backfill replays
+    const MAX_PICKS: usize = 500;
+
+    pub fn new(
+        token_repository: Arc<TokenRepository>,
+        birdeye_service: Arc<BirdeyeService>,
+    ) -> Self {
+        Self {
+            token_repository,
+            birdeye_service,
+        }
+    }
+
+    /// Picks to backfill in the scope, rejecting scopes too large to replay in one go
+    pub async fn list_picks(&self, scope: &TokenPickScope) -> Result<Vec<TokenPick>, AppError> {
+        if let TokenPickScope::Window(from, to) = scope {
+            if *to - *from > Duration::days(Self::MAX_WINDOW_DAYS) {
+                return Err(AppError::BadRequest(format!(
+                    "Backfill window can't be longer than {} days",
+                    Self::MAX_WINDOW_DAYS
+                )));
+            }
+        }
+
+        let picks = self
+            .token_repository
+            .list_token_picks_in_scope(scope)
+            .await?;
+        if picks.len() > Self::MAX_PICKS {
+            return Err(AppError::BadRequest(format!(
+                "Backfill scope holds {} picks, at most {} can be backfilled at once",
+                picks.len(),
+                Self::MAX_PICKS
+            )));
+        }
+
+        Ok(picks)
+    }
+
+    /// Backfills every pick, returning the number of picks that were updated.
+    pub async fn backfill_picks(&self, picks: Vec<TokenPick>) -> usize {
+        let total = picks.len();
+        let mut updated = 0;
+        for mut pick in picks {
+            match self.backfill_pick(&mut pick).await {
+                Ok(true) => updated += 1,
+                Ok(false) => debug!("No candles to replay for token pick {}", pick.id),
+                Err(e) => error!("Failed to backfill token pick {}: {}", pick.id, e),
+            }
+        }
+
+        info!("Backfilled {} of {} token picks", updated, total);
+        updated
+    }
+
+    pub async fn backfill_pick(&self, pick: &mut TokenPick) -> Result<bool, AppError> {
+        let Some(supply) = pick.supply_at_call.filter(|supply| !supply.is_zero()) else {
+            warn!("Token pick {} has no supply at call, skipping", pick.id);
+            return Ok(false);
+        };
+
+        let candles = self.fetch_candles(pick).await?;
+        if !replay_candles(pick, &candles, supply) {
+            return Ok(false);
+        }
+
+        self.token_repository
+            .save_backfilled_token_pick(pick)
+            .await?;
+
+        Ok(true)
+    }
+
+    async fn fetch_candles(&self, pick: &TokenPick) -> Result<Vec<BirdeyeOHLCVItem>, AppError> {
+        let now = Utc::now();
+        let call_date = pick.call_date.with_timezone(&Utc);
+        let (resolution, candle_seconds) =
+            if now - call_date <= Duration::hours(Self::ONE_MINUTE_RESOLUTION_HOURS) {
+                ("1m", 60)
+            } else {
+                ("5m", 300)
+            };
+        let chain = Chain::try_from(pick.token.chain.clone())?.to_string();
+        let window = candle_seconds * Self::MAX_CANDLES_PER_REQUEST;
+
+        let mut candles = Vec::new();
+        let mut time_from = call_date.timestamp();
+        while time_from < now.timestamp() {
+            let time_to = (time_from + window).min(now.timestamp());
+            candles.extend(
+                self.birdeye_service
+                    .get_ohlcv_items_request(
+                        &chain,
+                        &pick.token.address,
+                        time_from,
+                        time_to,
+                        resolution,
+                    )
+                    .await?,
+            );
+            time_from = time_to;
+        }
+
+        Ok(candles)
+    }
+}
+
+/// Replays `candles` (oldest first) over the pick, recomputing its peak, peak date, milestones
+/// and drawdown from scratch. Returns false when there is nothing to replay.
+fn replay_candles(pick: &mut TokenPick, candles: &[BirdeyeOHLCVItem], supply: Decimal) -> bool {
+    if candles.is_empty() {
+        return false;
+    }
+
+    pick.hit_date = None;
+    pick.milestone_hits.clear();
+    pick.lowest_market_cap = None;
+    pick.max_drawdown = None;
+
+    let mut peak = pick.market_cap_at_call;
+    let mut peak_date = pick.call_date;
+    for candle in candles {
+        let Some(candle_date) = DateTime::from_timestamp(candle.unix_time, 0) else {
+            continue;
+        };
+        let candle_date = candle_date.fixed_offset();
+
+        // The order of the high and low within a candle is unknown, so each low is measured
+        // against the highs of the previous candles
+        pick.record_low(candle.low * supply, peak);
+
+        let high = candle.high * supply;
+        if high > peak {
+            peak = high;
+            peak_date = candle_date;
+        }
+        pick.record_milestones(
+            calculate_price_multiplier(&pick.market_cap_at_call, &high),
+            candle_date,
+        );
+    }
+
+    pick.highest_market_cap = Some(peak);
+    pick.highest_market_cap_date = Some(peak_date);
+    pick.highest_multiplier =
+        Some(calculate_price_multiplier(&pick.market_cap_at_call, &peak).round_dp(2));
+
+    true
+}
@@ -0,0 +1,251 @@
+use std::sync::Arc;
+
+use arrow_array::{
+    ArrayRef, BooleanArray, Float32Array, Float64Array, Int64Array, RecordBatch, StringArray,
+    TimestampMillisecondArray,
+};
+use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use bytes::Bytes;
+use chrono::Utc;
+use futures::{stream, Stream, StreamExt};
+use parquet::arrow::ArrowWriter;
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+use tokio::sync::mpsc;
+use tracing::{error, info};
+
+use crate::{
+    apis::api_models::{
+        request::{ExportFormat, ExportTokenPicksQuery},
+        response::PickExportRow,
+    },
+    models::token_picks::TokenPickResponse,
+    repositories::token_repository::{ListTokenPicksParams, TokenPicksQuery, TokenRepository},
+    utils::errors::app_error::AppError,
+};
+
+use super::user_service::UserService;
+
+/// Exports token picks for analysis. Rows are streamed from the database and encoded in
+/// batches, so an export of the whole platform never sits in memory.
+pub struct ExportService {
+    token_repository: Arc<TokenRepository>,
+    user_service: Arc<UserService>,
+}
+
+impl ExportService {
+    const BATCH_SIZE: usize = 1000;
+    /// Encoded batches buffered ahead of a slow client
+    const CHANNEL_CAPACITY: usize = 4;
+
+    pub fn new(token_repository: Arc<TokenRepository>, user_service: Arc<UserService>) -> Self {
+        Self {
+            token_repository,
+            user_service,
+        }
+    }
+
+    pub async fn export_token_picks(
+        &self,
+        query: ExportTokenPicksQuery,
+    ) -> Result<impl Stream<Item = Result<Bytes, AppError>>, AppError> {
+        let user_id = match &query.username {
+            Some(username) => Some(
+                self.user_service
+                    .get_by_username(username)
+                    .await?
+                    .ok_or_else(|| AppError::NotFound("User not found".to_string()))?
+                    .id,
+            ),
+            None => None,
+        };
+        let params = ListTokenPicksParams {
+            user_id,
+            picked_after: query
+                .picked_after
+                .map(|t| t.to_date_time(Utc::now().into())),
+            after: None,
+            limit: 0,
+            order_by: query.order_by,
+            order_direction: query.order_direction,
+            get_all: true,
+            group_ids: query.group_ids,
+            following: false,
+            chain: query.chain,
+            milestone: query.milestone,
+            call_type: query.call_type,
+        };
+        let picks_query = TokenPicksQuery::new(&params, Some(query.qualified_only));
+        let mut encoder = PickEncoder::new(query.format)?;
+
+        let (tx, rx) = mpsc::channel(Self::CHANNEL_CAPACITY);
+        let token_repository = self.token_repository.clone();
+        tokio::spawn(async move {
+            let mut picks = token_repository
+                .stream_token_picks(&picks_query)
+                .chunks(Self::BATCH_SIZE);
+            let mut exported = 0;
+            while let Some(batch) = picks.next().await {
+                let rows = batch
+                    .into_iter()
+                    .map(|pick| pick.map(|p| TokenPickResponse::from(p).into()))
+                    .collect::<Result<Vec<PickExportRow>, _>>();
+                let bytes = rows.map_err(AppError::from).and_then(|rows| {
+                    exported += rows.len();
+                    encoder.encode(&rows)
+                });
+                let failed = bytes.is_err();
+                if tx.send(bytes).await.is_err() || failed {
+                    error!("Token picks export aborted after {} rows", exported);
+                    return;
+                }
+            }
+
+            let _ = tx.send(encoder.finish()).await;
+            info!("Exported {} token picks", exported);
+        });
+
+        Ok(stream::unfold(rx, |mut rx| async move {
+            rx.recv().await.map(|bytes| (bytes, rx))
+        }))
+    }
+}
+
+/// Encodes export rows batch by batch, returning the bytes each batch adds to the file
+enum PickEncoder {
+    Csv { has_headers: bool },
+    Ndjson,
+    Parquet(Box<ArrowWriter<Vec<u8>>>),
+}
+
+impl PickEncoder {
+    fn new(format: ExportFormat) -> Result<Self, AppError> {
+        Ok(match format {
+            ExportFormat::Csv => PickEncoder::Csv { has_headers: true },
+            ExportFormat::Ndjson => PickEncoder::Ndjson,
+            ExportFormat::Parquet => {
+                let writer = ArrowWriter::try_new(Vec::new(), parquet_schema(), None)
+                    .map_err(export_error)?;
+                PickEncoder::Parquet(Box::new(writer))
+            }
+        })
+    }
+
+    fn encode(&mut self, rows: &[PickExportRow]) -> Result<Bytes, AppError> {
+        match self {
+            PickEncoder::Csv { has_headers } => {
+                let mut writer = csv::WriterBuilder::new()
+                    .has_headers(*has_headers)
+                    .from_writer(Vec::new());
+                for row in rows {
+                    writer.serialize(row).map_err(export_error)?;
+                }
+                *has_headers = false;
+                let bytes = writer.into_inner().map_err(export_error)?;
+                Ok(Bytes::from(bytes))
+            }
+            PickEncoder::Ndjson => {
+                let mut bytes = Vec::new();
+                for row in rows {
+                    serde_json::to_writer(&mut bytes, row).map_err(export_error)?;
+                    bytes.push(b'\n');
+                }
+                Ok(Bytes::from(bytes))
+            }
+            PickEncoder::Parquet(writer) => {
+                writer.write(&parquet_batch(rows)?).map_err(export_error)?;
+                // Close the row group so its bytes can be sent before the next batch is read.
+                // The writer tracks offsets itself, so draining its buffer is safe.
+                writer.flush().map_err(export_error)?;
+                Ok(Bytes::from(std::mem::take(writer.inner_mut())))
+            }
+        }
+    }
+
+    /// Bytes closing the file, the Parquet footer
+    fn finish(self) -> Result<Bytes, AppError> {
+        match self {
+            PickEncoder::Csv { .. } | PickEncoder::Ndjson => Ok(Bytes::new()),
+            PickEncoder::Parquet(writer) => {
+                let bytes = writer.into_inner().map_err(export_error)?;
+                Ok(Bytes::from(bytes))
+            }
+        }
+    }
+}
+
+fn export_error(e: impl std::fmt::Display) -> AppError {
+    error!("Failed to encode token picks export: {}", e);
+    AppError::InternalServerError()
+}
+
+fn parquet_schema() -> SchemaRef {
+    let timestamp = DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into()));
+    Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Int64, false),
+        Field::new("chain", DataType::Utf8, false),
+        Field::new("token_address", DataType::Utf8, false),
+        Field::new("token_symbol", DataType::Utf8, false),
+        Field::new("username", DataType::Utf8, true),
+        Field::new("group_id", DataType::Int64, false),
+        Field::new("group_name", DataType::Utf8, false),
+        Field::new("call_date", timestamp.clone(), false),
+        Field::new("price_at_call", DataType::Float64, false),
+        Field::new("market_cap_at_call", DataType::Float64, false),
+        Field::new("current_market_cap", DataType::Float64, false),
+        Field::new("highest_market_cap", DataType::Float64, true),
+        Field::new("highest_market_cap_date", timestamp.clone(), true),
+        Field::new("highest_multiplier", DataType::Float32, false),
+        Field::new("hit_date", timestamp, true),
+        Field::new("lowest_market_cap", DataType::Float64, true),
+        Field::new("max_drawdown", DataType::Float64, true),
+        Field::new("is_original_call", DataType::Boolean, false),
+        Field::new("minutes_after_first_call", DataType::Int64, true),
+        Field::new("qualification_policy_id", DataType::Int64, false),
+    ]))
+}
+
+fn parquet_batch(rows: &[PickExportRow]) -> Result<RecordBatch, AppError> {
+    let int64 = |f: fn(&PickExportRow) -> Option<i64>| -> ArrayRef {
+        Arc::new(Int64Array::from_iter(rows.iter().map(f)))
+    };
+    let string = |f: fn(&PickExportRow) -> Option<&str>| -> ArrayRef {
+        Arc::new(StringArray::from_iter(rows.iter().map(f)))
+    };
+    let decimal = |f: fn(&PickExportRow) -> Option<Decimal>| -> ArrayRef {
+        Arc::new(Float64Array::from_iter(
+            rows.iter().map(|row| f(row).and_then(|d| d.to_f64())),
+        ))
+    };
+    let timestamp = |f: fn(&PickExportRow) -> Option<i64>| -> ArrayRef {
+        Arc::new(TimestampMillisecondArray::from_iter(rows.iter().map(f)).with_timezone("UTC"))
+    };
+
+    let columns = vec![
+        int64(|r| Some(r.id)),
+        string(|r| Some(r.chain.as_str())),
+        string(|r| Some(r.token_address.as_str())),
+        string(|r| Some(r.token_symbol.as_str())),
+        string(|r| r.username.as_deref()),
+        int64(|r| Some(r.group_id)),
+        string(|r| Some(r.group_name.as_str())),
+        timestamp(|r| Some(r.call_date.timestamp_millis())),
+        decimal(|r| Some(r.price_at_call)),
+        decimal(|r| Some(r.market_cap_at_call)),
+        decimal(|r| Some(r.current_market_cap)),
+        decimal(|r| r.highest_market_cap),
+        timestamp(|r| r.highest_market_cap_date.map(|d| d.timestamp_millis())),
+        Arc::new(Float32Array::from_iter_values(
+            rows.iter().map(|r| r.highest_multiplier),
+        )),
+        timestamp(|r| r.hit_date.map(|d| d.timestamp_millis())),
+        decimal(|r| r.lowest_market_cap),
+        decimal(|r| r.max_drawdown),
+        Arc::new(BooleanArray::from_iter(
+            rows.iter().map(|r| Some(r.is_original_call)),
+        )),
+        int64(|r| r.minutes_after_first_call),
+        int64(|r| Some(r.qualification_policy_id)),
+    ];
+
+    RecordBatch::try_new(parquet_schema(), columns).map_err(export_error)
+}
@@ -0,0 +1,301 @@
+use std::{cmp::Reverse, sync::Arc};
+
+use async_trait::async_trait;
+use chrono::{DateTime, FixedOffset, Utc};
+use futures::future::try_join_all;
+use uuid::Uuid;
+
+use crate::{
+    apis::api_models::query::FeedQuery,
+    models::{feed::FeedItem, token_picks::TokenPickResponse},
+    repositories::{token_repository::TokenRepository, user_repository::UserRepository},
+    utils::{
+        cursor::{page_after, Cursor, MAX_PAGE_SIZE},
+        errors::app_error::AppError,
+    },
+};
+
+use super::group_service::GroupService;
+
+/// Cursor of a feed page, the date, pick id and id of its last item
+type FeedCursor = Cursor<DateTime<FixedOffset>, (i64, String)>;
+
+const FEED_CURSOR_SORT: &str = "feed";
+
+/// Who the feed is built for
+pub struct FeedViewer {
+    pub user_id: Uuid,
+    /// Users the viewer follows
+    pub following: Vec<Uuid>,
+    /// Groups the viewer is a member of
+    pub group_ids: Vec<i64>,
+}
+
+/// Lists one kind of feed event. New event types are added to the feed by implementing a source
+/// and registering it with [FeedService::with_source].
+#[async_trait]
+pub trait FeedSource: Send + Sync {
+    /// Up to `limit` of the viewer's events that occurred at or before `before`, newest first
+    async fn list_items(
+        &self,
+        viewer: &FeedViewer,
+        before: DateTime<FixedOffset>,
+        limit: i64,
+    ) -> Result<Vec<FeedItem>, AppError>;
+}
+
+/// Picks of the users the viewer follows
+pub struct FollowedPicksSource {
+    token_repository: Arc<TokenRepository>,
+}
+
+#[async_trait]
+impl FeedSource for FollowedPicksSource {
+    async fn list_items(
+        &self,
+        viewer: &FeedViewer,
+        before: DateTime<FixedOffset>,
+        limit: i64,
+    ) -> Result<Vec<FeedItem>, AppError> {
+        if viewer.following.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let picks = self
+            .token_repository
+            .list_picks_of_users_before(&viewer.following, before, limit)
+            .await?;
+        Ok(picks
+            .into_iter()
+            .map(|pick| FeedItem::followed_pick(pick.into()))
+            .collect())
+    }
+}
+
+/// Milestones reached by the picks of the users the viewer follows
+pub struct MilestoneHitsSource {
+    token_repository: Arc<TokenRepository>,
+}
+
+#[async_trait]
+impl FeedSource for MilestoneHitsSource {
+    async fn list_items(
+        &self,
+        viewer: &FeedViewer,
+        before: DateTime<FixedOffset>,
+        limit: i64,
+    ) -> Result<Vec<FeedItem>, AppError> {
+        if viewer.following.is_empty() {
+            return Ok(vec![]);
+        }
+
+        // Every pick holds at least one hit, so the latest `limit` hits are among the hits of
+        // the `limit` picks with the latest hits
+        let picks = self
+            .token_repository
+            .list_milestone_picks_of_users_before(&viewer.following, before, limit)
+            .await?;
+        Ok(picks
+            .into_iter()
+            .map(TokenPickResponse::from)
+            .flat_map(|pick| {
+                pick.milestones
+                    .iter()
+                    .filter(|hit| hit.hit_at <= before)
+                    .map(|hit| FeedItem::milestone_hit(pick.clone(), hit.milestone, hit.hit_at))
+                    .collect::<Vec<_>>()
+            })
+            .collect())
+    }
+}
+
+/// Picks made in the viewer's groups by users the viewer does not follow, as the picks of the
+/// followed users are already in the feed
+pub struct GroupPicksSource {
+    token_repository: Arc<TokenRepository>,
+}
+
+#[async_trait]
+impl FeedSource for GroupPicksSource {
+    async fn list_items(
+        &self,
+        viewer: &FeedViewer,
+        before: DateTime<FixedOffset>,
+        limit: i64,
+    ) -> Result<Vec<FeedItem>, AppError> {
+        if viewer.group_ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut excluded_user_ids = viewer.following.clone();
+        excluded_user_ids.push(viewer.user_id);
+        let picks = self
+            .token_repository
+            .list_group_picks_before(&viewer.group_ids, &excluded_user_ids, before, limit)
+            .await?;
+        Ok(picks
+            .into_iter()
+            .map(|pick| FeedItem::group_pick(pick.into()))
+            .collect())
+    }
+}
+
+/// Builds the viewer's feed by merging the events of every [FeedSource], newest first
+pub struct FeedService {
+    user_repository: Arc<UserRepository>,
+    group_service: Arc<GroupService>,
+    sources: Vec<Box<dyn FeedSource>>,
+}
+
+impl FeedService {
+    const DEFAULT_PAGE_SIZE: u32 = 20;
+
+    pub fn new(
+        user_repository: Arc<UserRepository>,
+        group_service: Arc<GroupService>,
+        token_repository: Arc<TokenRepository>,
+    ) -> Self {
+        Self {
+            user_repository,
+            group_service,
+            sources: vec![],
+        }
+        .with_source(FollowedPicksSource {
+            token_repository: token_repository.clone(),
+        })
+        .with_source(MilestoneHitsSource {
+            token_repository: token_repository.clone(),
+        })
+        .with_source(GroupPicksSource { token_repository })
+    }
+
+    pub fn with_source(mut self, source: impl FeedSource + 'static) -> Self {
+        self.sources.push(Box::new(source));
+        self
+    }
+
+    /// The number of items a page of the feed holds
+    pub fn page_size(query: &FeedQuery) -> u32 {
+        query
+            .limit
+            .unwrap_or(Self::DEFAULT_PAGE_SIZE)
+            .clamp(1, MAX_PAGE_SIZE)
+    }
+
+    /// A page of the viewer's feed, starting after `query.cursor`
+    pub async fn get_feed(
+        &self,
+        query: &FeedQuery,
+    ) -> Result<(Vec<FeedItem>, Option<String>), AppError> {
+        let after = query
+            .cursor
+            .as_deref()
+            .map(|c| FeedCursor::decode(c, FEED_CURSOR_SORT))
+            .transpose()?;
+        let limit = Self::page_size(query);
+
+        let viewer = FeedViewer {
+            user_id: query.user_id,
+            following: self
+                .user_repository
+                .get_following(query.user_id)
+                .await?
+                .into_iter()
+                .map(|u| u.id)
+                .collect(),
+            group_ids: self
+                .group_service
+                .get_user_groups(query.user_id)
+                .await?
+                .into_iter()
+                .map(|g| g.id)
+                .collect(),
+        };
+
+        // Sources list from the cursor date on, so each lists the item at the cursor, which is
+        // skipped below, a page and one more item telling whether more follow
+        let before = after.as_ref().map_or(Utc::now().fixed_offset(), |c| c.key);
+        let mut items: Vec<FeedItem> = try_join_all(
+            self.sources
+                .iter()
+                .map(|source| source.list_items(&viewer, before, limit as i64 + 2)),
+        )
+        .await?
+        .into_iter()
+        .flatten()
+        .collect();
+        items.sort_by_cached_key(feed_position);
+
+        let after = after.map(|c| (Reverse(c.key), Reverse(c.id.0), Reverse(c.id.1)));
+        let (items, has_more) = page_after(items, after.as_ref(), limit as usize, feed_position);
+        let next_cursor = items.last().filter(|_| has_more).map(|last| {
+            FeedCursor::new(
+                FEED_CURSOR_SORT,
+                last.occurred_at,
+                (last.pick().id, last.id.clone()),
+            )
+            .encode()
+        });
+
+        Ok((items, next_cursor))
+    }
+}
+
+/// Where an item is in the feed: newest first, then by pick id as the sources list them, then by
+/// id between the events of a pick
+fn feed_position(
+    item: &FeedItem,
+) -> (
+    Reverse<DateTime<FixedOffset>>,
+    Reverse<i64>,
+    Reverse<String>,
+) {
+    (
+        Reverse(item.occurred_at),
+        Reverse(item.pick().id),
+        Reverse(item.id.clone()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn followed_pick(id: i64, call_date: DateTime<FixedOffset>) -> FeedItem {
+        FeedItem::followed_pick(TokenPickResponse {
+            id,
+            call_date,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_feed_position_orders_pick_ids_numerically() {
+        let now = Utc::now().fixed_offset();
+        let mut items = vec![followed_pick(99, now), followed_pick(100, now)];
+        items.sort_by_cached_key(feed_position);
+
+        assert_eq!(items[0].pick().id, 100);
+        assert_eq!(items[1].pick().id, 99);
+    }
+
+    #[test]
+    fn test_feed_page_after_cursor_at_same_date() {
+        let now = Utc::now().fixed_offset();
+        let mut items: Vec<_> = [8, 9, 10, 11]
+            .into_iter()
+            .map(|id| followed_pick(id, now))
+            .collect();
+        items.sort_by_cached_key(feed_position);
+
+        let (page, has_more) = page_after(items.clone(), None, 2, feed_position);
+        assert!(has_more);
+        let after = feed_position(page.last().unwrap());
+        let (page, has_more) = page_after(items, Some(&after), 2, feed_position);
+        assert!(!has_more);
+        assert_eq!(
+            page.iter().map(|i| i.pick().id).collect::<Vec<_>>(),
+            vec![9, 8]
+        );
+    }
+}
@@ -1,6 +1,7 @@
-use std::sync::Arc;
+use std::{cmp::Reverse, sync::Arc};
 
 use bytes::Bytes;
+use rust_decimal::Decimal;
 use tracing::error;
 use uuid::Uuid;
 
@@ -13,17 +14,30 @@ use crate::{
         },
         profile_handlers::ProfileQuery,
     },
-    models::groups::{CreateOrUpdateGroup, Group, GroupUser},
+    models::{
+        groups::{CreateOrUpdateGroup, Group, GroupUser},
+        profiles::ProfileDetailsResponse,
+        token_picks::PickMilestone,
+    },
     repositories::group_repository::GroupRepository,
-    utils::{errors::app_error::AppError, time::TimePeriod},
+    utils::{
+        cursor::{page_after, Cursor, MAX_PAGE_SIZE},
+        errors::app_error::AppError,
+        time::TimePeriod,
+    },
 };
 
 use super::{
-    profile_service::ProfileService, s3_service::S3Service,
-    telegram_service::TeloxideTelegramBotApi, user_service::UserService,
+    profile_service::{leaderboard_position, ProfileService},
+    s3_service::S3Service,
+    telegram_service::TeloxideTelegramBotApi,
+    user_service::UserService,
 };
 use futures::future::join_all;
 
+/// Cursor of a group members page, the sort key and username of its last member
+type MemberCursor = Cursor<Decimal, String>;
+
 pub struct GroupService {
     repository: Arc<GroupRepository>,
     user_service: Arc<UserService>,
@@ -181,18 +195,42 @@ impl GroupService {
             .map_err(|e| AppError::DatabaseError(e))
     }
 
+    /// A page of the group's members with their profiles, ranked by `sort` or by username.
+    /// A `limit` of 0 returns every member.
     pub async fn list_group_members(
         &self,
         group_id: i64,
         limit: u32,
-        page: u32,
+        cursor: Option<String>,
         sort: Option<ProfileLeaderboardSort>,
         username: Option<String>,
     ) -> Result<GroupMembersResponse, AppError> {
-        let (group_members, group_name, total) = self
-            .repository
-            .list_group_members(group_id, limit, page, sort.is_some())
-            .await?;
+        let Some(profile_service) = self.profile_service.as_ref() else {
+            return Err(AppError::InternalServerError());
+        };
+        let cursor_sort = format!("members:{:?}", sort);
+        let cursor = cursor
+            .map(|c| MemberCursor::decode(&c, &cursor_sort))
+            .transpose()?;
+        let limit = limit.min(MAX_PAGE_SIZE);
+
+        // Members are ranked on their profile, so a sorted page needs every member
+        let (group_members, group_name, total) = match sort {
+            Some(_) => {
+                self.repository
+                    .list_group_members(group_id, None, None)
+                    .await?
+            }
+            None => {
+                self.repository
+                    .list_group_members(
+                        group_id,
+                        (limit > 0).then_some(limit + 1),
+                        cursor.as_ref().map(|c| c.id.as_str()),
+                    )
+                    .await?
+            }
+        };
 
         let user = if let Some(username) = username {
             self.user_service.get_by_username(&username).await?
@@ -200,56 +238,44 @@ impl GroupService {
             None
         };
         let user_id = user.map(|u| u.id);
-        if let Some(profile_service) = &self.profile_service.as_ref() {
-            let profiles = join_all(group_members.iter().map(|g| {
-                profile_service.get_profile(
-                    ProfileQuery {
-                        username: g.username.clone(),
-                        picked_after: TimePeriod::AllTime,
-                        group_ids: Some(vec![group_id]),
-                    },
-                    user_id,
-                )
-            }))
-            .await
-            .into_iter()
-            .collect::<Result<Vec<_>, _>>()?;
-
-            let mut group_members_response = GroupMembersResponse {
-                members: profiles,
-                group_name,
-                group_id,
-                total,
-            };
+        let mut profiles = join_all(group_members.iter().map(|g| {
+            profile_service.get_profile(
+                ProfileQuery {
+                    username: g.username.clone(),
+                    picked_after: TimePeriod::AllTime,
+                    group_ids: Some(vec![group_id]),
+                    chain: None,
+                    call_type: None,
+                },
+                user_id,
+            )
+        }))
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()?;
 
-            if let Some(sort) = sort {
-                group_members_response.members.sort_by(|a, b| match sort {
-                    ProfileLeaderboardSort::PickReturns => b
-                        .pick_summary
-                        .pick_returns
-                        .cmp(&a.pick_summary.pick_returns),
-                    ProfileLeaderboardSort::HitRate => {
-                        b.pick_summary.hit_rate.cmp(&a.pick_summary.hit_rate)
-                    }
-                    ProfileLeaderboardSort::RealizedProfit => b
-                        .pick_summary
-                        .realized_profit
-                        .cmp(&a.pick_summary.realized_profit),
-                    ProfileLeaderboardSort::TotalPicks => {
-                        b.pick_summary.total_picks.cmp(&a.pick_summary.total_picks)
-                    }
-                    ProfileLeaderboardSort::AverageReturn => b
-                        .pick_summary
-                        .average_pick_return
-                        .cmp(&a.pick_summary.average_pick_return),
-                    _ => a.username.cmp(&b.username),
-                });
-            }
+        let position = |profile: &ProfileDetailsResponse| {
+            leaderboard_position(sort, profile, PickMilestone::default())
+        };
+        profiles.sort_by_cached_key(|profile| position(profile));
+        let after = match (sort, cursor) {
+            (Some(_), Some(cursor)) => Some((Reverse(cursor.key), cursor.id)),
+            // The repository already started after the cursor
+            _ => None,
+        };
+        let (members, has_more) = page_after(profiles, after.as_ref(), limit as usize, position);
+        let next_cursor = members.last().filter(|_| has_more).map(|last| {
+            let (Reverse(key), username) = position(last);
+            MemberCursor::new(&cursor_sort, key, username).encode()
+        });
 
-            Ok(group_members_response)
-        } else {
-            Err(AppError::InternalServerError())
-        }
+        Ok(GroupMembersResponse {
+            members,
+            group_name,
+            group_id,
+            total,
+            next_cursor,
+        })
     }
 
     pub async fn group_exists(&self, group_id: i64) -> Result<bool, AppError> {
@@ -0,0 +1,559 @@
+use std::{collections::HashMap, sync::Arc};
+
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use sqlx::types::Json;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::{
+    apis::api_models::{
+        request::{AddUserRequest, ImportFormat, ImportTokenPickRow},
+        response::{
+            ImportJobResponse, ImportJobStatus, ImportRowReport, ImportRowStatus,
+            ImportTokenPicksResponse,
+        },
+    },
+    external_services::{
+        birdeye::BirdeyeService, rust_monorepo::get_latest_w_metadata::LatestTokenMetadataResponse,
+    },
+    models::{token_picks::TokenPick, tokens::Chain},
+    repositories::token_repository::TokenRepository,
+    utils::{errors::app_error::AppError, redis_keys::RedisKeys},
+};
+
+use super::{
+    backfill_service::BackfillService, group_service::GroupService,
+    qualification_policy_service::QualificationPolicyService, redis_service::RedisService,
+    token_service::TokenService, user_service::UserService,
+};
+
+/// A parsed import row and its line
+type ImportRow = (usize, Result<ImportTokenPickRow, String>);
+
+/// Imports historical calls tracked outside the bot, resolving users and groups like a live pick
+/// and pricing each call from the OHLCV candle of its timestamp. Imports run in the background
+/// and are not announced to followers.
+pub struct ImportService {
+    token_service: Arc<TokenService>,
+    user_service: Arc<UserService>,
+    group_service: Arc<GroupService>,
+    qualification_policy_service: Arc<QualificationPolicyService>,
+    backfill_service: Arc<BackfillService>,
+    token_repository: Arc<TokenRepository>,
+    birdeye_service: Arc<BirdeyeService>,
+    redis_service: Arc<RedisService>,
+}
+
+impl ImportService {
+    const MAX_ROWS: usize = 5000;
+    /// How long the status and report of an import are kept
+    const JOB_TTL_SECONDS: u64 = 60 * 60 * 24 * 7;
+    const METADATA_BATCH_SIZE: usize = 50;
+    /// A token can only be called once per group in this window, as with live picks
+    const DUPLICATE_WINDOW_HOURS: i64 = 24;
+
+    pub fn new(
+        token_service: Arc<TokenService>,
+        user_service: Arc<UserService>,
+        group_service: Arc<GroupService>,
+        qualification_policy_service: Arc<QualificationPolicyService>,
+        backfill_service: Arc<BackfillService>,
+        token_repository: Arc<TokenRepository>,
+        birdeye_service: Arc<BirdeyeService>,
+        redis_service: Arc<RedisService>,
+    ) -> Self {
+        Self {
+            token_service,
+            user_service,
+            group_service,
+            qualification_policy_service,
+            backfill_service,
+            token_repository,
+            birdeye_service,
+            redis_service,
+        }
+    }
+
+    /// Parses the rows and imports them in the background. The returned job is polled with
+    /// [Self::get_import_job] until its report is ready.
+    pub async fn start_import(
+        self: Arc<Self>,
+        format: ImportFormat,
+        body: &str,
+        dry_run: bool,
+    ) -> Result<ImportJobResponse, AppError> {
+        let rows = parse_rows(format, body);
+        if rows.len() > Self::MAX_ROWS {
+            return Err(AppError::BadRequest(format!(
+                "Imports are limited to {} rows",
+                Self::MAX_ROWS
+            )));
+        }
+
+        let job = ImportJobResponse {
+            id: Uuid::new_v4(),
+            status: ImportJobStatus::Running,
+            dry_run,
+            total_rows: rows.len(),
+            started_at: Utc::now(),
+            finished_at: None,
+            error: None,
+            report: None,
+        };
+        self.save_job(&job).await?;
+
+        let mut finished = job.clone();
+        tokio::spawn(async move {
+            match self.import_token_picks(rows, dry_run).await {
+                Ok(report) => {
+                    finished.status = ImportJobStatus::Completed;
+                    finished.report = Some(report);
+                }
+                Err(e) => {
+                    error!(
+                        "Failed to import token picks of import {}: {}",
+                        finished.id, e
+                    );
+                    finished.status = ImportJobStatus::Failed;
+                    finished.error = Some(e.to_string());
+                }
+            }
+            finished.finished_at = Some(Utc::now());
+            if let Err(e) = self.save_job(&finished).await {
+                error!("Failed to save import {}: {}", finished.id, e);
+            }
+        });
+
+        Ok(job)
+    }
+
+    pub async fn get_import_job(&self, import_id: Uuid) -> Result<ImportJobResponse, AppError> {
+        self.redis_service
+            .get_cached(&RedisKeys::get_import_job_key(&import_id))
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Import {} not found", import_id)))
+    }
+
+    /// Parses the rows and imports them, returning the report once every row is processed
+    pub async fn run_import(
+        &self,
+        format: ImportFormat,
+        body: &str,
+        dry_run: bool,
+    ) -> Result<ImportTokenPicksResponse, AppError> {
+        self.import_token_picks(parse_rows(format, body), dry_run)
+            .await
+    }
+
+    async fn save_job(&self, job: &ImportJobResponse) -> Result<(), AppError> {
+        self.redis_service
+            .set_cached(
+                &RedisKeys::get_import_job_key(&job.id),
+                job,
+                Self::JOB_TTL_SECONDS,
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn import_token_picks(
+        &self,
+        rows: Vec<ImportRow>,
+        dry_run: bool,
+    ) -> Result<ImportTokenPicksResponse, AppError> {
+        let metadata = self.fetch_metadata(&rows).await;
+        let mut imported: Vec<TokenPick> = Vec::new();
+        let mut seen: Vec<(Chain, String, i64, DateTime<Utc>)> = Vec::new();
+        let mut reports = Vec::with_capacity(rows.len());
+        for (line, row) in rows {
+            let mut report = ImportRowReport {
+                row: line,
+                status: ImportRowStatus::Invalid,
+                address: None,
+                chain: None,
+                market_cap_at_call: None,
+                pick_id: None,
+                new_user: false,
+                new_group: false,
+                errors: Vec::new(),
+                warnings: Vec::new(),
+            };
+
+            match row {
+                Ok(row) => {
+                    if let Err(e) = self
+                        .import_row(&row, &metadata, &mut seen, &mut report, dry_run)
+                        .await
+                        .map(|pick| imported.extend(pick))
+                    {
+                        report.status = ImportRowStatus::Invalid;
+                        report.errors.push(e.to_string());
+                    }
+                }
+                Err(e) => report.errors.push(e),
+            }
+            reports.push(report);
+        }
+
+        let valid_rows = reports
+            .iter()
+            .filter(|r| matches!(r.status, ImportRowStatus::Valid | ImportRowStatus::Imported))
+            .count();
+        info!(
+            "Imported {} of {} token pick rows, dry run: {}",
+            imported.len(),
+            reports.len(),
+            dry_run
+        );
+
+        let response = ImportTokenPicksResponse {
+            dry_run,
+            total_rows: reports.len(),
+            valid_rows,
+            imported_rows: imported.len(),
+            rows: reports,
+        };
+
+        if !imported.is_empty() {
+            let backfill_service = self.backfill_service.clone();
+            tokio::spawn(async move {
+                backfill_service.backfill_picks(imported).await;
+            });
+        }
+
+        Ok(response)
+    }
+
+    /// Validates a row and, unless in a dry run, saves it. Returns the created pick.
+    async fn import_row(
+        &self,
+        row: &ImportTokenPickRow,
+        metadata: &HashMap<(Chain, String), LatestTokenMetadataResponse>,
+        seen: &mut Vec<(Chain, String, i64, DateTime<Utc>)>,
+        report: &mut ImportRowReport,
+        dry_run: bool,
+    ) -> Result<Option<TokenPick>, AppError> {
+        report.address = Some(row.address.clone());
+        let chain = Chain::detect(&row.address, row.chain.as_deref()).ok_or_else(|| {
+            AppError::BadRequest(format!("Unsupported token address {}", row.address))
+        })?;
+        report.chain = Some(chain.clone());
+
+        if row.timestamp > Utc::now() {
+            return Err(AppError::BadRequest(
+                "Timestamp is in the future".to_string(),
+            ));
+        }
+
+        let token_info = metadata
+            .get(&(chain.clone(), row.address.clone()))
+            .ok_or_else(|| AppError::NotFound("Token info not found".to_string()))?;
+        let price_at_call = self.price_at(&chain, &row.address, row.timestamp).await?;
+        let current_supply = token_info
+            .metadata
+            .supply
+            .unwrap_or(token_info.token_info.supply);
+        let (market_cap_at_call, supply, warning) =
+            market_cap_at_call(row, price_at_call, current_supply)?;
+        report.warnings.extend(warning);
+        report.market_cap_at_call = Some(market_cap_at_call.round_dp(2));
+
+        let window = Duration::hours(Self::DUPLICATE_WINDOW_HOURS);
+        let duplicate_in_file = seen.iter().any(|(c, address, group_id, timestamp)| {
+            *c == chain
+                && *address == row.address
+                && *group_id == row.telegram_chat_id
+                && (*timestamp - row.timestamp).abs() < window
+        });
+        if duplicate_in_file
+            || self
+                .token_repository
+                .token_called_between(
+                    &row.address,
+                    &chain,
+                    row.telegram_chat_id,
+                    row.timestamp - window,
+                    row.timestamp + window,
+                )
+                .await?
+        {
+            report.status = ImportRowStatus::Duplicate;
+            return Ok(None);
+        }
+        seen.push((
+            chain.clone(),
+            row.address.clone(),
+            row.telegram_chat_id,
+            row.timestamp,
+        ));
+
+        report.new_user = self
+            .user_service
+            .get_by_telegram_user_id(row.telegram_user_id)
+            .await?
+            .is_none();
+        report.new_group = !self
+            .group_service
+            .group_exists(row.telegram_chat_id)
+            .await
+            .unwrap_or(false);
+
+        if dry_run {
+            report.status = ImportRowStatus::Valid;
+            return Ok(None);
+        }
+
+        let user = self
+            .token_service
+            .get_or_create_user(row.telegram_user_id)
+            .await?;
+        let group = self
+            .token_service
+            .get_or_create_group(row.telegram_chat_id)
+            .await?;
+        self.token_service
+            .save_token_if_missing(&chain, token_info)
+            .await?;
+        let qualification_policy = self
+            .qualification_policy_service
+            .active_policy(group.id)
+            .await;
+
+        let token_pick = TokenPick {
+            token: token_info.clone().into(),
+            call_date: row.timestamp.into(),
+            group,
+            user: Some(Json(user.clone())),
+            telegram_message_id: row.telegram_message_id,
+            telegram_id: Some(row.telegram_user_id),
+            price_at_call,
+            market_cap_at_call,
+            supply_at_call: Some(supply),
+            highest_market_cap: Some(market_cap_at_call),
+            qualification_policy_id: qualification_policy.id,
+            ..Default::default()
+        };
+        let token_pick = self
+            .token_repository
+            .save_imported_token_pick(token_pick)
+            .await?;
+
+        if let Err(e) = self
+            .group_service
+            .add_user_to_group(
+                row.telegram_chat_id,
+                &AddUserRequest {
+                    user_id: Some(user.id),
+                    telegram_id: None,
+                },
+            )
+            .await
+        {
+            error!("Failed to add user to group: {}", e);
+        }
+
+        report.status = ImportRowStatus::Imported;
+        report.pick_id = Some(token_pick.id);
+        Ok(Some(token_pick))
+    }
+
+    /// Fetches current metadata of every token in the rows, for their name and logo
+    async fn fetch_metadata(
+        &self,
+        rows: &[ImportRow],
+    ) -> HashMap<(Chain, String), LatestTokenMetadataResponse> {
+        let mut addresses_by_chain: HashMap<Chain, Vec<String>> = HashMap::new();
+        for (_, row) in rows {
+            let Ok(row) = row else {
+                continue;
+            };
+            if let Some(chain) = Chain::detect(&row.address, row.chain.as_deref()) {
+                let addresses = addresses_by_chain.entry(chain).or_default();
+                if !addresses.contains(&row.address) {
+                    addresses.push(row.address.clone());
+                }
+            }
+        }
+
+        let mut metadata = HashMap::new();
+        for (chain, addresses) in addresses_by_chain {
+            for chunk in addresses.chunks(Self::METADATA_BATCH_SIZE) {
+                match self
+                    .token_service
+                    .get_latest_token_metadata(&chain, chunk)
+                    .await
+                {
+                    Ok(tokens) => metadata.extend(
+                        tokens
+                            .into_iter()
+                            .map(|(address, token)| ((chain.clone(), address), token)),
+                    ),
+                    Err(e) => error!("Failed to fetch metadata for imported tokens: {}", e),
+                }
+            }
+        }
+
+        metadata
+    }
+
+    /// Price at `timestamp`, the close of the last 1 minute candle that started before it, falling
+    /// back to hourly candles for tokens without minute history that far back
+    async fn price_at(
+        &self,
+        chain: &Chain,
+        address: &str,
+        timestamp: DateTime<Utc>,
+    ) -> Result<Decimal, AppError> {
+        let timestamp = timestamp.timestamp();
+        for (resolution, candle_seconds) in [("1m", 60), ("1H", 3600)] {
+            let candles = self
+                .birdeye_service
+                .get_ohlcv_items_request(
+                    &chain.to_string(),
+                    address,
+                    timestamp - candle_seconds * 5,
+                    timestamp + candle_seconds,
+                    resolution,
+                )
+                .await?;
+
+            let candle = candles
+                .iter()
+                .rev()
+                .find(|c| c.unix_time <= timestamp)
+                .or(candles.first());
+            if let Some(candle) = candle {
+                return Ok(candle.close);
+            }
+        }
+
+        Err(AppError::NotFound(
+            "No OHLCV candle found at the timestamp".to_string(),
+        ))
+    }
+}
+
+/// Market cap and supply of the token at the call of a row, from the market cap or supply of
+/// the row when given. Otherwise the current supply prices the call, which is reported with a
+/// warning as the supply may have changed since.
+fn market_cap_at_call(
+    row: &ImportTokenPickRow,
+    price_at_call: Decimal,
+    current_supply: Decimal,
+) -> Result<(Decimal, Decimal, Option<String>), AppError> {
+    let invalid = |field: &str| AppError::BadRequest(format!("{} must be positive", field));
+    match (row.market_cap, row.supply) {
+        (Some(market_cap), _) => {
+            if market_cap <= Decimal::ZERO {
+                return Err(invalid("market_cap"));
+            }
+            let supply = market_cap.checked_div(price_at_call).ok_or_else(|| {
+                AppError::BadRequest("Token had no price at the call".to_string())
+            })?;
+            Ok((market_cap, supply, None))
+        }
+        (None, Some(supply)) => {
+            if supply <= Decimal::ZERO {
+                return Err(invalid("supply"));
+            }
+            let market_cap = price_at_call
+                .checked_mul(supply)
+                .ok_or_else(|| invalid("supply"))?;
+            Ok((market_cap, supply, None))
+        }
+        (None, None) => {
+            if current_supply <= Decimal::ZERO {
+                return Err(AppError::BadRequest(
+                    "Token has no supply, set the market_cap or supply of the row".to_string(),
+                ));
+            }
+            let market_cap = price_at_call.checked_mul(current_supply).ok_or_else(|| {
+                AppError::BadRequest("Market cap at the call is out of range".to_string())
+            })?;
+            Ok((
+                market_cap,
+                current_supply,
+                Some(
+                    "Market cap derived from the current supply of the token, set market_cap or \
+                     supply if it changed since the call"
+                        .to_string(),
+                ),
+            ))
+        }
+    }
+}
+
+/// Parses the rows of an import, keeping their line so the report can point at them
+pub fn parse_rows(format: ImportFormat, body: &str) -> Vec<ImportRow> {
+    match format {
+        ImportFormat::Csv => csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .from_reader(body.as_bytes())
+            .deserialize()
+            .enumerate()
+            .map(|(i, row)| (i + 1, row.map_err(|e: csv::Error| e.to_string())))
+            .collect(),
+        ImportFormat::Jsonl => body
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| (i + 1, serde_json::from_str(line).map_err(|e| e.to_string())))
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(market_cap: Option<Decimal>, supply: Option<Decimal>) -> ImportTokenPickRow {
+        ImportTokenPickRow {
+            address: "So11111111111111111111111111111111111111112".to_string(),
+            chain: None,
+            telegram_user_id: 1,
+            telegram_chat_id: 2,
+            timestamp: Utc::now(),
+            telegram_message_id: None,
+            market_cap,
+            supply,
+        }
+    }
+
+    #[test]
+    fn test_market_cap_at_call_prefers_row_columns() {
+        let price = Decimal::from(2);
+        let current_supply = Decimal::from(1000);
+
+        let (market_cap, supply, warning) =
+            market_cap_at_call(&row(Some(Decimal::from(500)), None), price, current_supply)
+                .unwrap();
+        assert_eq!(
+            (market_cap, supply),
+            (Decimal::from(500), Decimal::from(250))
+        );
+        assert!(warning.is_none());
+
+        let (market_cap, supply, warning) =
+            market_cap_at_call(&row(None, Some(Decimal::from(100))), price, current_supply)
+                .unwrap();
+        assert_eq!(
+            (market_cap, supply),
+            (Decimal::from(200), Decimal::from(100))
+        );
+        assert!(warning.is_none());
+    }
+
+    #[test]
+    fn test_market_cap_at_call_falls_back_to_current_supply() {
+        let (market_cap, supply, warning) =
+            market_cap_at_call(&row(None, None), Decimal::from(2), Decimal::from(1000)).unwrap();
+        assert_eq!(
+            (market_cap, supply),
+            (Decimal::from(2000), Decimal::from(1000))
+        );
+        assert!(warning.is_some());
+
+        assert!(market_cap_at_call(&row(None, None), Decimal::from(2), Decimal::ZERO).is_err());
+    }
+}
@@ -1,8 +1,19 @@
+pub mod achievement_service;
+pub mod backfill_service;
 pub mod cache_service;
+pub mod export_service;
+pub mod feed_service;
 pub mod group_service;
+pub mod import_service;
+pub mod points_service;
 pub mod profile_service;
+pub mod qualification_policy_service;
+pub mod rank_history_service;
 pub mod redis_service;
 pub mod s3_service;
+pub mod season_service;
+pub mod simulation_service;
 pub mod telegram_service;
 pub mod token_service;
 pub mod user_service;
+pub mod user_stats_service;
@@ -0,0 +1,131 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::Arc,
+};
+
+use chrono::Utc;
+use tracing::{debug, info};
+use uuid::Uuid;
+
+use crate::{
+    models::{
+        points::PointsAward, profiles::ProfileTier, qualification_policies::QualificationPolicy,
+        tiers::TiersType, token_picks::TokenPick,
+    },
+    repositories::{points_repository::PointsRepository, token_repository::TokenRepository},
+    utils::{errors::app_error::AppError, time::TimePeriod},
+};
+
+use super::qualification_policy_service::QualificationPolicyService;
+
+/// Awards points for picks by the rules of [crate::models::points::PointsRule] and promotes
+/// users through the tiers as their points add up.
+pub struct PointsService {
+    points_repository: Arc<PointsRepository>,
+    token_repository: Arc<TokenRepository>,
+    qualification_policy_service: Arc<QualificationPolicyService>,
+}
+
+impl PointsService {
+    /// Users evaluated per batch
+    const EVALUATION_BATCH_SIZE: usize = 100;
+
+    pub fn new(
+        points_repository: Arc<PointsRepository>,
+        token_repository: Arc<TokenRepository>,
+        qualification_policy_service: Arc<QualificationPolicyService>,
+    ) -> Self {
+        Self {
+            points_repository,
+            token_repository,
+            qualification_policy_service,
+        }
+    }
+
+    /// Awards the users every point their picks earned that was not awarded yet, and records the
+    /// tiers they were promoted to
+    pub async fn evaluate_user_points(&self, user_ids: &[Uuid]) -> Result<(), AppError> {
+        let user_ids: Vec<Uuid> = user_ids
+            .iter()
+            .copied()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+
+        for user_ids in user_ids.chunks(Self::EVALUATION_BATCH_SIZE) {
+            let now = Utc::now().into();
+            let picks = self
+                .token_repository
+                .list_token_picks_of_users(user_ids, TimePeriod::AllTime.to_date_time(now))
+                .await?;
+
+            let mut policies = HashMap::<i64, QualificationPolicy>::new();
+            let mut picks_by_user = HashMap::<Uuid, Vec<TokenPick>>::new();
+            for pick in picks {
+                if !policies.contains_key(&pick.qualification_policy_id) {
+                    let policy = self
+                        .qualification_policy_service
+                        .get_policy(pick.qualification_policy_id)
+                        .await;
+                    policies.insert(pick.qualification_policy_id, policy);
+                }
+                if let Some(user_id) = pick.user.as_ref().map(|u| u.id) {
+                    picks_by_user.entry(user_id).or_default().push(pick);
+                }
+            }
+
+            let mut awards = Vec::new();
+            for (user_id, picks) in picks_by_user.iter_mut() {
+                picks.sort_by_key(|p| (p.call_date, p.id));
+                awards.extend(PointsAward::evaluate(
+                    *user_id,
+                    picks,
+                    |policy_id| &policies[&policy_id],
+                    now,
+                ));
+            }
+
+            let totals = self.points_repository.award_points(&awards).await?;
+            let tiers: Vec<(Uuid, TiersType)> = totals
+                .iter()
+                .map(|(user_id, points)| {
+                    (*user_id, ProfileTier::from_points(*points).current_tier())
+                })
+                .collect();
+            let promotions = self.points_repository.save_user_tiers(&tiers).await?;
+            for (user_id, tier) in &promotions {
+                info!("User {} was promoted to the {} tier", user_id, tier);
+            }
+            debug!(
+                "Evaluated points of {} users, {} were awarded points",
+                user_ids.len(),
+                totals.len()
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Tier of each user, users without points are on the first tier
+    pub async fn list_user_tiers(
+        &self,
+        user_ids: &[Uuid],
+    ) -> Result<HashMap<Uuid, ProfileTier>, AppError> {
+        let points = self.points_repository.list_user_points(user_ids).await?;
+
+        Ok(user_ids
+            .iter()
+            .map(|id| {
+                let total_points = points.get(id).copied().unwrap_or_default();
+                (*id, ProfileTier::from_points(total_points))
+            })
+            .collect())
+    }
+
+    pub async fn get_user_tier(&self, user_id: Uuid) -> Result<ProfileTier, AppError> {
+        let mut tiers = self.list_user_tiers(&[user_id]).await?;
+        Ok(tiers
+            .remove(&user_id)
+            .unwrap_or_else(|| ProfileTier::from_points(0)))
+    }
+}
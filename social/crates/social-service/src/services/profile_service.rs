@@ -1,11 +1,13 @@
-use std::{collections::HashMap, collections::HashSet, sync::Arc};
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 
+use chrono::Utc;
 use futures::future::join_all;
 use rayon::slice::ParallelSliceMut;
-use rust_decimal::{
-    prelude::{FromPrimitive, Zero},
-    Decimal,
-};
+use rust_decimal::Decimal;
 use tracing::{error, info};
 use uuid::Uuid;
 
@@ -13,8 +15,10 @@ use crate::{
     apis::{
         api_models::{
             query::{
-                PickLeaderboardSort, ProfileLeaderboardQuery, ProfileLeaderboardSort, TokenQuery,
+                LeaderboardRanking, PickLeaderboardSort, ProfileCompareQuery,
+                ProfileLeaderboardQuery, ProfileLeaderboardSort, RankingMode, TokenQuery,
             },
+            request::SetLeaderboardMinPicksRequest,
             response::LeaderboardResponse,
         },
         profile_handlers::ProfileQuery,
@@ -24,27 +28,53 @@ use crate::{
         usergate::UserGateService,
     },
     models::{
-        profiles::{ProfileDetailsResponse, ProfilePickSummary},
-        token_picks::{ProfilePicksAndStatsQuery, TokenPickResponse},
+        profiles::{
+            CommonToken, CommonTokenCall, ComparedProfile, LeaderboardMinPicks, ProfileComparison,
+            ProfileDetailsResponse, ProfilePickSummary, DEFAULT_LEADERBOARD,
+        },
+        token_picks::{PickMilestone, ProfilePicksAndStatsQuery, TokenPickResponse},
         tokens::Chain,
-        user_stats::{BestPick, UserStats},
+        user_stats::{UserStats, ALL_GROUPS},
+        users::User,
+    },
+    repositories::{
+        leaderboard_repository::LeaderboardRepository, token_repository::TokenRepository,
+        user_repository::UserRepository,
+    },
+    utils::{
+        cursor::{page_after, Cursor, MAX_PAGE_SIZE},
+        errors::app_error::AppError,
+        redis_keys::RedisKeys,
     },
-    repositories::{token_repository::TokenRepository, user_repository::UserRepository},
-    utils::{errors::app_error::AppError, redis_keys::RedisKeys},
 };
 
-use super::{redis_service::RedisService, s3_service::S3Service, token_service::TokenService};
+use super::{
+    achievement_service::AchievementService, points_service::PointsService,
+    rank_history_service::RankHistoryService, redis_service::RedisService, s3_service::S3Service,
+    token_service::TokenService, user_stats_service::UserStatsService,
+};
 
 const CACHE_TTL_SECONDS: u64 = 300; // 5
 
+/// Most users a profile comparison accepts
+const MAX_COMPARED_PROFILES: usize = 5;
+
+/// Cursor of a leaderboard page, the sort key and username of its last profile
+type ProfileCursor = Cursor<Decimal, String>;
+
 #[derive(Clone)]
 pub struct ProfileService {
     user_repository: Arc<UserRepository>,
     token_repository: Arc<TokenRepository>,
+    leaderboard_repository: Arc<LeaderboardRepository>,
     rust_monorepo_service: Arc<RustMonorepoService>,
     birdeye_service: Arc<BirdeyeService>,
     redis_service: Arc<RedisService>,
     token_service: Arc<TokenService>,
+    user_stats_service: Arc<UserStatsService>,
+    points_service: Arc<PointsService>,
+    achievement_service: Arc<AchievementService>,
+    rank_history_service: Arc<RankHistoryService>,
     cielo_service: Arc<CieloService>,
     usergate_service: Arc<UserGateService>,
     s3_service: Arc<S3Service>,
@@ -54,10 +84,15 @@ impl ProfileService {
     pub fn new(
         user_repository: Arc<UserRepository>,
         token_repository: Arc<TokenRepository>,
+        leaderboard_repository: Arc<LeaderboardRepository>,
         rust_monorepo_service: Arc<RustMonorepoService>,
         birdeye_service: Arc<BirdeyeService>,
         redis_service: Arc<RedisService>,
         token_service: Arc<TokenService>,
+        user_stats_service: Arc<UserStatsService>,
+        points_service: Arc<PointsService>,
+        achievement_service: Arc<AchievementService>,
+        rank_history_service: Arc<RankHistoryService>,
         cielo_service: Arc<CieloService>,
         usergate_service: Arc<UserGateService>,
         s3_service: Arc<S3Service>,
@@ -65,16 +100,59 @@ impl ProfileService {
         ProfileService {
             user_repository,
             token_repository,
+            leaderboard_repository,
             rust_monorepo_service,
             birdeye_service,
             redis_service,
             token_service,
+            user_stats_service,
+            points_service,
+            achievement_service,
+            rank_history_service,
             cielo_service,
             usergate_service,
             s3_service,
         }
     }
 
+    /// Fewest picks a user needs to be ranked on the leaderboard. Raw values are not adjusted for
+    /// sample size, so every user is ranked.
+    async fn min_picks(&self, params: &ProfileLeaderboardQuery) -> Result<i32, AppError> {
+        if params.ranking == RankingMode::Raw {
+            return Ok(0);
+        }
+
+        let leaderboard = params
+            .sort
+            .map_or(DEFAULT_LEADERBOARD.to_string(), |sort| sort.to_string());
+        Ok(self
+            .leaderboard_repository
+            .find_min_picks(&leaderboard, &params.picked_after.to_string())
+            .await?
+            .unwrap_or(0))
+    }
+
+    pub async fn list_leaderboard_min_picks(&self) -> Result<Vec<LeaderboardMinPicks>, AppError> {
+        Ok(self.leaderboard_repository.list_min_picks().await?)
+    }
+
+    /// Sets the minimum picks of a leaderboard, applied to lists cached after the change
+    pub async fn set_leaderboard_min_picks(
+        &self,
+        request: SetLeaderboardMinPicksRequest,
+    ) -> Result<LeaderboardMinPicks, AppError> {
+        let min_picks = i32::try_from(request.min_picks)
+            .map_err(|_| AppError::BadRequest("minPicks is too large".to_string()))?;
+        let leaderboard = request
+            .leaderboard
+            .map_or(DEFAULT_LEADERBOARD.to_string(), |sort| sort.to_string());
+
+        Ok(self
+            .leaderboard_repository
+            .upsert_min_picks(&leaderboard, &request.time_period.to_string(), min_picks)
+            .await?)
+    }
+
     pub async fn get_profile(
         &self,
         params: ProfileQuery,
@@ -106,8 +184,15 @@ impl ProfileService {
                 picked_after: Some(params.picked_after.clone()),
                 multiplier: None,
                 group_ids: params.group_ids.clone(),
+                chain: params.chain.clone(),
+                call_type: params.call_type,
             })
             .await?;
+        let tier = self.points_service.get_user_tier(user.id).await?;
+        let achievements = self
+            .achievement_service
+            .list_user_achievements(user.id)
+            .await?;
 
         let response = ProfileDetailsResponse {
             id: user.id,
@@ -116,6 +201,8 @@ impl ProfileService {
             avatar_url: user.image_uri,
             bio: user.bio,
             pick_summary: ProfilePickSummary::from(stats),
+            tier,
+            achievements,
             is_following,
             ..Default::default()
         };
@@ -133,9 +220,10 @@ impl ProfileService {
     ) -> Result<LeaderboardResponse, AppError> {
         info!("Listing profiles with params: {:?}", params);
         let cache_key = format!(
-            "{}:leaderboard:{}:{}{}:{}",
+            "{}:leaderboard:{}:{:?}:{}{}:{}:{}:{}:{}:{}",
             RedisKeys::get_env_prefix(),
             params.picked_after.to_string(),
+            params.sort,
             params
                 .group_ids
                 .clone()
@@ -150,7 +238,16 @@ impl ProfileService {
             params
                 .username
                 .clone()
-                .map_or(String::new(), |username| format!(":{}", username))
+                .map_or(String::new(), |username| format!(":{}", username)),
+            params
+                .chain
+                .as_ref()
+                .map_or("all".to_string(), |c| c.to_string()),
+            params.milestone.unwrap_or_default().to_string(),
+            params
+                .call_type
+                .map_or("all".to_string(), |c| c.to_string()),
+            params.ranking.to_string(),
         );
         if let Some(cached_response) = self
             .redis_service
@@ -160,6 +257,55 @@ impl ProfileService {
             return Ok(cached_response);
         }
 
+        let mut profiles = match stats_group_scope(params) {
+            Some(group_id) => {
+                self.user_stats_service
+                    .list_leaderboard_profiles(&params.picked_after, group_id, params.user_id)
+                    .await?
+            }
+            None => self.list_profiles_from_picks(params).await?,
+        };
+        info!("Fetched {} profiles", profiles.len());
+        let min_picks = self.min_picks(params).await?;
+        profiles.retain(|profile| profile.pick_summary.total_picks >= min_picks);
+
+        let milestone = params.milestone.unwrap_or_default();
+        let ranking =
+            LeaderboardRanking::new(params.ranking, profiles.iter().map(|p| &p.pick_summary));
+        profiles.par_sort_by_cached_key(|profile| {
+            ranked_leaderboard_position(params.sort, profile, milestone, &ranking)
+        });
+
+        info!("Sorted profiles");
+        for (profile, rank) in profiles.iter_mut().zip(1..) {
+            profile.rank = Some(rank);
+        }
+        // Snapshots rank every profile of the global leaderboards by the default milestone
+        if stats_group_scope(params) == Some(ALL_GROUPS)
+            && milestone == PickMilestone::default()
+            && params.ranking == RankingMode::Raw
+        {
+            if let Err(e) = self
+                .rank_history_service
+                .set_rank_changes(&mut profiles, params.sort, &params.picked_after)
+                .await
+            {
+                error!("Failed to set leaderboard rank changes: {}", e);
+            }
+        }
+
+        let response = LeaderboardResponse { profiles };
+        self.redis_service
+            .set_cached::<LeaderboardResponse>(&cache_key, &response, CACHE_TTL_SECONDS)
+            .await?;
+        Ok(response)
+    }
+
+    /// Profiles of every user with picks matching the query, computing the stats of each
+    async fn list_profiles_from_picks(
+        &self,
+        params: &ProfileLeaderboardQuery,
+    ) -> Result<Vec<ProfileDetailsResponse>, AppError> {
         let tokens = self
             .token_service
             .list_token_picks(
@@ -169,6 +315,8 @@ impl ProfileService {
                     group_ids: params.group_ids.clone(),
                     following: params.following.then_some(true),
                     username: params.username.clone(),
+                    chain: params.chain.clone(),
+                    call_type: params.call_type,
                     ..Default::default()
                 },
                 Some(false),
@@ -181,47 +329,138 @@ impl ProfileService {
             .map(|t| t.user.as_ref().map(|u| u.username.clone()))
             .collect::<HashSet<_>>();
         info!("Found {} unique users", unique_users.len());
-        let mut profiles = join_all(unique_users.iter().map(|username| {
+        join_all(unique_users.iter().map(|username| {
             let query = ProfileQuery {
                 username: username.clone().unwrap_or_default(),
                 picked_after: params.picked_after.clone(),
                 group_ids: params.group_ids.clone(),
+                chain: params.chain.clone(),
+                call_type: params.call_type,
             };
             self.get_profile(query, params.user_id)
         }))
         .await
         .into_iter()
-        .collect::<Result<Vec<_>, _>>()?;
-        info!("Fetched {} profiles", profiles.len());
+        .collect::<Result<Vec<_>, _>>()
+    }
 
-        let sort_key = |profile: &ProfileDetailsResponse| match params.sort {
-            Some(ProfileLeaderboardSort::PickReturns) => profile.pick_summary.pick_returns,
-            Some(ProfileLeaderboardSort::HitRate) => profile.pick_summary.hit_rate,
-            Some(ProfileLeaderboardSort::RealizedProfit) => profile.pick_summary.realized_profit,
-            Some(ProfileLeaderboardSort::TotalPicks) => {
-                Decimal::from(profile.pick_summary.total_picks)
-            }
-            Some(ProfileLeaderboardSort::AverageReturn) => profile.pick_summary.average_pick_return,
-            Some(ProfileLeaderboardSort::GreatestHits) => profile.pick_summary.best_pick.multiplier,
-            _ => Decimal::ZERO,
-        };
+    /// The number of profiles a page of the leaderboard holds, 0 listing every profile
+    pub fn page_size(params: &ProfileLeaderboardQuery) -> u32 {
+        params.limit.map_or(0, |l| l.clamp(1, MAX_PAGE_SIZE))
+    }
 
-        profiles.par_sort_by(|a, b| {
-            if params.sort.is_none() {
-                a.username.cmp(&b.username)
-            } else {
-                sort_key(b)
-                    .cmp(&sort_key(a))
-                    .then(a.username.cmp(&b.username))
-            }
+    /// A page of the leaderboard, starting after `params.cursor`
+    pub async fn list_profiles_page(
+        &self,
+        params: &ProfileLeaderboardQuery,
+    ) -> Result<(Vec<ProfileDetailsResponse>, Option<String>), AppError> {
+        let milestone = params.milestone.unwrap_or_default();
+        let cursor_sort = format!(
+            "profiles:{:?}:{}:{}",
+            params.sort,
+            milestone.to_string(),
+            params.ranking.to_string(),
+        );
+        let after = params
+            .cursor
+            .as_deref()
+            .map(|c| ProfileCursor::decode(c, &cursor_sort))
+            .transpose()?
+            .map(|c| (Reverse(c.key), c.id));
+        let limit = Self::page_size(params);
+
+        let leaderboard = self.list_profiles(params).await?;
+        let ranking = LeaderboardRanking::new(
+            params.ranking,
+            leaderboard.profiles.iter().map(|p| &p.pick_summary),
+        );
+        let position = |profile: &ProfileDetailsResponse| {
+            ranked_leaderboard_position(params.sort, profile, milestone, &ranking)
+        };
+        // Cursors are positions in the requested sort, so page in that order whatever the cache holds
+        let mut profiles = leaderboard.profiles;
+        profiles.sort_by_cached_key(position);
+        let (profiles, has_more) = page_after(profiles, after.as_ref(), limit as usize, position);
+        let next_cursor = profiles.last().filter(|_| has_more).map(|last| {
+            let (Reverse(key), username) = position(last);
+            ProfileCursor::new(&cursor_sort, key, username).encode()
         });
 
-        info!("Sorted profiles");
-        let response = LeaderboardResponse { profiles };
-        self.redis_service
-            .set_cached::<LeaderboardResponse>(&cache_key, &response, CACHE_TTL_SECONDS)
+        Ok((profiles, next_cursor))
+    }
+
+    /// Stats of the users side by side over the same time period and group, with the tokens at
+    /// least two of them called
+    pub async fn compare_profiles(
+        &self,
+        params: &ProfileCompareQuery,
+    ) -> Result<ProfileComparison, AppError> {
+        let mut seen = HashSet::new();
+        let usernames: Vec<&String> = params
+            .users
+            .iter()
+            .filter(|username| seen.insert(username.to_lowercase()))
+            .collect();
+        if !(2..=MAX_COMPARED_PROFILES).contains(&usernames.len()) {
+            return Err(AppError::BadRequest(format!(
+                "Between 2 and {} users can be compared",
+                MAX_COMPARED_PROFILES
+            )));
+        }
+
+        let mut users = Vec::with_capacity(usernames.len());
+        for username in usernames {
+            let user = self
+                .user_repository
+                .find_by_username(username)
+                .await?
+                .ok_or(AppError::NotFound(format!(
+                    "User with username {} not found",
+                    username
+                )))?;
+            users.push(user);
+        }
+
+        let user_ids: Vec<Uuid> = users.iter().map(|u| u.id).collect();
+        let picks = self
+            .token_repository
+            .list_public_token_picks_of_users(
+                &user_ids,
+                params.picked_after.to_date_time(Utc::now().into()),
+            )
             .await?;
-        Ok(response)
+
+        let mut picks_by_user = HashMap::<Uuid, Vec<TokenPickResponse>>::new();
+        for pick in picks {
+            if params.group_id.is_some_and(|id| id != pick.group.id) {
+                continue;
+            }
+            // Picks made in anonymous groups have no user and are left out
+            let pick = TokenPickResponse::from(pick);
+            if let Some(user_id) = pick.user.as_ref().map(|u| u.id) {
+                picks_by_user.entry(user_id).or_default().push(pick);
+            }
+        }
+
+        let common_tokens = common_tokens(&users, &picks_by_user);
+        let profiles = users
+            .into_iter()
+            .map(|user| ComparedProfile {
+                id: user.id,
+                stats: UserStats::from_picks(
+                    picks_by_user
+                        .get(&user.id)
+                        .map_or(&[][..], |p| p.as_slice()),
+                ),
+                username: user.username,
+                avatar_url: user.image_uri,
+            })
+            .collect();
+
+        Ok(ProfileComparison {
+            profiles,
+            common_tokens,
+        })
     }
 
     pub async fn get_user_picks_and_stats(
@@ -237,6 +476,8 @@ impl ProfileService {
             group_ids: params.group_ids.clone(),
             order_by: Some(PickLeaderboardSort::Reached),
             order_direction: Some("desc".to_string()),
+            chain: params.chain.clone(),
+            call_type: params.call_type,
             ..Default::default()
         };
 
@@ -250,61 +491,7 @@ impl ProfileService {
             return Ok((vec![], UserStats::default()));
         }
 
-        let first_picks = picks.iter().fold(
-            HashMap::<String, &TokenPickResponse>::new(),
-            |mut acc, pick| {
-                acc.entry(pick.token.address.clone()).or_insert(pick);
-                acc
-            },
-        );
-
-        let mut total_returns = Decimal::ZERO;
-        let mut best_pick = None::<BestPick>;
-        let mut hits_2x = 0;
-        let mut hit_returns = Decimal::ZERO;
-        for pick in first_picks.values() {
-            if pick.highest_mult_post_call >= 2.0 {
-                hits_2x += 1;
-                hit_returns += Decimal::from_f32(pick.highest_mult_post_call).unwrap_or_default();
-            }
-
-            let current_return = calculate_price_multiplier(
-                &pick.market_cap_at_call,
-                &pick.highest_mc_post_call.unwrap_or_default(),
-            );
-            total_returns += current_return;
-
-            let new_best = BestPick {
-                token_symbol: pick.token.symbol.clone(),
-                token_address: pick.token.address.clone(),
-                multiplier: current_return,
-                logo_uri: pick.token.logo_uri.clone(),
-            };
-
-            best_pick = match best_pick {
-                Some(b) if current_return > b.multiplier => Some(new_best),
-                None => Some(new_best),
-                b => b,
-            };
-        }
-
-        let total_picks = first_picks.len() as i32;
-        let total_hits = first_picks
-            .values()
-            .filter(|p| p.hit_date.is_some())
-            .count() as i32;
-
-        let hit_rate = if total_picks > 0 && hits_2x > 0 {
-            Decimal::from(hits_2x * 100) / Decimal::from(total_picks)
-        } else {
-            Decimal::ZERO
-        };
-
-        let average_pick_return = if total_picks > 0 && !total_returns.is_zero() {
-            total_returns / Decimal::from(total_picks)
-        } else {
-            Decimal::ZERO
-        };
+        let stats = UserStats::from_picks(&picks);
         let user = self
             .user_repository
             .find_by_username(&params.username)
@@ -340,33 +527,17 @@ impl ProfileService {
             })
             .unwrap_or_default();
 
-        let average_hit_return = if total_hits > 0 {
-            hit_returns / Decimal::from(total_hits)
-        } else {
-            Decimal::ZERO
-        };
         let total_busts = self.token_repository.count_busts(&user.id).await?;
         let stats = UserStats {
-            total_picks,
-            hit_rate: hit_rate.round_dp(2),
-            pick_returns: total_returns.round_dp(2),
-            average_pick_return: average_pick_return.round_dp(2),
             realized_profit,
             total_volume_traded: usergate_stats.trading_volume_usd.round_dp(2),
-            hits: total_hits,
-            misses: total_picks - total_hits,
-            best_pick: best_pick.unwrap_or_default(),
-            average_hit_return,
             total_busts,
+            ..stats
         };
 
         info!(
             "Stats for {}: {} picks, {}% hit rate, {} hits, {} misses",
-            params.username,
-            total_picks,
-            hit_rate,
-            total_hits,
-            total_picks - total_hits
+            params.username, stats.total_picks, stats.hit_rate, stats.hits, stats.misses
         );
 
         let result = (picks, stats);
@@ -375,15 +546,99 @@ impl ProfileService {
     }
 }
 
-fn calculate_price_multiplier(
-    market_cap_at_call: &Decimal,
-    highest_market_cap: &Decimal,
-) -> Decimal {
-    if market_cap_at_call.is_zero() || highest_market_cap.is_zero() {
-        Decimal::zero()
-    } else {
-        highest_market_cap / market_cap_at_call
+/// Tokens called by at least two of the users, with the first call of each, most recently first
+/// called first
+fn common_tokens(
+    users: &[User],
+    picks_by_user: &HashMap<Uuid, Vec<TokenPickResponse>>,
+) -> Vec<CommonToken> {
+    let mut first_calls = HashMap::<(String, String), HashMap<Uuid, &TokenPickResponse>>::new();
+    for (user_id, picks) in picks_by_user {
+        for pick in picks {
+            let token = (pick.token.chain.clone(), pick.token.address.clone());
+            first_calls
+                .entry(token)
+                .or_default()
+                .entry(*user_id)
+                .and_modify(|first| {
+                    if (pick.call_date, pick.id) < (first.call_date, first.id) {
+                        *first = pick;
+                    }
+                })
+                .or_insert(pick);
+        }
+    }
+
+    let mut common_tokens: Vec<CommonToken> = first_calls
+        .into_values()
+        .filter(|calls| calls.len() >= 2)
+        .map(|calls| {
+            let mut calls: Vec<(Uuid, &TokenPickResponse)> = calls.into_iter().collect();
+            calls.sort_by_key(|(_, pick)| (pick.call_date, pick.id));
+            CommonToken {
+                token: calls[0].1.token.clone(),
+                calls: calls
+                    .into_iter()
+                    .filter_map(|(user_id, pick)| {
+                        let user = users.iter().find(|u| u.id == user_id)?;
+                        Some(CommonTokenCall {
+                            user_id,
+                            username: user.username.clone(),
+                            pick_id: pick.id,
+                            call_date: pick.call_date,
+                            market_cap_at_call: pick.market_cap_at_call,
+                            highest_multiplier: pick.highest_mult_post_call,
+                        })
+                    })
+                    .collect(),
+            }
+        })
+        .collect();
+    common_tokens.sort_by_key(|token| Reverse(token.calls[0].call_date));
+    common_tokens
+}
+
+/// The group of the `user_stats` rows answering the leaderboard, [ALL_GROUPS] for every group.
+/// None when the query filters picks beyond their period and group, or sorts by realized profit,
+/// which the table does not keep.
+fn stats_group_scope(params: &ProfileLeaderboardQuery) -> Option<i64> {
+    if params.following
+        || params.username.is_some()
+        || params.chain.is_some()
+        || params.call_type.is_some()
+        || matches!(params.sort, Some(ProfileLeaderboardSort::RealizedProfit))
+    {
+        return None;
     }
+
+    match params.group_ids.as_deref() {
+        None => Some(ALL_GROUPS),
+        Some([group_id]) => Some(*group_id),
+        Some(_) => None,
+    }
+}
+
+/// Where a profile ranks on a leaderboard: highest sort key first, then by username. Without a
+/// sort every profile has the same key, so they are ordered by username.
+pub fn leaderboard_position(
+    sort: Option<ProfileLeaderboardSort>,
+    profile: &ProfileDetailsResponse,
+    milestone: PickMilestone,
+) -> (Reverse<Decimal>, String) {
+    ranked_leaderboard_position(sort, profile, milestone, &LeaderboardRanking::default())
+}
+
+/// [leaderboard_position] with the sort key adjusted for sample size by `ranking`
+fn ranked_leaderboard_position(
+    sort: Option<ProfileLeaderboardSort>,
+    profile: &ProfileDetailsResponse,
+    milestone: PickMilestone,
+    ranking: &LeaderboardRanking,
+) -> (Reverse<Decimal>, String) {
+    let key = sort.map_or(Decimal::ZERO, |s| {
+        s.ranked_summary_key(&profile.pick_summary, milestone, ranking)
+    });
+    (Reverse(key), profile.username.clone())
 }
 
 #[cfg(test)]
@@ -0,0 +1,111 @@
+use std::sync::Arc;
+
+use tokio::sync::RwLock;
+use tracing::{error, warn};
+
+use crate::{
+    apis::api_models::request::CreateQualificationPolicyRequest,
+    models::qualification_policies::{QualificationPolicy, DEFAULT_QUALIFICATION_POLICY_ID},
+    repositories::qualification_policy_repository::QualificationPolicyRepository,
+    utils::errors::app_error::AppError,
+};
+
+/// Resolves which [QualificationPolicy] applies to a pick. Policies are few and read for every
+/// processed pick, so they are kept in memory and reloaded by the token picks job.
+pub struct QualificationPolicyService {
+    repository: Arc<QualificationPolicyRepository>,
+    environment: String,
+    policies: RwLock<Vec<QualificationPolicy>>,
+}
+
+impl QualificationPolicyService {
+    pub fn new(repository: Arc<QualificationPolicyRepository>, environment: String) -> Self {
+        Self {
+            repository,
+            environment,
+            policies: RwLock::new(Vec::new()),
+        }
+    }
+
+    pub async fn refresh(&self) -> Result<Vec<QualificationPolicy>, AppError> {
+        let policies = self.repository.list_policies().await?;
+        *self.policies.write().await = policies.clone();
+        Ok(policies)
+    }
+
+    pub async fn list_policies(&self) -> Result<Vec<QualificationPolicy>, AppError> {
+        self.refresh().await
+    }
+
+    pub async fn create_policy(
+        &self,
+        payload: CreateQualificationPolicyRequest,
+    ) -> Result<QualificationPolicy, AppError> {
+        let thresholds = [
+            payload.min_market_cap,
+            payload.min_liquidity_volume_ratio,
+            payload.large_cap_threshold,
+            payload.large_cap_min_liquidity,
+            payload.bust_min_volume,
+            payload.bust_min_liquidity,
+            payload.bust_min_market_cap,
+        ];
+        if thresholds.iter().any(|t| t.is_sign_negative()) {
+            return Err(AppError::BadRequest(
+                "Qualification thresholds must not be negative".to_string(),
+            ));
+        }
+        if payload.cancellation_window_seconds < 0 {
+            return Err(AppError::BadRequest(
+                "Cancellation window must not be negative".to_string(),
+            ));
+        }
+
+        let policy = self.repository.create_policy(&payload).await?;
+        self.refresh().await?;
+        Ok(policy)
+    }
+
+    /// The active policy new picks in `group_id` are created under
+    pub async fn active_policy(&self, group_id: i64) -> QualificationPolicy {
+        self.load_if_empty().await;
+        self.policies
+            .read()
+            .await
+            .iter()
+            .filter(|p| p.is_active)
+            .filter_map(|p| Some((p.specificity(&self.environment, group_id)?, p)))
+            .max_by_key(|(specificity, p)| (*specificity, p.version))
+            .map(|(_, p)| p.clone())
+            .unwrap_or_default()
+    }
+
+    /// The policy a pick was created under, whether or not it is still active
+    pub async fn get_policy(&self, id: i64) -> QualificationPolicy {
+        self.load_if_empty().await;
+        let policy = self
+            .policies
+            .read()
+            .await
+            .iter()
+            .find(|p| p.id == id)
+            .cloned();
+
+        policy.unwrap_or_else(|| {
+            if id != DEFAULT_QUALIFICATION_POLICY_ID {
+                warn!("Qualification policy {} not found, using the default", id);
+            }
+            QualificationPolicy::default()
+        })
+    }
+
+    async fn load_if_empty(&self) {
+        if !self.policies.read().await.is_empty() {
+            return;
+        }
+
+        if let Err(e) = self.refresh().await {
+            error!("Failed to load qualification policies: {}", e);
+        }
+    }
+}
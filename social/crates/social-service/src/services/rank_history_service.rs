@@ -0,0 +1,189 @@
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use tracing::info;
+
+use crate::{
+    apis::api_models::query::{ProfileLeaderboardSort, RankHistoryQuery},
+    models::{
+        profiles::ProfileDetailsResponse,
+        rank_history::{RankHistoryPoint, RankSnapshot, SnapshotResolution},
+        token_picks::PickMilestone,
+        user_stats::ALL_GROUPS,
+    },
+    repositories::rank_history_repository::{LeaderboardKey, RankHistoryRepository},
+    utils::{errors::app_error::AppError, time::TimePeriod},
+};
+
+use super::{
+    profile_service::leaderboard_position, user_service::UserService,
+    user_stats_service::UserStatsService,
+};
+
+/// Snapshots the ranks of the global leaderboards, so leaderboards show how users moved and
+/// profiles can chart their rank
+pub struct RankHistoryService {
+    rank_history_repository: Arc<RankHistoryRepository>,
+    user_stats_service: Arc<UserStatsService>,
+    user_service: Arc<UserService>,
+}
+
+impl RankHistoryService {
+    /// Hourly snapshots older than this are deleted, daily snapshots are kept
+    const HOURLY_RETENTION_DAYS: i64 = 7;
+    /// Rank history returned without a `since`
+    const DEFAULT_HISTORY_DAYS: i64 = 30;
+
+    pub fn new(
+        rank_history_repository: Arc<RankHistoryRepository>,
+        user_stats_service: Arc<UserStatsService>,
+        user_service: Arc<UserService>,
+    ) -> Self {
+        Self {
+            rank_history_repository,
+            user_stats_service,
+            user_service,
+        }
+    }
+
+    /// Snapshots every [ProfileLeaderboardSort::SNAPSHOTTED] leaderboard of every time period at
+    /// the start of the current hour and day. Snapshots already taken are kept.
+    pub async fn snapshot_leaderboards(&self) -> Result<(), AppError> {
+        let now = Utc::now();
+        for period in TimePeriod::ALL {
+            let mut profiles = self
+                .user_stats_service
+                .list_leaderboard_profiles(&period, ALL_GROUPS, None)
+                .await?;
+            let time_period = period.to_string();
+
+            for sort in ProfileLeaderboardSort::SNAPSHOTTED {
+                rank_profiles(&mut profiles, sort);
+                let ranks: Vec<RankSnapshot> = profiles
+                    .iter()
+                    .zip(1..)
+                    .map(|(profile, rank)| RankSnapshot {
+                        user_id: profile.id,
+                        rank,
+                        sort_value: sort.sort_key(profile, PickMilestone::default()),
+                    })
+                    .collect();
+
+                let sort = sort.to_string();
+                for resolution in SnapshotResolution::ALL {
+                    let resolution_name = resolution.to_string();
+                    let leaderboard = LeaderboardKey {
+                        resolution: &resolution_name,
+                        sort: &sort,
+                        time_period: &time_period,
+                    };
+                    self.rank_history_repository
+                        .save_rank_snapshots(&leaderboard, resolution.snapshot_at(now), &ranks)
+                        .await?;
+                }
+            }
+        }
+
+        let deleted = self
+            .rank_history_repository
+            .delete_rank_snapshots_before(
+                &SnapshotResolution::Hourly.to_string(),
+                now - Duration::days(Self::HOURLY_RETENTION_DAYS),
+            )
+            .await?;
+        info!(
+            "Snapshotted leaderboard ranks, deleted {} old hourly ranks",
+            deleted
+        );
+
+        Ok(())
+    }
+
+    /// Sets how many positions each ranked profile of a global leaderboard moved since the
+    /// daily snapshot taken a day ago. Leaderboards that are not snapshotted are left as is.
+    pub async fn set_rank_changes(
+        &self,
+        profiles: &mut [ProfileDetailsResponse],
+        sort: Option<ProfileLeaderboardSort>,
+        period: &TimePeriod,
+    ) -> Result<(), AppError> {
+        let Some(sort) = sort.filter(|s| ProfileLeaderboardSort::SNAPSHOTTED.contains(s)) else {
+            return Ok(());
+        };
+        let resolution = SnapshotResolution::Daily.to_string();
+        let sort = sort.to_string();
+        let time_period = period.to_string();
+        let previous_ranks = self
+            .rank_history_repository
+            .list_ranks_at(
+                &LeaderboardKey {
+                    resolution: &resolution,
+                    sort: &sort,
+                    time_period: &time_period,
+                },
+                Utc::now() - SnapshotResolution::Daily.interval(),
+            )
+            .await?;
+
+        for profile in profiles.iter_mut() {
+            profile.rank_change = previous_ranks
+                .get(&profile.id)
+                .zip(profile.rank)
+                .map(|(previous, current)| previous - current);
+        }
+
+        Ok(())
+    }
+
+    /// Ranks of the user on the snapshots of a global leaderboard, oldest first
+    pub async fn get_rank_history(
+        &self,
+        username: &str,
+        query: &RankHistoryQuery,
+    ) -> Result<Vec<RankHistoryPoint>, AppError> {
+        if !ProfileLeaderboardSort::SNAPSHOTTED.contains(&query.sort) {
+            return Err(AppError::BadRequest(format!(
+                "Rank history is not kept for the {} leaderboard",
+                query.sort.to_string()
+            )));
+        }
+
+        let user = self
+            .user_service
+            .get_by_username(username)
+            .await?
+            .ok_or(AppError::NotFound(format!(
+                "User with username {} not found",
+                username
+            )))?;
+
+        let resolution = query.resolution.to_string();
+        let sort = query.sort.to_string();
+        let time_period = query.picked_after.to_string();
+        let since = query
+            .since
+            .unwrap_or_else(|| Utc::now() - Duration::days(Self::DEFAULT_HISTORY_DAYS));
+
+        let history = self
+            .rank_history_repository
+            .list_rank_history(
+                user.id,
+                &LeaderboardKey {
+                    resolution: &resolution,
+                    sort: &sort,
+                    time_period: &time_period,
+                },
+                since,
+            )
+            .await?;
+
+        Ok(history)
+    }
+}
+
+/// Sorts the profiles as the leaderboard of `sort` ranks them
+fn rank_profiles(profiles: &mut [ProfileDetailsResponse], sort: ProfileLeaderboardSort) {
+    profiles.sort_by_cached_key(|profile| {
+        leaderboard_position(Some(sort), profile, PickMilestone::default())
+    });
+}
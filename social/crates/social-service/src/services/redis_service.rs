@@ -91,6 +91,26 @@ impl RedisService {
         Ok(result.is_some())
     }
 
+    /// Deletes `key` only if it still holds `value`, so a lock is only released by its holder.
+    /// Returns whether the key was deleted.
+    pub async fn delete_if_equals(&self, key: &str, value: &str) -> Result<bool, RedisError> {
+        let mut connection = self.connection.clone();
+        let deleted: i64 = redis::Script::new(
+            r#"
+            if redis.call("GET", KEYS[1]) == ARGV[1] then
+                return redis.call("DEL", KEYS[1])
+            end
+            return 0
+            "#,
+        )
+        .key(key)
+        .arg(value)
+        .invoke_async(&mut connection)
+        .await?;
+
+        Ok(deleted > 0)
+    }
+
     pub async fn execute_pipe(&self, pipe: redis::Pipeline) -> Result<(), RedisError> {
         let mut connection = self.connection.clone();
         let _: () = pipe.query_async(&mut connection).await?;
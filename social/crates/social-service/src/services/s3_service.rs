@@ -8,6 +8,7 @@ use tracing::error;
 use crate::utils::errors::app_error::AppError;
 
 const PROFILE_AVATARS_PATH: &str = "profile-avatars";
+const SEASON_STANDINGS_PATH: &str = "season-standings";
 const AVATAR_SIZE: u32 = 400; // 400x400 pixels for avatars
 
 pub struct S3Service {
@@ -75,6 +76,27 @@ impl S3Service {
         Ok(format!("https://{}.s3.amazonaws.com/{}", self.bucket, key))
     }
 
+    /// Archives the final standings of a season as JSON
+    pub async fn upload_season_standings(
+        &self,
+        season_id: i64,
+        standings: Vec<u8>,
+    ) -> Result<String, AppError> {
+        let key = format!("{}/{}.json", SEASON_STANDINGS_PATH, season_id);
+
+        self.client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(&key)
+            .body(ByteStream::from(standings))
+            .content_type("application/json")
+            .send()
+            .await
+            .map_err(|e| AppError::S3Error(e.to_string()))?;
+
+        Ok(format!("https://{}.s3.amazonaws.com/{}", self.bucket, key))
+    }
+
     pub async fn delete_profile_image(&self, user_telegram_id: &i64) -> Result<(), AppError> {
         let objects = self
             .client
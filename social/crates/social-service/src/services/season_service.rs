@@ -0,0 +1,310 @@
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use chrono::{Duration, Utc};
+use rust_decimal::Decimal;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::{
+    apis::api_models::{
+        query::{ProfileLeaderboardSort, SeasonLeaderboardQuery},
+        request::CreateSeasonRequest,
+    },
+    models::{
+        groups::CreateOrUpdateGroup,
+        profiles::{ProfileDetailsResponse, ProfilePickSummary},
+        seasons::{Season, SeasonGroupStanding, SeasonLeaderboardResponse, SeasonStandings},
+        token_picks::{PickMilestone, TokenPickResponse, PICK_SETTLE_DAYS},
+        user_stats::UserStats,
+        users::User,
+    },
+    repositories::{season_repository::SeasonRepository, token_repository::TokenRepository},
+    utils::{
+        cursor::{page_after, Cursor, MAX_PAGE_SIZE},
+        errors::app_error::AppError,
+        redis_keys::RedisKeys,
+    },
+};
+
+use super::{
+    group_service::GroupService, profile_service::leaderboard_position,
+    redis_service::RedisService, s3_service::S3Service,
+};
+
+/// Cursor of a season leaderboard page, the sort key and username of its last user
+type SeasonCursor = Cursor<Decimal, String>;
+
+/// Runs named seasons: leaderboards of the users and groups by the picks made during a season,
+/// frozen and archived once the picks made at its end settled
+pub struct SeasonService {
+    season_repository: Arc<SeasonRepository>,
+    token_repository: Arc<TokenRepository>,
+    group_service: Arc<GroupService>,
+    redis_service: Arc<RedisService>,
+    s3_service: Arc<S3Service>,
+}
+
+impl SeasonService {
+    const STANDINGS_CACHE_TTL_SECONDS: u64 = 300;
+
+    pub fn new(
+        season_repository: Arc<SeasonRepository>,
+        token_repository: Arc<TokenRepository>,
+        group_service: Arc<GroupService>,
+        redis_service: Arc<RedisService>,
+        s3_service: Arc<S3Service>,
+    ) -> Self {
+        Self {
+            season_repository,
+            token_repository,
+            group_service,
+            redis_service,
+            s3_service,
+        }
+    }
+
+    pub async fn create_season(&self, payload: CreateSeasonRequest) -> Result<Season, AppError> {
+        if payload.name.trim().is_empty() {
+            return Err(AppError::BadRequest(
+                "Season name must not be empty".to_string(),
+            ));
+        }
+        if payload.starts_at >= payload.ends_at {
+            return Err(AppError::BadRequest(
+                "Season must end after it starts".to_string(),
+            ));
+        }
+        if let Some(group_id) = payload.group_id {
+            if !self.group_service.group_exists(group_id).await? {
+                return Err(AppError::NotFound("Group not found".to_string()));
+            }
+        }
+
+        let season = self.season_repository.create_season(&payload).await?;
+        Ok(season)
+    }
+
+    pub async fn list_seasons(&self) -> Result<Vec<Season>, AppError> {
+        let seasons = self.season_repository.list_seasons().await?;
+        Ok(seasons)
+    }
+
+    /// Leaderboards of the season, final once it is closed and live before. Users are paginated,
+    /// every group is returned.
+    pub async fn get_season_leaderboard(
+        &self,
+        season_id: i64,
+        query: &SeasonLeaderboardQuery,
+    ) -> Result<SeasonLeaderboardResponse, AppError> {
+        let season =
+            self.season_repository
+                .get_season(season_id)
+                .await?
+                .ok_or(AppError::NotFound(format!(
+                    "Season {} not found",
+                    season_id
+                )))?;
+
+        let final_standings = match season.closed_at {
+            Some(_) => {
+                self.season_repository
+                    .get_season_standings(season_id)
+                    .await?
+            }
+            None => None,
+        };
+        let is_final = final_standings.is_some();
+        // Pages of the live standings don't continue into the final ones
+        let cursor_sort = format!(
+            "season:{}:{:?}:{}:{}",
+            season_id,
+            query.sort,
+            query.milestone.to_string(),
+            is_final
+        );
+        let after = query
+            .cursor
+            .as_deref()
+            .map(|c| SeasonCursor::decode(c, &cursor_sort))
+            .transpose()?
+            .map(|c| (Reverse(c.key), c.id));
+        let limit = query.limit.map_or(0, |l| l.clamp(1, MAX_PAGE_SIZE));
+
+        let mut standings = match final_standings {
+            Some(standings) => standings,
+            None => self.cached_standings(&season).await?,
+        };
+        rank_standings(&mut standings, query.sort, query.milestone);
+
+        let position = |profile: &ProfileDetailsResponse| {
+            leaderboard_position(Some(query.sort), profile, query.milestone)
+        };
+        let (users, has_more) = page_after(
+            std::mem::take(&mut standings.users),
+            after.as_ref(),
+            limit as usize,
+            position,
+        );
+        let next_cursor = users.last().filter(|_| has_more).map(|last| {
+            let (Reverse(key), username) = position(last);
+            SeasonCursor::new(&cursor_sort, key, username).encode()
+        });
+        standings.users = users;
+
+        Ok(SeasonLeaderboardResponse {
+            season,
+            is_final,
+            standings,
+            next_cursor,
+        })
+    }
+
+    /// Freezes and archives the standings of every season that ended, once the picks made at
+    /// its end had [PICK_SETTLE_DAYS] to hit
+    pub async fn close_ended_seasons(&self) -> Result<(), AppError> {
+        let seasons = self
+            .season_repository
+            .list_seasons_to_close(Utc::now() - Duration::days(PICK_SETTLE_DAYS))
+            .await?;
+
+        for season in seasons {
+            let mut standings = self.compute_standings(&season).await?;
+            rank_standings(
+                &mut standings,
+                ProfileLeaderboardSort::default(),
+                PickMilestone::default(),
+            );
+
+            let archive = serde_json::to_vec(&standings).map_err(|e| {
+                error!(
+                    "Failed to serialize standings of season {}: {}",
+                    season.id, e
+                );
+                AppError::InternalServerError()
+            })?;
+            let standings_uri = self
+                .s3_service
+                .upload_season_standings(season.id, archive)
+                .await?;
+            self.season_repository
+                .close_season(season.id, &standings, &standings_uri)
+                .await?;
+            info!(
+                "Closed season {} with {} users and {} groups",
+                season.name,
+                standings.users.len(),
+                standings.groups.len()
+            );
+        }
+
+        Ok(())
+    }
+
+    async fn cached_standings(&self, season: &Season) -> Result<SeasonStandings, AppError> {
+        let cache_key = RedisKeys::get_season_standings_key(season.id);
+        if let Some(standings) = self.redis_service.get_cached(&cache_key).await? {
+            return Ok(standings);
+        }
+
+        let standings = self.compute_standings(season).await?;
+        self.redis_service
+            .set_cached(&cache_key, &standings, Self::STANDINGS_CACHE_TTL_SECONDS)
+            .await?;
+        Ok(standings)
+    }
+
+    /// Stats of every user and group over the picks made during the season so far, unranked
+    async fn compute_standings(&self, season: &Season) -> Result<SeasonStandings, AppError> {
+        let picks = self
+            .token_repository
+            .list_token_picks_between(
+                season.starts_at,
+                season.ends_at.min(Utc::now()),
+                season.group_id,
+            )
+            .await?;
+
+        let mut users = HashMap::<Uuid, User>::new();
+        let mut groups = HashMap::<i64, CreateOrUpdateGroup>::new();
+        let mut picks_by_user = HashMap::<Uuid, Vec<TokenPickResponse>>::new();
+        let mut picks_by_group = HashMap::<i64, Vec<TokenPickResponse>>::new();
+        for pick in picks {
+            let Some(user) = pick.user.clone() else {
+                continue;
+            };
+            groups.entry(pick.group.id).or_insert(pick.group.clone());
+            let pick = TokenPickResponse::from(pick);
+            picks_by_group
+                .entry(pick.group.id)
+                .or_default()
+                .push(pick.clone());
+            picks_by_user.entry(user.id).or_default().push(pick);
+            users.entry(user.id).or_insert(user.0);
+        }
+
+        let users = picks_by_user
+            .into_iter()
+            .filter_map(|(user_id, picks)| {
+                let user = users.remove(&user_id)?;
+                Some(ProfileDetailsResponse {
+                    id: user.id,
+                    name: Some(user.username.clone()),
+                    username: user.username,
+                    avatar_url: user.image_uri,
+                    bio: user.bio,
+                    pick_summary: ProfilePickSummary::from(UserStats::from_picks(&picks)),
+                    ..Default::default()
+                })
+            })
+            .collect();
+        let groups = picks_by_group
+            .into_iter()
+            .filter_map(|(group_id, picks)| {
+                let group = groups.remove(&group_id)?;
+                let callers = picks
+                    .iter()
+                    .filter_map(|p| p.user.as_ref().map(|u| u.id))
+                    .collect::<HashSet<_>>()
+                    .len();
+                Some(SeasonGroupStanding {
+                    rank: None,
+                    group_id,
+                    name: group.name,
+                    logo_uri: group.logo_uri,
+                    callers: callers as i32,
+                    pick_summary: ProfilePickSummary::from(UserStats::from_picks(&picks)),
+                })
+            })
+            .collect();
+
+        Ok(SeasonStandings { users, groups })
+    }
+}
+
+/// Sorts and ranks the users and groups of the standings by `sort`
+fn rank_standings(
+    standings: &mut SeasonStandings,
+    sort: ProfileLeaderboardSort,
+    milestone: PickMilestone,
+) {
+    standings
+        .users
+        .sort_by_cached_key(|profile| leaderboard_position(Some(sort), profile, milestone));
+    for (profile, rank) in standings.users.iter_mut().zip(1..) {
+        profile.rank = Some(rank);
+    }
+
+    standings.groups.sort_by_cached_key(|group| {
+        (
+            Reverse(sort.summary_key(&group.pick_summary, milestone)),
+            group.name.clone(),
+        )
+    });
+    for (group, rank) in standings.groups.iter_mut().zip(1..) {
+        group.rank = Some(rank);
+    }
+}
@@ -0,0 +1,655 @@
+use std::{cmp::Reverse, collections::HashMap, sync::Arc};
+
+use chrono::{DateTime, Duration, FixedOffset, Utc};
+use futures::{stream, StreamExt};
+use rust_decimal::Decimal;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::{
+    apis::api_models::query::SimulationQuery,
+    external_services::birdeye::{ohlcv::BirdeyeOHLCVItem, BirdeyeService},
+    models::{
+        simulations::{
+            EquityPoint, SimulatedTrade, SimulationPriceSource, SimulationResponse,
+            SimulationSummary, TradeExit,
+        },
+        token_picks::TokenPick,
+        tokens::Chain,
+    },
+    repositories::{token_repository::TokenRepository, user_repository::UserRepository},
+    utils::{errors::app_error::AppError, redis_keys::RedisKeys},
+};
+
+use super::redis_service::RedisService;
+
+/// Picks replayed by a simulation
+enum SimulationScope {
+    User(Uuid),
+    Group(i64),
+}
+
+impl ToString for SimulationScope {
+    fn to_string(&self) -> String {
+        match self {
+            SimulationScope::User(user_id) => format!("user:{}", user_id),
+            SimulationScope::Group(group_id) => format!("group:{}", group_id),
+        }
+    }
+}
+
+/// A price observation of a token, a single price for snapshots
+struct PricePoint {
+    date: DateTime<FixedOffset>,
+    open: Decimal,
+    high: Decimal,
+    low: Decimal,
+    close: Decimal,
+}
+
+impl PricePoint {
+    fn at(date: DateTime<FixedOffset>, price: Decimal) -> Self {
+        PricePoint {
+            date,
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+        }
+    }
+}
+
+/// Replays picks as trades: buying a fixed amount of every called token and selling it on a
+/// take-profit, a stop-loss or after a maximum holding period
+pub struct SimulationService {
+    token_repository: Arc<TokenRepository>,
+    user_repository: Arc<UserRepository>,
+    birdeye_service: Arc<BirdeyeService>,
+    redis_service: Arc<RedisService>,
+}
+
+impl SimulationService {
+    const CACHE_TTL_SECONDS: u64 = 300;
+    /// Most recent picks a simulation replays
+    const MAX_PICKS: usize = 200;
+    /// Positions still held this long after being bought are sold
+    const MAX_HOLD_DAYS: i64 = 30;
+    /// USD put in every pick when the query sets no position size
+    const DEFAULT_POSITION_SIZE: i64 = 100;
+    /// Largest USD amount a query can put in every pick
+    const MAX_POSITION_SIZE: i64 = 1_000_000;
+    /// Largest take-profit multiple a query can set
+    const MAX_TAKE_PROFIT: i64 = 1_000;
+    /// Longest delay between a call and the simulated buy
+    const MAX_ENTRY_DELAY_MINUTES: u32 = 1_440;
+    /// Birdeye candle requests a simulation makes at once
+    const MAX_CONCURRENT_CANDLE_REQUESTS: usize = 4;
+    /// Candles of a window that ended no longer change
+    const CLOSED_CANDLES_CACHE_TTL_SECONDS: u64 = 86_400;
+
+    pub fn new(
+        token_repository: Arc<TokenRepository>,
+        user_repository: Arc<UserRepository>,
+        birdeye_service: Arc<BirdeyeService>,
+        redis_service: Arc<RedisService>,
+    ) -> Self {
+        Self {
+            token_repository,
+            user_repository,
+            birdeye_service,
+            redis_service,
+        }
+    }
+
+    /// Copy-trades the picks of a user, only those made in `query.group_id` if set
+    pub async fn simulate_user(
+        &self,
+        username: &str,
+        query: &SimulationQuery,
+    ) -> Result<SimulationResponse, AppError> {
+        let user = self
+            .user_repository
+            .find_by_username(username)
+            .await?
+            .ok_or(AppError::NotFound(format!(
+                "User with username {} not found",
+                username
+            )))?;
+
+        self.simulate(SimulationScope::User(user.id), query).await
+    }
+
+    /// Copy-trades the picks of every user in a group
+    pub async fn simulate_group(
+        &self,
+        group_id: i64,
+        query: &SimulationQuery,
+    ) -> Result<SimulationResponse, AppError> {
+        self.simulate(SimulationScope::Group(group_id), query).await
+    }
+
+    async fn simulate(
+        &self,
+        scope: SimulationScope,
+        query: &SimulationQuery,
+    ) -> Result<SimulationResponse, AppError> {
+        validate_query(query)?;
+
+        let cache_key = RedisKeys::get_simulation_key(&scope.to_string(), &query.cache_key());
+        if let Some(cached) = self.redis_service.get_cached(&cache_key).await? {
+            return Ok(cached);
+        }
+
+        let now = Utc::now().fixed_offset();
+        let mut picks = self.list_picks(&scope, query, now).await?;
+        picks.sort_by_key(|p| Reverse(p.call_date));
+        picks.truncate(Self::MAX_PICKS);
+
+        let prices = self.list_prices(&picks, query, now).await?;
+        let position_size = query
+            .position_size
+            .unwrap_or(Decimal::from(Self::DEFAULT_POSITION_SIZE));
+        let mut trades = Vec::new();
+        let mut skipped_picks = 0;
+        for pick in &picks {
+            let points = prices.get(&pick.id).map_or(&[][..], |p| p.as_slice());
+            match simulate_trade(pick, points, query, position_size, now) {
+                Some(trade) => trades.push(trade),
+                None => skipped_picks += 1,
+            }
+        }
+
+        let response = summarize(trades, skipped_picks, position_size);
+        info!(
+            "Simulated {} trades of {}, {} picks skipped",
+            response.summary.trades,
+            scope.to_string(),
+            skipped_picks
+        );
+        self.redis_service
+            .set_cached(&cache_key, &response, Self::CACHE_TTL_SECONDS)
+            .await?;
+        Ok(response)
+    }
+
+    async fn list_picks(
+        &self,
+        scope: &SimulationScope,
+        query: &SimulationQuery,
+        now: DateTime<FixedOffset>,
+    ) -> Result<Vec<TokenPick>, AppError> {
+        let picked_after = query.picked_after.to_date_time(now);
+        let picks = match scope {
+            SimulationScope::User(user_id) => self
+                .token_repository
+                .list_token_picks_of_users(&[*user_id], picked_after)
+                .await?
+                .into_iter()
+                .filter(|p| query.group_id.map_or(true, |id| id == p.group.id))
+                .collect(),
+            SimulationScope::Group(group_id) => {
+                self.token_repository
+                    .list_token_picks_between(
+                        picked_after.with_timezone(&Utc),
+                        now.with_timezone(&Utc),
+                        Some(*group_id),
+                    )
+                    .await?
+            }
+        };
+        Ok(picks)
+    }
+
+    /// Prices of every pick from its call on, oldest first, starting with the price at call
+    async fn list_prices(
+        &self,
+        picks: &[TokenPick],
+        query: &SimulationQuery,
+        now: DateTime<FixedOffset>,
+    ) -> Result<HashMap<i64, Vec<PricePoint>>, AppError> {
+        let mut prices: HashMap<i64, Vec<PricePoint>> = picks
+            .iter()
+            .map(|p| (p.id, vec![PricePoint::at(p.call_date, p.price_at_call)]))
+            .collect();
+
+        match query.source {
+            SimulationPriceSource::Snapshots => {
+                let pick_ids: Vec<i64> = picks.iter().map(|p| p.id).collect();
+                let snapshots = self
+                    .token_repository
+                    .list_pick_snapshots_of_picks(&pick_ids)
+                    .await?;
+                for snapshot in snapshots {
+                    if let Some(points) = prices.get_mut(&snapshot.token_pick_id) {
+                        points.push(PricePoint::at(snapshot.observed_at, snapshot.price));
+                    }
+                }
+            }
+            SimulationPriceSource::Ohlcv => {
+                let candles: Vec<(i64, Vec<BirdeyeOHLCVItem>)> = stream::iter(picks)
+                    .map(|pick| async move {
+                        match self.list_candles(pick, now).await {
+                            Ok(candles) => Some((pick.id, candles)),
+                            Err(e) => {
+                                warn!("Failed to fetch candles of token pick {}: {}", pick.id, e);
+                                None
+                            }
+                        }
+                    })
+                    .buffer_unordered(Self::MAX_CONCURRENT_CANDLE_REQUESTS)
+                    .filter_map(|candles| async move { candles })
+                    .collect()
+                    .await;
+
+                for (pick_id, candles) in candles {
+                    let Some(points) = prices.get_mut(&pick_id) else {
+                        continue;
+                    };
+                    points.extend(candles.into_iter().filter_map(|candle| {
+                        Some(PricePoint {
+                            date: DateTime::from_timestamp(candle.unix_time, 0)?.fixed_offset(),
+                            open: candle.open,
+                            high: candle.high,
+                            low: candle.low,
+                            close: candle.close,
+                        })
+                    }));
+                }
+            }
+        }
+
+        for points in prices.values_mut() {
+            points.sort_by_key(|p| p.date);
+        }
+        Ok(prices)
+    }
+
+    /// Hourly candles of the token of a pick over every window a simulation can hold it in.
+    /// Candles are cached per pick, for longer once the window is over.
+    async fn list_candles(
+        &self,
+        pick: &TokenPick,
+        now: DateTime<FixedOffset>,
+    ) -> Result<Vec<BirdeyeOHLCVItem>, AppError> {
+        let cache_key = RedisKeys::get_simulation_candles_key(pick.id);
+        if let Some(candles) = self.redis_service.get_cached(&cache_key).await? {
+            return Ok(candles);
+        }
+
+        let chain = Chain::try_from(pick.token.chain.clone())?;
+        let window_end = pick.call_date
+            + Duration::minutes(Self::MAX_ENTRY_DELAY_MINUTES as i64)
+            + Duration::days(Self::MAX_HOLD_DAYS);
+        let candles = self
+            .birdeye_service
+            .get_ohlcv_items_request(
+                &chain.to_string(),
+                &pick.token.address,
+                pick.call_date.timestamp(),
+                window_end.min(now).timestamp(),
+                "1H",
+            )
+            .await?;
+
+        let ttl = if window_end <= now {
+            Self::CLOSED_CANDLES_CACHE_TTL_SECONDS
+        } else {
+            Self::CACHE_TTL_SECONDS
+        };
+        self.redis_service
+            .set_cached(&cache_key, &candles, ttl)
+            .await?;
+        Ok(candles)
+    }
+}
+
+fn validate_query(query: &SimulationQuery) -> Result<(), AppError> {
+    if query.position_size.is_some_and(|size| {
+        size <= Decimal::ZERO || size > Decimal::from(SimulationService::MAX_POSITION_SIZE)
+    }) {
+        return Err(AppError::BadRequest(format!(
+            "Position size must be positive and at most {}",
+            SimulationService::MAX_POSITION_SIZE
+        )));
+    }
+    if query.take_profit.is_some_and(|multiple| {
+        multiple <= Decimal::ONE || multiple > Decimal::from(SimulationService::MAX_TAKE_PROFIT)
+    }) {
+        return Err(AppError::BadRequest(format!(
+            "Take profit must be a multiple above 1 and at most {}",
+            SimulationService::MAX_TAKE_PROFIT
+        )));
+    }
+    if query.entry_delay_minutes > SimulationService::MAX_ENTRY_DELAY_MINUTES {
+        return Err(AppError::BadRequest(format!(
+            "Entry delay can't be longer than {} minutes",
+            SimulationService::MAX_ENTRY_DELAY_MINUTES
+        )));
+    }
+    if query
+        .stop_loss
+        .is_some_and(|percent| percent <= Decimal::ZERO || percent >= Decimal::ONE_HUNDRED)
+    {
+        return Err(AppError::BadRequest(
+            "Stop loss must be a percentage between 0 and 100".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Buys the pick at the first price after the entry delay and sells it at the first price
+/// crossing the take-profit or stop-loss, or at the last price of the holding period. None when
+/// no price was found to buy at, or the prices are too far apart to compute the profit.
+fn simulate_trade(
+    pick: &TokenPick,
+    points: &[PricePoint],
+    query: &SimulationQuery,
+    position_size: Decimal,
+    now: DateTime<FixedOffset>,
+) -> Option<SimulatedTrade> {
+    let entry_after = pick.call_date + Duration::minutes(query.entry_delay_minutes as i64);
+    let hold_until = entry_after + Duration::days(SimulationService::MAX_HOLD_DAYS);
+    let mut points = points
+        .iter()
+        .filter(|p| p.date >= entry_after && p.date <= hold_until)
+        .peekable();
+    let entry = *points.peek()?;
+    let entry_price = entry.open;
+    if entry_price <= Decimal::ZERO {
+        return None;
+    }
+
+    let take_profit = match query.take_profit {
+        Some(multiple) => Some(entry_price.checked_mul(multiple)?),
+        None => None,
+    };
+    let stop_loss = query
+        .stop_loss
+        .map(|percent| entry_price * (Decimal::ONE - percent / Decimal::ONE_HUNDRED));
+    let mut exit = (entry.date, entry.close, TradeExit::Open);
+    for point in points {
+        // The order of the high and low within a candle is unknown, so the stop-loss is checked
+        // first. Prices that gap past a level are sold at the open.
+        if let Some(stop) = stop_loss.filter(|stop| point.low <= *stop) {
+            exit = (point.date, stop.min(point.open), TradeExit::StopLoss);
+            break;
+        }
+        if let Some(target) = take_profit.filter(|target| point.high >= *target) {
+            exit = (point.date, target.max(point.open), TradeExit::TakeProfit);
+            break;
+        }
+        exit = (point.date, point.close, TradeExit::Open);
+    }
+    if exit.2 == TradeExit::Open && hold_until <= now {
+        exit.2 = TradeExit::HoldLimit;
+    }
+
+    let (exit_date, exit_price, exit) = exit;
+    let pnl = position_size.checked_mul(exit_price.checked_div(entry_price)? - Decimal::ONE)?;
+    Some(SimulatedTrade {
+        token_pick_id: pick.id,
+        token_symbol: pick.token.symbol.clone(),
+        token_address: pick.token.address.clone(),
+        call_date: pick.call_date,
+        entry_date: entry.date,
+        entry_price,
+        exit_date,
+        exit_price,
+        exit,
+        pnl: pnl.round_dp(2),
+    })
+}
+
+/// Orders the trades by sell date and builds their equity curve and summary
+fn summarize(
+    mut trades: Vec<SimulatedTrade>,
+    skipped_picks: i32,
+    position_size: Decimal,
+) -> SimulationResponse {
+    trades.sort_by_key(|t| (t.exit_date, t.token_pick_id));
+
+    let mut pnl = Decimal::ZERO;
+    let mut peak = Decimal::ZERO;
+    let mut max_drawdown = Decimal::ZERO;
+    let equity_curve = trades
+        .iter()
+        .map(|trade| {
+            pnl = pnl.saturating_add(trade.pnl);
+            peak = peak.max(pnl);
+            max_drawdown = max_drawdown.max(peak.saturating_sub(pnl));
+            EquityPoint {
+                date: trade.exit_date,
+                pnl,
+            }
+        })
+        .collect();
+
+    let total_trades = trades.len() as i32;
+    let wins = trades.iter().filter(|t| t.pnl > Decimal::ZERO).count() as i32;
+    let losses = trades.iter().filter(|t| t.pnl < Decimal::ZERO).count() as i32;
+    let total_invested = position_size * Decimal::from(total_trades);
+    let (win_rate, return_percent) = if total_trades > 0 {
+        (
+            Decimal::from(wins * 100) / Decimal::from(total_trades),
+            (pnl / total_invested).saturating_mul(Decimal::ONE_HUNDRED),
+        )
+    } else {
+        (Decimal::ZERO, Decimal::ZERO)
+    };
+
+    SimulationResponse {
+        summary: SimulationSummary {
+            trades: total_trades,
+            wins,
+            losses,
+            win_rate: win_rate.round_dp(2),
+            skipped_picks,
+            total_invested,
+            final_value: total_invested.saturating_add(pnl),
+            total_pnl: pnl,
+            return_percent: return_percent.round_dp(2),
+            max_drawdown,
+        },
+        equity_curve,
+        trades,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(hours: i64) -> DateTime<FixedOffset> {
+        (DateTime::<Utc>::from_timestamp(1_700_000_000, 0).unwrap() + Duration::hours(hours))
+            .fixed_offset()
+    }
+
+    fn candle(hours: i64, open: f64, high: f64, low: f64, close: f64) -> PricePoint {
+        let price = |v: f64| v.to_string().parse::<Decimal>().unwrap();
+        PricePoint {
+            date: date(hours),
+            open: price(open),
+            high: price(high),
+            low: price(low),
+            close: price(close),
+        }
+    }
+
+    fn pick() -> TokenPick {
+        TokenPick {
+            id: 1,
+            call_date: date(0),
+            price_at_call: Decimal::ONE,
+            ..Default::default()
+        }
+    }
+
+    fn query(take_profit: Option<i64>, stop_loss: Option<i64>) -> SimulationQuery {
+        SimulationQuery {
+            take_profit: take_profit.map(Decimal::from),
+            stop_loss: stop_loss.map(Decimal::from),
+            ..Default::default()
+        }
+    }
+
+    fn trade(points: &[PricePoint], query: &SimulationQuery) -> Option<SimulatedTrade> {
+        simulate_trade(&pick(), points, query, Decimal::from(100), date(24))
+    }
+
+    #[test]
+    fn test_simulate_trade_stop_loss_before_take_profit() {
+        // The candle crosses both levels, the stop-loss is assumed to be hit first
+        let points = [
+            PricePoint::at(date(0), Decimal::ONE),
+            candle(1, 1.0, 3.0, 0.4, 2.5),
+        ];
+        let trade = trade(&points, &query(Some(2), Some(50))).unwrap();
+
+        assert_eq!(trade.exit, TradeExit::StopLoss);
+        assert_eq!(trade.exit_price, Decimal::new(5, 1));
+        assert_eq!(trade.pnl, Decimal::from(-50));
+    }
+
+    #[test]
+    fn test_simulate_trade_take_profit() {
+        let points = [
+            PricePoint::at(date(0), Decimal::ONE),
+            candle(1, 1.0, 1.5, 0.8, 1.2),
+            candle(2, 1.2, 2.5, 1.1, 2.2),
+        ];
+        let trade = trade(&points, &query(Some(2), Some(50))).unwrap();
+
+        assert_eq!(trade.exit, TradeExit::TakeProfit);
+        assert_eq!(trade.exit_date, date(2));
+        assert_eq!(trade.exit_price, Decimal::from(2));
+        assert_eq!(trade.pnl, Decimal::from(100));
+    }
+
+    #[test]
+    fn test_simulate_trade_gap_fills_at_open() {
+        let points = [
+            PricePoint::at(date(0), Decimal::ONE),
+            candle(1, 3.0, 3.5, 2.8, 3.2),
+        ];
+        let trade_up = trade(&points, &query(Some(2), None)).unwrap();
+        assert_eq!(trade_up.exit, TradeExit::TakeProfit);
+        assert_eq!(trade_up.exit_price, Decimal::from(3));
+
+        let points = [
+            PricePoint::at(date(0), Decimal::ONE),
+            candle(1, 0.3, 0.35, 0.2, 0.25),
+        ];
+        let trade_down = trade(&points, &query(None, Some(50))).unwrap();
+        assert_eq!(trade_down.exit, TradeExit::StopLoss);
+        assert_eq!(trade_down.exit_price, Decimal::new(3, 1));
+    }
+
+    #[test]
+    fn test_simulate_trade_open_and_hold_limit() {
+        let points = [
+            PricePoint::at(date(0), Decimal::ONE),
+            candle(1, 1.0, 1.5, 0.8, 1.2),
+        ];
+        let query = query(Some(2), Some(50));
+
+        let open = trade(&points, &query).unwrap();
+        assert_eq!(open.exit, TradeExit::Open);
+        assert_eq!(open.exit_price, Decimal::new(12, 1));
+
+        let now = date(24 * (SimulationService::MAX_HOLD_DAYS + 1));
+        let held = simulate_trade(&pick(), &points, &query, Decimal::from(100), now).unwrap();
+        assert_eq!(held.exit, TradeExit::HoldLimit);
+    }
+
+    #[test]
+    fn test_simulate_trade_entry_delay() {
+        let points = [
+            PricePoint::at(date(0), Decimal::ONE),
+            candle(2, 2.0, 2.0, 2.0, 2.0),
+        ];
+        let query = SimulationQuery {
+            entry_delay_minutes: 60,
+            ..Default::default()
+        };
+        let trade = trade(&points, &query).unwrap();
+        assert_eq!(trade.entry_date, date(2));
+        assert_eq!(trade.entry_price, Decimal::from(2));
+
+        let query = SimulationQuery {
+            entry_delay_minutes: 180,
+            ..Default::default()
+        };
+        assert!(simulate_trade(&pick(), &points, &query, Decimal::from(100), date(24)).is_none());
+    }
+
+    #[test]
+    fn test_simulate_trade_overflow() {
+        let points = [
+            PricePoint::at(date(0), Decimal::new(1, 20)),
+            PricePoint::at(
+                date(1),
+                Decimal::from_i128_with_scale(100_000_000_000_000_000_000, 0),
+            ),
+        ];
+        assert!(trade(&points, &query(None, None)).is_none());
+    }
+
+    fn simulated_trade(pick_id: i64, exit_hours: i64, pnl: i64) -> SimulatedTrade {
+        SimulatedTrade {
+            token_pick_id: pick_id,
+            token_symbol: String::new(),
+            token_address: String::new(),
+            call_date: date(0),
+            entry_date: date(0),
+            entry_price: Decimal::ONE,
+            exit_date: date(exit_hours),
+            exit_price: Decimal::ONE,
+            exit: TradeExit::Open,
+            pnl: Decimal::from(pnl),
+        }
+    }
+
+    #[test]
+    fn test_summarize() {
+        let trades = vec![
+            simulated_trade(1, 3, -80),
+            simulated_trade(2, 1, 100),
+            simulated_trade(3, 2, 20),
+            simulated_trade(4, 4, 0),
+        ];
+        let response = summarize(trades, 2, Decimal::from(100));
+
+        let order: Vec<i64> = response.trades.iter().map(|t| t.token_pick_id).collect();
+        assert_eq!(order, vec![2, 3, 1, 4]);
+        let curve: Vec<Decimal> = response.equity_curve.iter().map(|p| p.pnl).collect();
+        assert_eq!(
+            curve,
+            vec![100, 120, 40, 40]
+                .into_iter()
+                .map(Decimal::from)
+                .collect::<Vec<_>>()
+        );
+
+        let summary = response.summary;
+        assert_eq!(summary.trades, 4);
+        assert_eq!(summary.wins, 2);
+        assert_eq!(summary.losses, 1);
+        assert_eq!(summary.win_rate, Decimal::from(50));
+        assert_eq!(summary.skipped_picks, 2);
+        assert_eq!(summary.total_invested, Decimal::from(400));
+        assert_eq!(summary.final_value, Decimal::from(440));
+        assert_eq!(summary.total_pnl, Decimal::from(40));
+        assert_eq!(summary.return_percent, Decimal::from(10));
+        assert_eq!(summary.max_drawdown, Decimal::from(80));
+    }
+
+    #[test]
+    fn test_summarize_without_trades() {
+        let summary = summarize(Vec::new(), 3, Decimal::from(100)).summary;
+
+        assert_eq!(summary.trades, 0);
+        assert_eq!(summary.win_rate, Decimal::ZERO);
+        assert_eq!(summary.return_percent, Decimal::ZERO);
+        assert_eq!(summary.skipped_picks, 3);
+    }
+}
@@ -1,29 +1,31 @@
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     sync::Arc,
     time,
 };
 
-use chrono::{DateTime, Duration, Utc};
+use chrono::{DateTime, Duration, FixedOffset, SecondsFormat, Utc};
+use futures::{stream, StreamExt};
 
 use rayon::iter::{IntoParallelIterator, IntoParallelRefMutIterator, ParallelIterator};
 use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::{prelude::One, Decimal};
 use sqlx::types::Json;
 use tokio::task;
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
 use crate::{
     apis::api_models::{
-        query::{GroupLeaderboardQuery, TokenQuery},
+        query::{GroupLeaderboardQuery, PickHistoryQuery, TokenQuery, TokenSearchQuery},
         request::{
-            AddUserRequest, CreateGroupRequest, DeleteTokenPickRequest, TokenGroupQuery,
-            TokenValueDataRequest,
+            AddUserRequest, CreateGroupRequest, DeleteTokenPickRequest, RestoreTokenPickRequest,
+            TokenGroupQuery, TokenValueDataRequest,
         },
         response::{
-            TokenPickDiff, TokenPickResponseType, TokenPickResponseWithMetadata,
-            TokenPickWithDiffResponse, TokenValueDataResponse,
+            TierMultiplier, TokenCaller, TokenDetailResponse, TokenPickDiff,
+            TokenPickHistoryResponse, TokenPickResponseType, TokenPickResponseWithMetadata,
+            TokenPickWithDiffResponse, TokenSearchResult, TokenValueDataResponse,
         },
     },
     external_services::{
@@ -34,19 +36,35 @@ use crate::{
         rust_monorepo::{get_latest_w_metadata::LatestTokenMetadataResponse, RustMonorepoService},
     },
     models::{
-        groups::CreateOrUpdateGroup,
-        token_picks::{TokenPick, TokenPickResponse},
-        tokens::{Chain, Token, TokenPickRequest},
+        groups::{CreateOrUpdateGroup, PickLimits},
+        tiers::TiersType,
+        token_picks::{DeletedTokenPickResponse, TokenPick, TokenPickResponse, TokenPickSnapshot},
+        tokens::{Chain, PickRequestReplay, Token, TokenPickRequest},
+        users::User,
     },
     repositories::token_repository::{ListTokenPicksParams, TokenRepository, UserPickLimitScope},
     services::user_service::UserService,
     utils::{
-        errors::app_error::AppError, math::calculate_price_multiplier, redis_keys::RedisKeys,
-        time::TimePeriod,
+        cursor::{page_size, Cursor},
+        errors::app_error::AppError,
+        math::calculate_price_multiplier,
+        redis_keys::RedisKeys,
     },
 };
 
-use super::{group_service::GroupService, redis_service::RedisService};
+use super::{
+    achievement_service::AchievementService, group_service::GroupService,
+    points_service::PointsService, qualification_policy_service::QualificationPolicyService,
+    redis_service::RedisService, user_stats_service::UserStatsService,
+};
+
+/// Cursor of a token picks page, the sort key and id of its last pick
+type PickCursor = Cursor<String, i64>;
+
+/// Cursor of a deleted picks page, the deletion date and id of its last pick
+type DeletedPickCursor = Cursor<DateTime<FixedOffset>, i64>;
+/// Cursor of a pick history page, the date of its last observation and the pick id
+type PickHistoryCursor = Cursor<DateTime<FixedOffset>, i64>;
 
 pub struct TokenService {
     token_repository: Arc<TokenRepository>,
@@ -55,10 +73,33 @@ pub struct TokenService {
     redis_service: Arc<RedisService>,
     birdeye_service: Arc<BirdeyeService>,
     group_service: Arc<GroupService>,
+    qualification_policy_service: Arc<QualificationPolicyService>,
+    user_stats_service: Arc<UserStatsService>,
+    points_service: Arc<PointsService>,
+    achievement_service: Arc<AchievementService>,
 }
 
 impl TokenService {
-    const MAX_PICK_LIMIT: i64 = 5;
+    /// Birdeye token overview requests in flight at once for a batch of EVM tokens
+    const MAX_CONCURRENT_OVERVIEW_REQUESTS: usize = 8;
+    /// Snapshots younger than this keep the job's 10 minute resolution
+    const RAW_SNAPSHOT_RETENTION_DAYS: i64 = 7;
+    /// Snapshots older than this are deleted
+    const SNAPSHOT_RETENTION_DAYS: i64 = 90;
+    const MIN_SEARCH_LENGTH: usize = 2;
+    const MAX_SEARCH_RESULTS: i64 = 50;
+    /// Picks returned with each token search result
+    const SEARCH_LATEST_PICKS: i64 = 3;
+    const SEARCH_CACHE_TTL_SECONDS: u64 = 60;
+    const TOKEN_DETAIL_CACHE_TTL_SECONDS: u64 = 60;
+    const DELETED_PICKS_CURSOR_SORT: &'static str = "deleted_picks";
+    const PICK_HISTORY_CURSOR_SORT: &'static str = "pick_history";
+    /// Largest page of observations the pick history returns
+    const MAX_PICK_HISTORY_POINTS: u32 = 1000;
+    /// How long the response of a pick creation request is replayed to its retries
+    const PICK_REQUEST_TTL_SECONDS: u64 = 60 * 60 * 24;
+    /// Upper bound on how long a pick creation request is considered in flight
+    const PICK_REQUEST_LOCK_TTL_SECONDS: u64 = 30;
 
     pub fn new(
         token_repository: Arc<TokenRepository>,
@@ -67,6 +108,10 @@ impl TokenService {
         redis_service: Arc<RedisService>,
         birdeye_service: Arc<BirdeyeService>,
         group_service: Arc<GroupService>,
+        qualification_policy_service: Arc<QualificationPolicyService>,
+        user_stats_service: Arc<UserStatsService>,
+        points_service: Arc<PointsService>,
+        achievement_service: Arc<AchievementService>,
     ) -> Self {
         Self {
             token_repository,
@@ -75,12 +120,16 @@ impl TokenService {
             redis_service,
             birdeye_service,
             group_service,
+            qualification_policy_service,
+            user_stats_service,
+            points_service,
+            achievement_service,
         }
     }
 
     fn generate_token_picks_cache_key(&self, params: &ListTokenPicksParams) -> String {
         format!(
-            "{}:token_picks:{}:{}:{}:{}:{}:{}:{}:{}",
+            "{}:token_picks:{}:{}:{}:{}:{}:{}:{}:{}:{}:{}:{}",
             RedisKeys::get_env_prefix(),
             params.user_id.unwrap_or(Uuid::nil()),
             params.group_ids.as_ref().map_or("none".to_string(), |ids| {
@@ -89,7 +138,10 @@ impl TokenService {
                     .collect::<Vec<_>>()
                     .join(",")
             }),
-            params.page,
+            params
+                .after
+                .as_ref()
+                .map_or("first".to_string(), |(key, id)| format!("{}|{}", key, id)),
             params.limit,
             params.order_by.unwrap_or_default().to_string(),
             params.order_direction.clone().unwrap_or_default(),
@@ -97,14 +149,71 @@ impl TokenService {
                 .picked_after
                 .map_or("none".to_string(), |t| t.to_rfc3339()),
             params.following,
+            params
+                .chain
+                .as_ref()
+                .map_or("all".to_string(), |c| c.to_string()),
+            params
+                .milestone
+                .map_or("all".to_string(), |m| m.to_string()),
+            params
+                .call_type
+                .map_or("all".to_string(), |c| c.to_string()),
         )
     }
 
+    /// Fetches the latest price and metadata for tokens of a single chain. Solana tokens come
+    /// from the rust monorepo, EVM tokens from Birdeye's token overview.
+    pub async fn get_latest_token_metadata(
+        &self,
+        chain: &Chain,
+        addresses: &[String],
+    ) -> Result<HashMap<String, LatestTokenMetadataResponse>, AppError> {
+        if !chain.is_evm() {
+            return self
+                .rust_monorepo_service
+                .get_latest_w_metadata(addresses)
+                .await;
+        }
+
+        let chain_name = chain.to_string();
+        let overviews: Vec<_> = stream::iter(addresses)
+            .map(|address| async {
+                let overview = self
+                    .birdeye_service
+                    .get_token_overview_request(&chain_name, address)
+                    .await;
+                (address, overview)
+            })
+            .buffer_unordered(Self::MAX_CONCURRENT_OVERVIEW_REQUESTS)
+            .collect()
+            .await;
+
+        let mut result = HashMap::with_capacity(addresses.len());
+        for (address, overview) in overviews {
+            match overview {
+                Ok(Some(overview)) => {
+                    result.insert(
+                        address.clone(),
+                        overview.into_latest_metadata(chain.clone()),
+                    );
+                }
+                Ok(None) => debug!("No token overview for {} on {}", address, chain_name),
+                Err(e) => error!(
+                    "Failed to fetch token overview for {} on {}: {}",
+                    address, chain_name, e
+                ),
+            }
+        }
+
+        Ok(result)
+    }
+
     pub async fn list_token_picks(
         &self,
         query: TokenQuery,
         qualified: Option<bool>,
-    ) -> Result<(Vec<TokenPickResponse>, i64), AppError> {
+    ) -> Result<(Vec<TokenPickResponse>, Option<String>), AppError> {
         debug!("Listing token picks with query: {:?}", query);
         let mut query = query.clone();
         let user = if let Some(username) = &query.username {
@@ -120,26 +229,35 @@ impl TokenService {
                 query.group_ids = Some(user_groups.iter().map(|g| g.id).collect());
             }
         }
-        let params = ListTokenPicksParams {
+        let get_all = query.get_all.unwrap_or(false);
+        let mut params = ListTokenPicksParams {
             user_id: user.map(|u| u.id),
-            page: query.page,
-            limit: query.limit,
+            after: None,
+            limit: page_size(query.limit),
             order_by: query.order_by,
             order_direction: query.order_direction,
-            get_all: query.get_all.unwrap_or(false),
+            get_all,
             group_ids: query.group_ids,
             picked_after: query
                 .picked_after
                 .clone()
                 .map(|t| t.to_date_time(Utc::now().into())),
             following: query.following.unwrap_or(false),
+            chain: query.chain,
+            milestone: query.milestone,
+            call_type: query.call_type,
         };
+        let cursor_sort = params.cursor_sort();
+        if let Some(cursor) = query.cursor.as_deref().filter(|_| !get_all) {
+            let cursor = PickCursor::decode(cursor, &cursor_sort)?;
+            params.after = Some((cursor.key, cursor.id));
+        }
 
         let cache_key = self.generate_token_picks_cache_key(&params);
 
         if let Ok(Some(cached)) = self
             .redis_service
-            .get_cached::<(Vec<TokenPickResponse>, i64)>(&cache_key)
+            .get_cached::<(Vec<TokenPickResponse>, Option<String>)>(&cache_key)
             .await
         {
             debug!("Cache hit for token picks list: {}", cache_key);
@@ -148,7 +266,7 @@ impl TokenService {
 
         debug!("Cache miss for token picks list: {}", cache_key);
 
-        let (picks, total) = if params.group_ids.is_some() {
+        let (picks, next) = if params.group_ids.is_some() {
             info!("Fetching token picks group");
             self.token_repository
                 .list_token_picks_group(Some(&params))
@@ -175,7 +293,8 @@ impl TokenService {
         //     .into_par_iter()
         //     .collect::<Result<Vec<_>, _>>()?;
 
-        let response = (pick_responses, total);
+        let next_cursor = next.map(|(key, id)| PickCursor::new(&cursor_sort, key, id).encode());
+        let response = (pick_responses, next_cursor);
         if let Err(e) = self
             .redis_service
             .set_cached(&cache_key, &response, 120)
@@ -218,9 +337,9 @@ impl TokenService {
         &self,
         pick: &mut TokenPick,
     ) -> Result<TokenPickResponse, AppError> {
+        let chain = Chain::try_from(pick.token.chain.clone())?;
         let latest_prices = self
-            .rust_monorepo_service
-            .get_latest_w_metadata(&[pick.token.address.clone()])
+            .get_latest_token_metadata(&chain, &[pick.token.address.clone()])
             .await
             .map_err(|e| {
                 error!(
@@ -240,7 +359,7 @@ impl TokenService {
             let ohlcv = self
                 .birdeye_service
                 .get_ohlcv_request(
-                    "solana",
+                    &chain.to_string(),
                     &pick.token.address,
                     pick.call_date.timestamp(),
                     Utc::now().timestamp(),
@@ -261,21 +380,11 @@ impl TokenService {
             } else {
                 Some(highest_market_cap)
             };
-            let hit_2x = calculate_price_multiplier(&pick.market_cap_at_call, &ohlcv.high)
-                >= Decimal::from(2);
-            if hit_2x {
-                pick.hit_date = Some(Utc::now().into());
-            }
             has_update = true;
         }
 
         // Check if we need to update the highest market cap
-        let diff = calculate_price_multiplier(
-            &pick.highest_market_cap.unwrap_or_default(),
-            &current_market_cap,
-        );
         if current_market_cap.round_dp(2) > pick.highest_market_cap.unwrap_or_default().round_dp(2)
-            && diff < Decimal::from(20)
         {
             debug!(
                 "Updating highest market cap for token pick {}. Old: {}, New: {}. With price {} and supply {}",
@@ -287,12 +396,6 @@ impl TokenService {
             );
 
             pick.highest_market_cap = Some(current_market_cap);
-
-            let hit_2x = calculate_price_multiplier(&pick.market_cap_at_call, &current_market_cap)
-                >= Decimal::from(2);
-            if hit_2x {
-                pick.hit_date = Some(Utc::now().into());
-            }
             has_update = true;
         }
         pick.highest_multiplier = Some(
@@ -302,6 +405,11 @@ impl TokenService {
             )
             .round_dp(2),
         );
+        has_update |= pick.record_milestones(
+            pick.highest_multiplier.unwrap_or_default(),
+            pick.highest_market_cap_date
+                .unwrap_or_else(|| Utc::now().into()),
+        );
         let mut pick_response = TokenPickResponse::from(pick.clone());
         pick_response.current_market_cap = current_market_cap.round_dp(2);
         pick_response.current_multiplier =
@@ -342,19 +450,23 @@ impl TokenService {
             "Processing single pick with metadata for token pick {}",
             pick_row.id
         );
-        if !TokenPick::is_qualified(
+        let current_market_cap = metadata.price * metadata.token_info.supply;
+        // Drawdowns are tracked even once a token no longer qualifies, rugs included
+        let mut has_update = self.update_lowest_market_cap(pick_row, current_market_cap);
+        let policy = self
+            .qualification_policy_service
+            .get_policy(pick_row.qualification_policy_id)
+            .await;
+        if !policy.is_qualified(
             metadata.market_cap,
             metadata.metadata.liquidity,
             metadata.metadata.v_24h_usd,
         ) {
-            return Ok((TokenPickResponse::from(pick_row.clone()), false));
+            return Ok((TokenPickResponse::from(pick_row.clone()), has_update));
         }
 
-        let current_market_cap = metadata.price * metadata.token_info.supply;
-        let mut has_update = false;
-
         if pick_row.highest_market_cap.unwrap_or_default() == Decimal::ZERO {
-            has_update = self
+            has_update |= self
                 .initialize_highest_market_cap(pick_row, metadata.token_info.supply)
                 .await?;
         }
@@ -369,10 +481,13 @@ impl TokenService {
             .round_dp(2),
         );
 
-        if pick_row.highest_multiplier.unwrap_or_default() > Decimal::from(2) {
-            pick_row.hit_date = Some(Utc::now().into());
-            has_update = true;
-        }
+        // Milestones are reached when the peak was observed, not when the job noticed it
+        has_update |= pick_row.record_milestones(
+            pick_row.highest_multiplier.unwrap_or_default(),
+            pick_row
+                .highest_market_cap_date
+                .unwrap_or_else(|| Utc::now().into()),
+        );
 
         let mut pick_response = TokenPickResponse::from(pick_row.clone());
         pick_response.current_market_cap = current_market_cap.round_dp(2);
@@ -399,14 +514,18 @@ impl TokenService {
             pick_row.id
         );
         let current_market_cap = price * supply;
-        if !TokenPick::is_qualified(current_market_cap, Some(liquidity), Some(volume_24h)) {
-            return Ok((TokenPickResponse::from(pick_row.clone()), false));
+        // Drawdowns are tracked even once a token no longer qualifies, rugs included
+        let mut has_update = self.update_lowest_market_cap(pick_row, current_market_cap);
+        let policy = self
+            .qualification_policy_service
+            .get_policy(pick_row.qualification_policy_id)
+            .await;
+        if !policy.is_qualified(current_market_cap, Some(liquidity), Some(volume_24h)) {
+            return Ok((TokenPickResponse::from(pick_row.clone()), has_update));
         }
 
-        let mut has_update = false;
-
         if pick_row.highest_market_cap.unwrap_or_default() == Decimal::ZERO {
-            has_update = self.initialize_highest_market_cap(pick_row, supply).await?;
+            has_update |= self.initialize_highest_market_cap(pick_row, supply).await?;
         }
 
         has_update |= self.update_highest_market_cap(pick_row, current_market_cap);
@@ -419,10 +538,13 @@ impl TokenService {
             .round_dp(2),
         );
 
-        if pick_row.highest_multiplier.unwrap_or_default() > Decimal::from(2) {
-            pick_row.hit_date = Some(Utc::now().into());
-            has_update = true;
-        }
+        // Milestones are reached when the peak was observed, not when the job noticed it
+        has_update |= pick_row.record_milestones(
+            pick_row.highest_multiplier.unwrap_or_default(),
+            pick_row
+                .highest_market_cap_date
+                .unwrap_or_else(|| Utc::now().into()),
+        );
 
         let mut pick_response = TokenPickResponse::from(pick_row.clone());
         pick_response.current_market_cap = current_market_cap.round_dp(2);
@@ -436,9 +558,97 @@ impl TokenService {
         Ok((pick_response, has_update))
     }
 
+    /// Saves a token pick. Retries of a request return the response of its first call, kept for
+    /// [Self::PICK_REQUEST_TTL_SECONDS], and are rejected while the first call is in flight.
+    /// Picks are still saved, without replays, when Redis is unavailable.
     pub async fn save_token_pick(
         &self,
         pick: TokenPickRequest,
+    ) -> Result<TokenPickResponseWithMetadata, AppError> {
+        let request_key = pick.request_key();
+        let fingerprint = pick.payload_fingerprint();
+        let response_key = RedisKeys::get_pick_request_key(&request_key);
+        match self
+            .redis_service
+            .get_cached::<PickRequestReplay>(&response_key)
+            .await
+        {
+            Ok(Some(replay)) if replay.fingerprint == fingerprint => {
+                debug!("Replaying token pick request {}", request_key);
+                return Ok(replay.response);
+            }
+            Ok(Some(_)) => {
+                return Err(AppError::BadRequest(
+                    "Idempotency key was already used for another pick".to_string(),
+                ));
+            }
+            Ok(None) => {}
+            Err(e) => warn!(
+                "Failed to look up token pick request {}, saving without idempotency: {}",
+                request_key, e
+            ),
+        }
+
+        // The lock holds a token of this request, so it is only released by the request that
+        // took it
+        let lock_key = RedisKeys::get_pick_request_lock_key(&request_key);
+        let lock_token = Uuid::new_v4().to_string();
+        let lock_acquired = match self
+            .redis_service
+            .set_nx(&lock_key, &lock_token, Self::PICK_REQUEST_LOCK_TTL_SECONDS)
+            .await
+        {
+            Ok(true) => true,
+            Ok(false) => {
+                return Err(AppError::BusinessLogicError(format!(
+                    "Token pick request {} is already being processed",
+                    request_key
+                )));
+            }
+            Err(e) => {
+                warn!(
+                    "Failed to lock token pick request {}, saving without idempotency: {}",
+                    request_key, e
+                );
+                false
+            }
+        };
+
+        let result = self.create_token_pick(pick).await;
+        if !lock_acquired {
+            return result;
+        }
+
+        let result = result.map(|response| PickRequestReplay {
+            fingerprint,
+            response,
+        });
+        if let Ok(replay) = &result {
+            if let Err(e) = self
+                .redis_service
+                .set_cached(&response_key, replay, Self::PICK_REQUEST_TTL_SECONDS)
+                .await
+            {
+                error!("Failed to store token pick request {}: {}", request_key, e);
+            }
+        }
+        if let Err(e) = self
+            .redis_service
+            .delete_if_equals(&lock_key, &lock_token)
+            .await
+        {
+            error!(
+                "Failed to release token pick request {}: {}",
+                request_key, e
+            );
+        }
+
+        result.map(|replay| replay.response)
+    }
+
+    async fn create_token_pick(
+        &self,
+        pick: TokenPickRequest,
     ) -> Result<TokenPickResponseWithMetadata, AppError> {
         debug!(
             "Saving token pick for user {} and token {}",
@@ -447,63 +657,39 @@ impl TokenService {
 
         let telegram_user_id = pick.telegram_user_id.parse::<i64>().unwrap();
 
-        let user = match self
-            .user_service
-            .get_by_telegram_user_id(telegram_user_id)
-            .await?
-        {
-            Some(user) => user,
-            None => {
-                debug!("User {} not found", pick.telegram_user_id);
-                let user = self
-                    .user_service
-                    .upsert_user(telegram_user_id, None)
-                    .await?
-                    .0
-                    .ok_or(AppError::NotFound("User not found".to_string()))?;
+        let user = self.get_or_create_user(telegram_user_id).await?;
 
-                self.user_service
-                    .get_by_telegram_user_id(user.telegram_id)
-                    .await?
-                    .ok_or(AppError::NotFound(format!(
-                        "User with telegram id {} not found",
-                        telegram_user_id
-                    )))?
-            }
+        let group = match pick.telegram_chat_id.parse() {
+            Ok(id) => self.get_or_create_group(id).await?,
+            Err(_) => CreateOrUpdateGroup::default(),
         };
 
-        if self
-            .has_user_reached_action_limit(&user.id)
+        let pick_limits = group
+            .settings
+            .as_ref()
+            .map(|settings| settings.pick_limits.clone())
+            .unwrap_or_default();
+        if let Some(next_pick_at) = self
+            .next_pick_allowed_at(&user.id, group.id, &pick_limits)
             .await
-            .map_err(|e| {
-                error!("Failed to check if user has reached action limit: {}", e);
-                AppError::InternalServerError()
+            .map_err(|e| match e {
+                AppError::BusinessLogicError(_) => e,
+                e => {
+                    error!("Failed to check if user has reached action limit: {}", e);
+                    AppError::InternalServerError()
+                }
             })?
         {
-            return Err(AppError::BusinessLogicError(
-                "User reached the maximum number of picks".to_string(),
-            ));
+            return Err(AppError::BusinessLogicError(format!(
+                "User reached the maximum number of picks, next pick allowed at {}",
+                next_pick_at.to_rfc3339_opts(SecondsFormat::Secs, true)
+            )));
         }
-
-        let group = match pick.telegram_chat_id.parse() {
-            Ok(id) => match self.group_service.get_group(id).await {
-                Ok(group) => group.into(),
-                Err(_) => {
-                    let group = self
-                        .group_service
-                        .create_or_update_group(CreateGroupRequest {
-                            group_id: pick.telegram_chat_id.parse::<i64>().unwrap_or_default(),
-                            ..Default::default()
-                        })
-                        .await?;
-                    group.into()
-                }
-            },
-            Err(_) => CreateOrUpdateGroup::default(),
-        };
+        let chain = Chain::detect(&pick.address, pick.chain.as_deref()).ok_or_else(|| {
+            AppError::BadRequest(format!("Unsupported token address {}", pick.address))
+        })?;
         let token_info = self
-            .rust_monorepo_service
-            .get_latest_w_metadata(&[pick.address.clone()])
+            .get_latest_token_metadata(&chain, &[pick.address.clone()])
             .await?;
 
         let token_info = token_info.get(&pick.address).ok_or_else(|| {
@@ -511,16 +697,13 @@ impl TokenService {
             AppError::NotFound("Token info not found".to_string())
         })?;
 
-        if let Ok(None) = self.token_repository.get_token(&pick.address, None).await {
-            let token: Token = token_info.clone().into();
-            tracing::debug!("Saving new token: {:?}", token);
-            self.token_repository.save_token(token).await.map_err(|e| {
-                error!("Failed to save new token: {}", e);
-                AppError::InternalServerError()
-            })?;
-        }
+        self.save_token_if_missing(&chain, token_info).await?;
 
         let market_cap_at_call = token_info.market_cap;
+        let qualification_policy = self
+            .qualification_policy_service
+            .active_policy(group.id)
+            .await;
 
         let token_pick = TokenPick {
             token: token_info.clone().into(),
@@ -530,6 +713,7 @@ impl TokenService {
             telegram_message_id: pick.telegram_message_id.parse::<i64>().ok(),
             price_at_call: token_info.price,
             highest_market_cap: Some(market_cap_at_call),
+            qualification_policy_id: qualification_policy.id,
             supply_at_call: Some(token_info.token_info.supply),
             market_cap_at_call,
             telegram_id: Some(pick.telegram_user_id.parse::<i64>().unwrap_or_default()),
@@ -540,6 +724,7 @@ impl TokenService {
             .token_repository
             .check_if_token_already_called_in_timeframe(
                 &pick.address,
+                &chain,
                 pick.telegram_chat_id.parse::<i64>().unwrap_or_default(),
                 pick.timestamp - Duration::hours(24),
             )
@@ -611,10 +796,76 @@ impl TokenService {
         })
     }
 
+    /// Finds the user with the telegram id, creating it on its first pick
+    pub async fn get_or_create_user(&self, telegram_user_id: i64) -> Result<User, AppError> {
+        if let Some(user) = self
+            .user_service
+            .get_by_telegram_user_id(telegram_user_id)
+            .await?
+        {
+            return Ok(user);
+        }
+
+        debug!("User {} not found", telegram_user_id);
+        let user = self
+            .user_service
+            .upsert_user(telegram_user_id, None)
+            .await?
+            .0
+            .ok_or(AppError::NotFound("User not found".to_string()))?;
+
+        self.user_service
+            .get_by_telegram_user_id(user.telegram_id)
+            .await?
+            .ok_or(AppError::NotFound(format!(
+                "User with telegram id {} not found",
+                telegram_user_id
+            )))
+    }
+
+    /// Finds the group with the telegram chat id, creating it on its first pick
+    pub async fn get_or_create_group(
+        &self,
+        telegram_chat_id: i64,
+    ) -> Result<CreateOrUpdateGroup, AppError> {
+        match self.group_service.get_group(telegram_chat_id).await {
+            Ok(group) => Ok(group.into()),
+            Err(_) => {
+                self.group_service
+                    .create_or_update_group(CreateGroupRequest {
+                        group_id: telegram_chat_id,
+                        ..Default::default()
+                    })
+                    .await
+            }
+        }
+    }
+
+    pub async fn save_token_if_missing(
+        &self,
+        chain: &Chain,
+        token_info: &LatestTokenMetadataResponse,
+    ) -> Result<(), AppError> {
+        if let Ok(None) = self
+            .token_repository
+            .get_token(&token_info.address, chain)
+            .await
+        {
+            let token: Token = token_info.clone().into();
+            tracing::debug!("Saving new token: {:?}", token);
+            self.token_repository.save_token(token).await.map_err(|e| {
+                error!("Failed to save new token: {}", e);
+                AppError::InternalServerError()
+            })?;
+        }
+
+        Ok(())
+    }
+
     pub async fn list_token_picks_group(
         &self,
         query: TokenGroupQuery,
-    ) -> Result<(HashMap<String, Vec<TokenPickResponse>>, i64), AppError> {
+    ) -> Result<(HashMap<String, Vec<TokenPickResponse>>, Option<String>), AppError> {
         let groups = if let Some(user_id) = query.user_id {
             let user = self
                 .user_service
@@ -631,17 +882,17 @@ impl TokenService {
                 .map(CreateOrUpdateGroup::from)
                 .collect()
         } else {
-            return Ok((HashMap::new(), 0));
+            return Ok((HashMap::new(), None));
         };
         if groups.is_empty() {
-            return Ok((HashMap::new(), 0));
+            return Ok((HashMap::new(), None));
         }
         info!("Fetching token picks for groups: {:?}", groups);
-        let (picks, total) = self
+        let (picks, next_cursor) = self
             .list_token_picks(
                 TokenQuery {
                     username: None,
-                    page: query.page,
+                    cursor: query.cursor,
                     limit: query.limit,
                     order_by: query.order_by,
                     order_direction: query.order_direction,
@@ -651,6 +902,9 @@ impl TokenService {
                     following: None,
                     filter_by_group: false,
                     user_id: None,
+                    chain: None,
+                    milestone: None,
+                    call_type: None,
                 },
                 None,
             )
@@ -668,24 +922,82 @@ impl TokenService {
                 }
                 acc
             });
-        Ok((map_group_id, total))
+        Ok((map_group_id, next_cursor))
     }
 
-    pub async fn has_user_reached_action_limit(&self, user_id: &Uuid) -> Result<bool, AppError> {
-        let max_limit = UserPickLimitScope::User(*user_id, TimePeriod::Day);
+    /// Returns when the user can pick again in the group, or None if a pick is allowed now.
+    /// A count limit frees up once the oldest of the most recent `max` picks leaves the period.
+    /// Besides the group's limits, the user's daily limit across every group applies.
+    pub async fn next_pick_allowed_at(
+        &self,
+        user_id: &Uuid,
+        group_id: i64,
+        limits: &PickLimits,
+    ) -> Result<Option<DateTime<Utc>>, AppError> {
+        let now = Utc::now();
+        let group_period = Duration::seconds(limits.period.seconds());
+        let daily_period = Duration::days(1);
+        let mut next_pick_at = None;
+
+        let count_limits = [
+            (
+                limits.max_picks_per_user,
+                group_period,
+                UserPickLimitScope::UserInGroup(*user_id, group_id, now - group_period),
+            ),
+            (
+                limits.max_picks_per_group,
+                group_period,
+                UserPickLimitScope::Group(group_id, now - group_period),
+            ),
+            (
+                self.user_service.daily_pick_limit(*user_id).await?,
+                daily_period,
+                UserPickLimitScope::User(*user_id, now - daily_period),
+            ),
+        ];
+        for (max_picks, period, scope) in count_limits {
+            let Some(max_picks) = max_picks else {
+                continue;
+            };
+            if max_picks == 0 {
+                let reason = match scope {
+                    UserPickLimitScope::User(..) => "Picks are disabled for this user",
+                    _ => "Picks are disabled in this group",
+                };
+                return Err(AppError::BusinessLogicError(reason.to_string()));
+            }
 
-        let count = self
-            .token_repository
-            .count_user_picks_in_period(max_limit)
-            .await?;
+            let dates = self
+                .token_repository
+                .list_pick_dates_in_period(scope, max_picks as i64)
+                .await?;
+            if let Some(oldest) = dates.get(max_picks as usize - 1) {
+                next_pick_at = next_pick_at.max(Some(oldest.to_utc() + period));
+            }
+        }
 
-        Ok(count >= Self::MAX_PICK_LIMIT)
+        if let Some(cooldown) = limits.user_cooldown_seconds {
+            let cooldown = Duration::seconds(cooldown as i64);
+            let dates = self
+                .token_repository
+                .list_pick_dates_in_period(
+                    UserPickLimitScope::UserInGroup(*user_id, group_id, now - cooldown),
+                    1,
+                )
+                .await?;
+            if let Some(last) = dates.first() {
+                next_pick_at = next_pick_at.max(Some(last.to_utc() + cooldown));
+            }
+        }
+
+        Ok(next_pick_at)
     }
 
     pub async fn get_all_tokens(
         &self,
         since: DateTime<Utc>,
-    ) -> Result<HashMap<String, Vec<TokenPick>>, AppError> {
+    ) -> Result<HashMap<(Chain, String), Vec<TokenPick>>, AppError> {
         debug!("Getting all tokens with picks");
         self.token_repository
             .get_all_tokens_with_picks_group_by_group_id(since)
@@ -701,9 +1013,299 @@ impl TokenService {
         picks: &[TokenPickResponse],
     ) -> Result<(), AppError> {
         self.token_repository.bulk_update_token_picks(picks).await?;
+
+        let user_ids: Vec<Uuid> = picks
+            .iter()
+            .filter_map(|p| p.user.as_ref().map(|u| u.id))
+            .collect();
+        if let Err(e) = self.user_stats_service.refresh_user_stats(&user_ids).await {
+            error!("Failed to refresh user stats: {}", e);
+        }
+        if let Err(e) = self.points_service.evaluate_user_points(&user_ids).await {
+            error!("Failed to evaluate user points: {}", e);
+        }
+        if let Err(e) = self
+            .achievement_service
+            .evaluate_user_achievements(&user_ids)
+            .await
+        {
+            error!("Failed to evaluate user achievements: {}", e);
+        }
+        Ok(())
+    }
+
+    pub async fn save_pick_snapshots(
+        &self,
+        snapshots: &[TokenPickSnapshot],
+    ) -> Result<(), AppError> {
+        self.token_repository
+            .save_pick_snapshots(snapshots)
+            .await
+            .map_err(|e| {
+                error!("Failed to save token pick snapshots: {}", e);
+                AppError::InternalServerError()
+            })
+    }
+
+    /// Downsamples old pick snapshots to hourly resolution and drops the expired ones.
+    pub async fn compact_pick_snapshots(&self) -> Result<(), AppError> {
+        let now = Utc::now();
+        let downsampled = self
+            .token_repository
+            .downsample_pick_snapshots(now - Duration::days(Self::RAW_SNAPSHOT_RETENTION_DAYS))
+            .await?;
+        let deleted = self
+            .token_repository
+            .delete_pick_snapshots_before(now - Duration::days(Self::SNAPSHOT_RETENTION_DAYS))
+            .await?;
+        debug!(
+            "Compacted token pick snapshots, downsampled: {}, expired: {}",
+            downsampled, deleted
+        );
         Ok(())
     }
 
+    /// A page of the market cap observations of a pick, starting after `query.cursor`
+    pub async fn get_token_pick_history(
+        &self,
+        pick_id: i64,
+        query: &PickHistoryQuery,
+    ) -> Result<TokenPickHistoryResponse, AppError> {
+        let after = query
+            .cursor
+            .as_deref()
+            .map(|c| PickHistoryCursor::decode(c, Self::PICK_HISTORY_CURSOR_SORT))
+            .transpose()?;
+        if after.as_ref().is_some_and(|c| c.id != pick_id) {
+            return Err(AppError::BadRequest(
+                "Cursor was returned for another pick".to_string(),
+            ));
+        }
+        let limit = query
+            .limit
+            .unwrap_or(Self::MAX_PICK_HISTORY_POINTS)
+            .clamp(1, Self::MAX_PICK_HISTORY_POINTS);
+
+        let pick = self
+            .token_repository
+            .get_token_pick_by_id(pick_id)
+            .await?
+            .ok_or(AppError::TokenPickNotFound)?;
+
+        let mut points = self
+            .token_repository
+            .list_pick_snapshots(pick_id, after.map(|c| c.key), i64::from(limit) + 1)
+            .await?;
+        let next_cursor = if points.len() > limit as usize {
+            points.truncate(limit as usize);
+            points.last().map(|last| {
+                PickHistoryCursor::new(Self::PICK_HISTORY_CURSOR_SORT, last.observed_at, pick_id)
+                    .encode()
+            })
+        } else {
+            None
+        };
+
+        Ok(TokenPickHistoryResponse {
+            pick_id: pick.id,
+            market_cap_at_call: pick.market_cap_at_call.round_dp(2),
+            call_date: pick.call_date,
+            points,
+            next_cursor,
+        })
+    }
+
+    /// Searches tokens by symbol, name or address prefix, ranked by how often and how recently
+    /// they were picked
+    pub async fn search_tokens(
+        &self,
+        query: TokenSearchQuery,
+    ) -> Result<Vec<TokenSearchResult>, AppError> {
+        let prefix = query.q.trim();
+        if prefix.len() < Self::MIN_SEARCH_LENGTH {
+            return Err(AppError::BadRequest(format!(
+                "Search query must have at least {} characters",
+                Self::MIN_SEARCH_LENGTH
+            )));
+        }
+        let limit = query.limit.clamp(1, Self::MAX_SEARCH_RESULTS);
+
+        let cache_key = format!(
+            "token_search:{}:{}:{}",
+            prefix.to_lowercase(),
+            query
+                .chain
+                .as_ref()
+                .map_or("all".to_string(), |c| c.to_string()),
+            limit
+        );
+        if let Ok(Some(cached)) = self
+            .redis_service
+            .get_cached::<Vec<TokenSearchResult>>(&cache_key)
+            .await
+        {
+            return Ok(cached);
+        }
+
+        let tokens = self
+            .token_repository
+            .search_tokens(prefix, query.chain.as_ref(), limit)
+            .await?;
+        let keys: Vec<(String, String)> = tokens
+            .iter()
+            .map(|t| (t.token.address.clone(), t.token.chain.clone()))
+            .collect();
+        let mut picks_by_token: HashMap<(String, String), Vec<TokenPickResponse>> = HashMap::new();
+        if !keys.is_empty() {
+            for pick in self
+                .token_repository
+                .list_latest_picks_of_tokens(&keys, Self::SEARCH_LATEST_PICKS)
+                .await?
+            {
+                picks_by_token
+                    .entry((pick.token.address.clone(), pick.token.chain.clone()))
+                    .or_default()
+                    .push(pick.into());
+            }
+        }
+
+        let results: Vec<TokenSearchResult> = tokens
+            .into_iter()
+            .map(|t| TokenSearchResult {
+                latest_picks: picks_by_token
+                    .remove(&(t.token.address.clone(), t.token.chain.clone()))
+                    .unwrap_or_default(),
+                token: t.token,
+                pick_count: t.pick_count,
+                last_pick_date: t.last_pick_date,
+            })
+            .collect();
+
+        if let Err(e) = self
+            .redis_service
+            .set_cached(&cache_key, &results, Self::SEARCH_CACHE_TTL_SECONDS)
+            .await
+        {
+            error!("Failed to cache token search: {}", e);
+        }
+
+        Ok(results)
+    }
+
+    /// The token with every pick of it across groups and statistics about who called it
+    pub async fn get_token_detail(
+        &self,
+        chain: &Chain,
+        address: &str,
+    ) -> Result<TokenDetailResponse, AppError> {
+        let cache_key = format!("token_detail:{}:{}", chain.to_string(), address);
+        if let Ok(Some(cached)) = self
+            .redis_service
+            .get_cached::<TokenDetailResponse>(&cache_key)
+            .await
+        {
+            return Ok(cached);
+        }
+
+        let token = self
+            .token_repository
+            .get_token(address, chain)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Token not found".to_string()))?;
+        let picks: Vec<TokenPickResponse> = self
+            .token_repository
+            .list_picks_of_token(address, chain)
+            .await?
+            .into_iter()
+            .map(TokenPickResponse::from)
+            .collect();
+        let callers = self
+            .token_repository
+            .list_token_callers(address, chain)
+            .await?;
+
+        let first_caller = picks
+            .iter()
+            .find(|p| p.is_original_call)
+            .or(picks.first())
+            .map(TokenCaller::from);
+        let best_caller = picks
+            .iter()
+            .max_by(|a, b| {
+                a.highest_mult_post_call
+                    .total_cmp(&b.highest_mult_post_call)
+            })
+            .map(TokenCaller::from);
+
+        let user_ids: HashSet<Uuid> = callers.iter().map(|c| c.user_id).collect();
+        let unique_groups = callers
+            .iter()
+            .map(|c| c.group_id)
+            .collect::<HashSet<_>>()
+            .len();
+        let tiers = self.caller_tiers(&user_ids).await;
+        let multipliers: HashMap<i64, f32> = picks
+            .iter()
+            .map(|p| (p.id, p.highest_mult_post_call))
+            .collect();
+        let mut by_tier: BTreeMap<TiersType, (HashSet<Uuid>, Vec<f32>)> = BTreeMap::new();
+        for caller in &callers {
+            let Some(multiplier) = multipliers.get(&caller.pick_id) else {
+                continue;
+            };
+            let tier = tiers.get(&caller.user_id).copied().unwrap_or_default();
+            let (tier_callers, tier_multipliers) = by_tier.entry(tier).or_default();
+            tier_callers.insert(caller.user_id);
+            tier_multipliers.push(*multiplier);
+        }
+        let multiplier_by_tier = by_tier
+            .into_iter()
+            .map(|(tier, (tier_callers, tier_multipliers))| TierMultiplier {
+                tier,
+                callers: tier_callers.len(),
+                picks: tier_multipliers.len(),
+                average_multiplier: tier_multipliers.iter().sum::<f32>()
+                    / tier_multipliers.len() as f32,
+            })
+            .collect();
+
+        let response = TokenDetailResponse {
+            token,
+            total_picks: picks.len(),
+            unique_callers: user_ids.len(),
+            unique_groups,
+            first_caller,
+            best_caller,
+            multiplier_by_tier,
+            picks,
+        };
+
+        if let Err(e) = self
+            .redis_service
+            .set_cached(&cache_key, &response, Self::TOKEN_DETAIL_CACHE_TTL_SECONDS)
+            .await
+        {
+            error!("Failed to cache token detail: {}", e);
+        }
+
+        Ok(response)
+    }
+
+    /// The tier of each caller, by the points they accumulated
+    async fn caller_tiers(&self, user_ids: &HashSet<Uuid>) -> HashMap<Uuid, TiersType> {
+        let user_ids: Vec<Uuid> = user_ids.iter().copied().collect();
+        match self.points_service.list_user_tiers(&user_ids).await {
+            Ok(tiers) => tiers
+                .into_iter()
+                .map(|(id, tier)| (id, tier.current_tier()))
+                .collect(),
+            Err(e) => {
+                error!("Failed to get caller tiers: {}", e);
+                HashMap::new()
+            }
+        }
+    }
+
     pub async fn save_many_tokens(&self, tokens: Vec<Token>) -> Result<(), AppError> {
         self.token_repository
             .save_many_tokens(tokens)
@@ -720,10 +1322,10 @@ impl TokenService {
         pick_row: &mut TokenPick,
         supply: Decimal,
     ) -> Result<bool, AppError> {
-        let ohlcv = self
+        let candles = self
             .birdeye_service
-            .get_ohlcv_request(
-                "solana",
+            .get_ohlcv_items_request(
+                &Chain::try_from(pick_row.token.chain.clone())?.to_string(),
                 &pick_row.token.address,
                 pick_row.call_date.timestamp(),
                 Utc::now().timestamp(),
@@ -739,28 +1341,62 @@ impl TokenService {
             })?;
 
         let supply = pick_row.supply_at_call.unwrap_or(supply);
-        let highest_market_cap = ohlcv.high * supply;
-        pick_row.highest_market_cap = if highest_market_cap < Decimal::one() {
-            Some(pick_row.market_cap_at_call)
+
+        // Measure each low against the highs of the previous candles, as the order of the
+        // high and low within a candle is unknown. Milestones are dated by the first candle
+        // crossing them.
+        let mut peak = pick_row.market_cap_at_call;
+        for candle in &candles {
+            pick_row.record_low(candle.low * supply, peak);
+            peak = peak.max(candle.high * supply);
+            if let Some(candle_date) = DateTime::from_timestamp(candle.unix_time, 0) {
+                pick_row.record_milestones(
+                    calculate_price_multiplier(
+                        &pick_row.market_cap_at_call,
+                        &(candle.high * supply),
+                    )
+                    .round_dp(2),
+                    candle_date.fixed_offset(),
+                );
+            }
+        }
+
+        let highest_candle = candles
+            .iter()
+            .max_by_key(|candle| candle.high)
+            .cloned()
+            .unwrap_or_default();
+        let highest_market_cap = highest_candle.high * supply;
+        if highest_market_cap < Decimal::one() {
+            pick_row.highest_market_cap = Some(pick_row.market_cap_at_call);
+            pick_row.highest_market_cap_date = Some(pick_row.call_date);
         } else {
-            Some(highest_market_cap)
-        };
+            pick_row.highest_market_cap = Some(highest_market_cap);
+            pick_row.highest_market_cap_date =
+                DateTime::from_timestamp(highest_candle.unix_time, 0)
+                    .map(|date| date.fixed_offset());
+        }
         Ok(true)
     }
 
-    fn update_highest_market_cap(
+    fn update_lowest_market_cap(
         &self,
         pick_row: &mut TokenPick,
         current_market_cap: Decimal,
     ) -> bool {
-        let diff = calculate_price_multiplier(
-            &pick_row.highest_market_cap.unwrap_or_default(),
-            &current_market_cap,
-        );
+        let peak = pick_row
+            .highest_market_cap
+            .unwrap_or(pick_row.market_cap_at_call);
+        pick_row.record_low(current_market_cap, peak)
+    }
 
+    fn update_highest_market_cap(
+        &self,
+        pick_row: &mut TokenPick,
+        current_market_cap: Decimal,
+    ) -> bool {
         if current_market_cap.round_dp(2)
             > pick_row.highest_market_cap.unwrap_or_default().round_dp(2)
-            && diff < Decimal::from(20)
         {
             info!(
                 "Updating highest market cap for token pick {}. Old: {}, New: {}",
@@ -769,6 +1405,7 @@ impl TokenService {
                 current_market_cap,
             );
             pick_row.highest_market_cap = Some(current_market_cap);
+            pick_row.highest_market_cap_date = Some(Utc::now().into());
 
             return true;
         }
@@ -802,7 +1439,12 @@ impl TokenService {
 
         let mut picks = self
             .token_repository
-            .get_group_leaderboard(group_id, &query.timeframe, query.limit)
+            .get_group_leaderboard(
+                group_id,
+                &query.timeframe,
+                query.limit,
+                query.chain.as_ref(),
+            )
             .await?;
         let mut responses: Vec<TokenPickResponse> = Vec::new();
         if picks.is_empty() {
@@ -813,17 +1455,19 @@ impl TokenService {
             responses = picks.into_iter().map(TokenPickResponse::from).collect();
         } else {
             info!("Fetching metadata for {} picks", picks.len());
-            let addresses: Vec<String> = picks
-                .iter()
-                .map(|pick| pick.token.address.clone())
-                .collect::<HashSet<_>>()
-                .into_iter()
-                .collect();
+            let mut addresses_by_chain: HashMap<Chain, HashSet<String>> = HashMap::new();
+            for pick in &picks {
+                addresses_by_chain
+                    .entry(Chain::try_from(pick.token.chain.clone())?)
+                    .or_default()
+                    .insert(pick.token.address.clone());
+            }
 
-            let metadata = self
-                .rust_monorepo_service
-                .get_latest_w_metadata(&addresses)
-                .await?;
+            let mut metadata = HashMap::new();
+            for (chain, addresses) in addresses_by_chain {
+                let addresses: Vec<String> = addresses.into_iter().collect();
+                metadata.extend(self.get_latest_token_metadata(&chain, &addresses).await?);
+            }
 
             info!(
                 "Processing {:?} picks",
@@ -918,6 +1562,8 @@ impl TokenService {
         Ok(())
     }
 
+    /// Cancels a pick on behalf of its caller. The pick is soft deleted, and only within the
+    /// cancellation window of the policy it was created under.
     pub async fn delete_token_pick(&self, body: DeleteTokenPickRequest) -> Result<(), AppError> {
         let token_pick = self
             .token_repository
@@ -929,15 +1575,123 @@ impl TokenService {
             .await?
             .ok_or(AppError::TokenPickNotFound)?;
 
-        let pick_time = token_pick.call_date;
-        if Utc::now() - Duration::minutes(1) > pick_time {
-            return Err(AppError::BusinessLogicError(
-                "Can only delete picks within 1 minute of creation".to_string(),
+        let policy = self
+            .qualification_policy_service
+            .get_policy(token_pick.qualification_policy_id)
+            .await;
+        if !policy.can_cancel(token_pick.call_date, Utc::now()) {
+            return Err(AppError::BusinessLogicError(format!(
+                "Can only delete picks within {} seconds of the call",
+                policy.cancellation_window_seconds
+            )));
+        }
+
+        let actor = format!("telegram:{}", body.telegram_user_id);
+        self.token_repository
+            .delete_token_pick(token_pick.id, &actor, body.reason.as_deref())
+            .await?;
+        info!("Token pick {} deleted by {}", token_pick.id, actor);
+        self.after_pick_visibility_change(
+            token_pick.user_id,
+            &token_pick.token_address,
+            &token_pick.token_chain,
+        )
+        .await
+    }
+
+    /// Soft deleted picks for moderators, most recently deleted first
+    pub async fn list_deleted_token_picks(
+        &self,
+        limit: u32,
+        cursor: Option<String>,
+    ) -> Result<(Vec<DeletedTokenPickResponse>, Option<String>), AppError> {
+        let limit = page_size(limit);
+        let after = cursor
+            .map(|c| DeletedPickCursor::decode(&c, Self::DELETED_PICKS_CURSOR_SORT))
+            .transpose()?
+            .map(|c| (c.key, c.id));
+
+        let mut picks = self
+            .token_repository
+            .list_deleted_token_picks(i64::from(limit) + 1, after)
+            .await?;
+        let next_cursor = if picks.len() > limit as usize {
+            picks.truncate(limit as usize);
+            picks.last().map(|last| {
+                DeletedPickCursor::new(
+                    Self::DELETED_PICKS_CURSOR_SORT,
+                    last.deleted_at,
+                    last.pick.id,
+                )
+                .encode()
+            })
+        } else {
+            None
+        };
+
+        Ok((picks.into_iter().map(Into::into).collect(), next_cursor))
+    }
+
+    /// Restores a soft deleted pick, so it counts in stats and leaderboards again
+    pub async fn restore_token_pick(
+        &self,
+        id: i64,
+        body: RestoreTokenPickRequest,
+    ) -> Result<TokenPickResponse, AppError> {
+        if body.moderator.trim().is_empty() {
+            return Err(AppError::BadRequest("Moderator is required".to_string()));
+        }
+        let actor = format!("moderator:{}", body.moderator.trim());
+        if !self
+            .token_repository
+            .restore_token_pick(id, &actor, body.reason.as_deref())
+            .await?
+        {
+            return Err(AppError::NotFound(
+                "Deleted token pick not found".to_string(),
             ));
         }
+        info!("Token pick {} restored by {}", id, actor);
+
+        let token_pick = self
+            .token_repository
+            .get_token_pick_by_id(id)
+            .await?
+            .ok_or(AppError::TokenPickNotFound)?;
+        if let Some(user) = &token_pick.user {
+            self.after_pick_visibility_change(
+                user.id,
+                &token_pick.token.address,
+                &token_pick.token.chain,
+            )
+            .await?;
+        }
 
+        Ok(token_pick.into())
+    }
+
+    /// Recomputes the first call of the token and the stats of the caller and drops their cached
+    /// picks, after a pick was deleted or restored
+    async fn after_pick_visibility_change(
+        &self,
+        user_id: Uuid,
+        address: &str,
+        chain: &str,
+    ) -> Result<(), AppError> {
         self.token_repository
-            .delete_token_pick(token_pick.id)
+            .update_first_call(address, chain)
+            .await?;
+        self.user_stats_service
+            .refresh_user_stats(&[user_id])
+            .await?;
+
+        if let Some(user) = self.user_service.get_by_id(user_id).await? {
+            let user_cache_key = format!("user_picks_stats:{}", user.username);
+            self.redis_service.delete_cached(&user_cache_key).await?;
+        }
+        let list_cache_pattern = format!("token_picks:{}:*", user_id);
+        self.redis_service
+            .delete_pattern(&list_cache_pattern)
             .await?;
 
         Ok(())
@@ -946,13 +1700,14 @@ impl TokenService {
     async fn token_already_called(
         &self,
         address: &str,
+        chain: &Chain,
         group_id: i64,
         call_date: DateTime<Utc>,
     ) -> Result<Option<TokenPick>, AppError> {
         let timeframe = call_date - Duration::hours(24);
         let pick = self
             .token_repository
-            .check_if_token_already_called_in_timeframe(address, group_id, timeframe)
+            .check_if_token_already_called_in_timeframe(address, chain, group_id, timeframe)
             .await?;
 
         Ok(pick)
@@ -967,66 +1722,60 @@ impl TokenService {
             .collect::<HashSet<_>>()
             .into_iter()
             .collect();
+        let chain = payload.chain;
         let mut resp = HashMap::with_capacity(addresses.len());
 
         for address in &addresses {
-            let supply = if let Some(supply) = self
-                .redis_service
-                .get_cached(&format!("token_supply:{}", address))
-                .await?
-            {
-                supply
-            } else if let Some(token_pick) = self
-                .token_repository
-                .get_token_pick_by_address(address)
-                .await?
-            {
-                let supply = match token_pick.supply_at_call {
-                    Some(supply) => supply,
-                    None => {
-                        let metadata = self
-                            .rust_monorepo_service
-                            .get_latest_w_metadata(&[address.clone()])
-                            .await?;
-                        let supply = metadata
-                            .get(address)
-                            .map_or(Decimal::from(0), |m| m.token_info.supply);
-
-                        self.token_repository
-                            .update_highest_market_cap(
-                                token_pick.id,
-                                token_pick.highest_market_cap.unwrap_or_default(),
-                                token_pick.hit_date,
-                                Some(supply),
-                            )
-                            .await?;
-
-                        supply
-                    }
-                };
-
-                if let Err(e) = self
-                    .redis_service
-                    .set_cached(
-                        &format!("token_supply:{}", address),
-                        &supply,
-                        60 * 60 * 24 * 30,
-                    )
-                    .await
+            let supply_cache_key = format!("token_supply:{}:{}", chain.to_string(), address);
+            let supply =
+                if let Some(supply) = self.redis_service.get_cached(&supply_cache_key).await? {
+                    supply
+                } else if let Some(token_pick) = self
+                    .token_repository
+                    .get_token_pick_by_address(address, &chain)
+                    .await?
                 {
-                    error!("Failed to set token supply cache: {}", e);
-                }
+                    let supply = match token_pick.supply_at_call {
+                        Some(supply) => supply,
+                        None => {
+                            let metadata = self
+                                .get_latest_token_metadata(&chain, &[address.clone()])
+                                .await?;
+                            let supply = metadata
+                                .get(address)
+                                .map_or(Decimal::from(0), |m| m.token_info.supply);
+
+                            self.token_repository
+                                .update_highest_market_cap(
+                                    token_pick.id,
+                                    token_pick.highest_market_cap.unwrap_or_default(),
+                                    token_pick.hit_date,
+                                    Some(supply),
+                                )
+                                .await?;
+
+                            supply
+                        }
+                    };
+
+                    if let Err(e) = self
+                        .redis_service
+                        .set_cached(&supply_cache_key, &supply, 60 * 60 * 24 * 30)
+                        .await
+                    {
+                        error!("Failed to set token supply cache: {}", e);
+                    }
 
-                supply
-            } else {
-                Decimal::from(0)
-            };
+                    supply
+                } else {
+                    Decimal::from(0)
+                };
 
             resp.insert(
                 address.clone(),
                 TokenValueDataResponse {
                     address: address.clone(),
-                    chain: Chain::Solana.to_string(),
+                    chain: chain.to_string(),
                     supply,
                     ..Default::default()
                 },
@@ -1035,7 +1784,7 @@ impl TokenService {
 
         for chunk in addresses.chunks(50) {
             let list_address = chunk.join(",");
-            let chain = Chain::Solana.to_string();
+            let chain = chain.to_string();
 
             let (price_response, volume_response) = tokio::join!(
                 self.birdeye_service.get_multi_price_request(
@@ -1,3 +1,5 @@
+use crate::apis::api_models::request::SetUserPickLimitRequest;
+use crate::models::groups::{UserPickLimit, DEFAULT_MAX_DAILY_PICKS_PER_USER};
 use crate::models::users::{SavedUser, User, UserResponse};
 use crate::repositories::user_repository::UserRepository;
 use crate::utils::errors::app_error::AppError;
@@ -134,4 +136,47 @@ impl UserService {
             )))
         }
     }
+
+    /// Picks the user can make per day across every group, `None` if unlimited
+    pub async fn daily_pick_limit(&self, user_id: Uuid) -> Result<Option<u32>, AppError> {
+        let limit = self.user_repository.find_pick_limit(user_id).await?;
+        Ok(match limit {
+            Some(limit) => limit.max_daily_picks.map(|max| max.max(0) as u32),
+            None => Some(DEFAULT_MAX_DAILY_PICKS_PER_USER),
+        })
+    }
+
+    pub async fn set_pick_limit(
+        &self,
+        user_id: Uuid,
+        request: SetUserPickLimitRequest,
+    ) -> Result<UserPickLimit, AppError> {
+        self.get_by_id(user_id)
+            .await?
+            .ok_or(AppError::NotFound(format!(
+                "User with id {} not found",
+                user_id
+            )))?;
+        let max_daily_picks = request
+            .max_daily_picks
+            .map(i32::try_from)
+            .transpose()
+            .map_err(|_| AppError::BadRequest("maxDailyPicks is too large".to_string()))?;
+
+        Ok(self
+            .user_repository
+            .upsert_pick_limit(user_id, max_daily_picks)
+            .await?)
+    }
+
+    /// Restores the default daily pick limit of the user
+    pub async fn delete_pick_limit(&self, user_id: Uuid) -> Result<(), AppError> {
+        if !self.user_repository.delete_pick_limit(user_id).await? {
+            return Err(AppError::NotFound(format!(
+                "User {} has no pick limit override",
+                user_id
+            )));
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,201 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use chrono::{Duration, Utc};
+use tracing::{debug, info};
+use uuid::Uuid;
+
+use crate::{
+    models::{
+        profiles::{ProfileDetailsResponse, ProfilePickSummary, ProfileTier},
+        token_picks::TokenPickResponse,
+        user_stats::{UserStats, UserStatsLeaderboardRow, UserStatsRecord, ALL_GROUPS},
+    },
+    repositories::{token_repository::TokenRepository, user_stats_repository::UserStatsRepository},
+    utils::{errors::app_error::AppError, time::TimePeriod},
+};
+
+/// Keeps the `user_stats` table, the pick stats of each user per time period and group, up to
+/// date as picks change, so leaderboards are a single query instead of one per user.
+pub struct UserStatsService {
+    user_stats_repository: Arc<UserStatsRepository>,
+    token_repository: Arc<TokenRepository>,
+    /// Whether the users with picks but no stats were computed since startup
+    missing_stats_computed: AtomicBool,
+}
+
+impl UserStatsService {
+    /// Stats older than this are recomputed by the token picks job, as picks leave the windows
+    /// of the shorter time periods without being updated
+    const STALE_AFTER_MINUTES: i64 = 60;
+    /// Users recomputed per refresh batch
+    const REFRESH_BATCH_SIZE: usize = 100;
+
+    pub fn new(
+        user_stats_repository: Arc<UserStatsRepository>,
+        token_repository: Arc<TokenRepository>,
+    ) -> Self {
+        Self {
+            user_stats_repository,
+            token_repository,
+            missing_stats_computed: AtomicBool::new(false),
+        }
+    }
+
+    /// Recomputes the stats of the users for every time period, across every group and in
+    /// each group they picked in. Returns the number of users left with stats.
+    pub async fn refresh_user_stats(&self, user_ids: &[Uuid]) -> Result<usize, AppError> {
+        let user_ids: Vec<Uuid> = user_ids
+            .iter()
+            .copied()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+
+        let mut users_with_stats = 0;
+        for user_ids in user_ids.chunks(Self::REFRESH_BATCH_SIZE) {
+            let now = Utc::now().into();
+            let picks = self
+                .token_repository
+                .list_token_picks_of_users(user_ids, TimePeriod::AllTime.to_date_time(now))
+                .await?;
+
+            let mut picks_by_user = HashMap::<Uuid, Vec<TokenPickResponse>>::new();
+            for pick in picks {
+                if let Some(user_id) = pick.user.as_ref().map(|u| u.id) {
+                    picks_by_user.entry(user_id).or_default().push(pick.into());
+                }
+            }
+
+            let mut records = Vec::new();
+            for (user_id, picks) in &picks_by_user {
+                for period in TimePeriod::ALL {
+                    let picked_after = period.to_date_time(now);
+                    let period_picks: Vec<TokenPickResponse> = picks
+                        .iter()
+                        .filter(|p| p.call_date >= picked_after)
+                        .cloned()
+                        .collect();
+                    if period_picks.is_empty() {
+                        continue;
+                    }
+
+                    let mut picks_by_group = HashMap::<i64, Vec<TokenPickResponse>>::new();
+                    for pick in &period_picks {
+                        picks_by_group
+                            .entry(pick.group.id)
+                            .or_default()
+                            .push(pick.clone());
+                    }
+
+                    records.push(UserStatsRecord::new(
+                        *user_id,
+                        period.to_string(),
+                        ALL_GROUPS,
+                        &period_picks,
+                    ));
+                    records.extend(picks_by_group.iter().map(|(group_id, group_picks)| {
+                        UserStatsRecord::new(*user_id, period.to_string(), *group_id, group_picks)
+                    }));
+                }
+            }
+
+            self.user_stats_repository
+                .replace_user_stats(user_ids, &records)
+                .await?;
+            debug!(
+                "Refreshed {} user stats of {} users",
+                records.len(),
+                user_ids.len()
+            );
+            users_with_stats += picks_by_user.len();
+        }
+
+        Ok(users_with_stats)
+    }
+
+    /// Recomputes the stats of users that were not refreshed recently, batch by batch until
+    /// none are left. Users with picks but no stats yet are computed on the first run.
+    pub async fn refresh_stale_user_stats(&self) -> Result<(), AppError> {
+        if !self.missing_stats_computed.load(Ordering::Relaxed) {
+            let picked_after = TimePeriod::AllTime.to_date_time(Utc::now().into());
+            let mut refreshed = 0;
+            loop {
+                let user_ids = self
+                    .user_stats_repository
+                    .list_user_ids_without_stats(picked_after, Self::REFRESH_BATCH_SIZE as i64)
+                    .await?;
+                if user_ids.is_empty() {
+                    break;
+                }
+                // Users whose picks are all left out of the stats would be listed again
+                let computed = self.refresh_user_stats(&user_ids).await?;
+                refreshed += computed;
+                if computed == 0 {
+                    break;
+                }
+            }
+            if refreshed > 0 {
+                info!("Computed missing stats of {} users", refreshed);
+            }
+            self.missing_stats_computed.store(true, Ordering::Relaxed);
+        }
+
+        // Refreshed rows are updated after `before`, so every batch makes progress
+        let before = Utc::now() - Duration::minutes(Self::STALE_AFTER_MINUTES);
+        let mut refreshed = 0;
+        loop {
+            let user_ids = self
+                .user_stats_repository
+                .list_stale_user_ids(before, Self::REFRESH_BATCH_SIZE as i64)
+                .await?;
+            if user_ids.is_empty() {
+                break;
+            }
+            self.refresh_user_stats(&user_ids).await?;
+            refreshed += user_ids.len();
+        }
+        if refreshed > 0 {
+            info!("Refreshed stale stats of {} users", refreshed);
+        }
+
+        Ok(())
+    }
+
+    /// Profiles of every user with picks in the time period and group, [ALL_GROUPS] for every
+    /// group. Realized profit and volume traded are not kept, so they are left empty.
+    pub async fn list_leaderboard_profiles(
+        &self,
+        period: &TimePeriod,
+        group_id: i64,
+        viewer_id: Option<Uuid>,
+    ) -> Result<Vec<ProfileDetailsResponse>, AppError> {
+        let rows = self
+            .user_stats_repository
+            .list_user_stats(&period.to_string(), group_id, viewer_id)
+            .await?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+}
+
+impl From<UserStatsLeaderboardRow> for ProfileDetailsResponse {
+    fn from(row: UserStatsLeaderboardRow) -> Self {
+        ProfileDetailsResponse {
+            id: row.stats.user_id,
+            name: Some(row.username.clone()),
+            username: row.username,
+            avatar_url: row.image_uri,
+            bio: row.bio,
+            pick_summary: ProfilePickSummary::from(UserStats::from(row.stats)),
+            is_following: row.is_following,
+            tier: ProfileTier::from_points(row.total_points),
+            ..Default::default()
+        }
+    }
+}
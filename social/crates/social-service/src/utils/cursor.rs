@@ -0,0 +1,136 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use super::errors::app_error::AppError;
+
+/// Largest page any cursor paginated list returns
+pub const MAX_PAGE_SIZE: u32 = 100;
+
+/// The number of items a page of `limit` items holds, at least one and at most [MAX_PAGE_SIZE]
+pub fn page_size(limit: u32) -> u32 {
+    limit.clamp(1, MAX_PAGE_SIZE)
+}
+
+/// An opaque keyset cursor pointing after the last item of a page: the sort key and id of that
+/// item. It also holds the sort the list was ordered by, so it can't be replayed against another
+/// order.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Cursor<K, I> {
+    #[serde(rename = "s")]
+    sort: String,
+    #[serde(rename = "k")]
+    pub key: K,
+    #[serde(rename = "i")]
+    pub id: I,
+}
+
+impl<K: Serialize + DeserializeOwned, I: Serialize + DeserializeOwned> Cursor<K, I> {
+    pub fn new(sort: &str, key: K, id: I) -> Self {
+        Self {
+            sort: sort.to_string(),
+            key,
+            id,
+        }
+    }
+
+    pub fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(self).unwrap_or_default())
+    }
+
+    /// Decodes a cursor returned by a list ordered by `sort`
+    pub fn decode(cursor: &str, sort: &str) -> Result<Self, AppError> {
+        let invalid = || AppError::BadRequest("Invalid cursor".to_string());
+        let bytes = URL_SAFE_NO_PAD.decode(cursor).map_err(|_| invalid())?;
+        let cursor: Self = serde_json::from_slice(&bytes).map_err(|_| invalid())?;
+        if cursor.sort != sort {
+            return Err(AppError::BadRequest(
+                "Cursor was returned for another sort".to_string(),
+            ));
+        }
+
+        Ok(cursor)
+    }
+}
+
+/// The page of `limit` items after the `after` position, of items already sorted by `position`.
+/// Also returns whether more items follow the page. A `limit` of 0 returns every item after the
+/// position.
+pub fn page_after<T, P: Ord>(
+    items: Vec<T>,
+    after: Option<&P>,
+    limit: usize,
+    position: impl Fn(&T) -> P,
+) -> (Vec<T>, bool) {
+    let start = after.map_or(0, |after| {
+        items.partition_point(|item| position(item) <= *after)
+    });
+    let limit = if limit == 0 { items.len() } else { limit };
+    let has_more = items.len() > start + limit;
+
+    (
+        items.into_iter().skip(start).take(limit).collect(),
+        has_more,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursor_round_trip() {
+        let cursor = Cursor::new("hit_rate", 0.5_f64, 42_i64);
+        let decoded = Cursor::<f64, i64>::decode(&cursor.encode(), "hit_rate").unwrap();
+        assert_eq!(decoded.key, 0.5);
+        assert_eq!(decoded.id, 42);
+    }
+
+    #[test]
+    fn test_cursor_rejects_another_sort() {
+        let cursor = Cursor::new("hit_rate", 0.5_f64, 42_i64).encode();
+        assert!(matches!(
+            Cursor::<f64, i64>::decode(&cursor, "newest"),
+            Err(AppError::BadRequest(_))
+        ));
+    }
+
+    #[test]
+    fn test_cursor_rejects_invalid_input() {
+        assert!(Cursor::<f64, i64>::decode("not a cursor", "hit_rate").is_err());
+        let wrong_types = Cursor::new("hit_rate", "key".to_string(), "id".to_string()).encode();
+        assert!(Cursor::<f64, i64>::decode(&wrong_types, "hit_rate").is_err());
+    }
+
+    #[test]
+    fn test_page_after_first_page() {
+        let (page, has_more) = page_after(vec![1, 2, 3, 4, 5], None, 2, |i| *i);
+        assert_eq!(page, vec![1, 2]);
+        assert!(has_more);
+    }
+
+    #[test]
+    fn test_page_after_position() {
+        let (page, has_more) = page_after(vec![1, 2, 3, 4, 5], Some(&2), 2, |i| *i);
+        assert_eq!(page, vec![3, 4]);
+        assert!(has_more);
+
+        let (page, has_more) = page_after(vec![1, 2, 3, 4, 5], Some(&3), 2, |i| *i);
+        assert_eq!(page, vec![4, 5]);
+        assert!(!has_more);
+    }
+
+    #[test]
+    fn test_page_after_removed_position() {
+        // The item the cursor points to may have left the list since the previous page
+        let (page, has_more) = page_after(vec![1, 2, 4, 5], Some(&3), 10, |i| *i);
+        assert_eq!(page, vec![4, 5]);
+        assert!(!has_more);
+    }
+
+    #[test]
+    fn test_page_after_without_limit() {
+        let (page, has_more) = page_after(vec![1, 2, 3], Some(&1), 0, |i| *i);
+        assert_eq!(page, vec![2, 3]);
+        assert!(!has_more);
+    }
+}
@@ -10,3 +10,105 @@ pub fn calculate_price_multiplier(
 
     current_market_cap / initial_market_cap
 }
+
+/// z-score of a 95% confidence level
+const Z_95: f64 = 1.96;
+
+/// 95% Wilson score interval of `successes` out of `trials`, as fractions between 0 and 1
+pub fn wilson_interval(successes: u64, trials: u64) -> (f64, f64) {
+    if trials == 0 {
+        return (0.0, 0.0);
+    }
+
+    let n = trials as f64;
+    let p = successes.min(trials) as f64 / n;
+    let z2 = Z_95 * Z_95;
+    let denominator = 1.0 + z2 / n;
+    let center = p + z2 / (2.0 * n);
+    let margin = Z_95 * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt();
+    (
+        ((center - margin) / denominator).max(0.0),
+        ((center + margin) / denominator).min(1.0),
+    )
+}
+
+/// The `mean` of `samples` values shrunk towards `prior`, as if `prior_weight` more samples
+/// averaged the prior
+pub fn bayesian_average(mean: Decimal, samples: u64, prior: Decimal, prior_weight: u64) -> Decimal {
+    let total = samples + prior_weight;
+    if total == 0 {
+        return prior;
+    }
+
+    (mean * Decimal::from(samples) + prior * Decimal::from(prior_weight)) / Decimal::from(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-4,
+            "{} is not close to {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn test_wilson_interval() {
+        let (lower, upper) = wilson_interval(5, 10);
+        assert_close(lower, 0.2366);
+        assert_close(upper, 0.7634);
+    }
+
+    #[test]
+    fn test_wilson_interval_narrows_with_trials() {
+        let (small_lower, small_upper) = wilson_interval(1, 2);
+        let (large_lower, large_upper) = wilson_interval(500, 1000);
+        assert!(large_upper - large_lower < small_upper - small_lower);
+        assert!(small_lower < large_lower);
+    }
+
+    #[test]
+    fn test_wilson_interval_bounds() {
+        assert_eq!(wilson_interval(0, 0), (0.0, 0.0));
+
+        let (lower, upper) = wilson_interval(0, 10);
+        assert_eq!(lower, 0.0);
+        assert!(upper > 0.0 && upper < 1.0);
+
+        let (lower, upper) = wilson_interval(10, 10);
+        assert!(lower > 0.0 && lower < 1.0);
+        assert_close(upper, 1.0);
+
+        // Successes are capped to the trials
+        assert_eq!(wilson_interval(20, 10), wilson_interval(10, 10));
+    }
+
+    #[test]
+    fn test_bayesian_average() {
+        // 2 samples averaging 100, shrunk towards a prior of 10 weighted as 8 samples
+        assert_eq!(
+            bayesian_average(Decimal::from(100), 2, Decimal::from(10), 8),
+            Decimal::from(28)
+        );
+    }
+
+    #[test]
+    fn test_bayesian_average_bounds() {
+        assert_eq!(
+            bayesian_average(Decimal::from(100), 0, Decimal::from(10), 8),
+            Decimal::from(10)
+        );
+        assert_eq!(
+            bayesian_average(Decimal::from(100), 0, Decimal::from(10), 0),
+            Decimal::from(10)
+        );
+        assert_eq!(
+            bayesian_average(Decimal::from(100), 5, Decimal::from(10), 0),
+            Decimal::from(100)
+        );
+    }
+}
@@ -1,3 +1,4 @@
+pub mod cursor;
 pub mod errors;
 pub mod math;
 pub mod redis_keys;
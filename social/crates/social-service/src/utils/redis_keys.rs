@@ -1,3 +1,5 @@
+use uuid::Uuid;
+
 use super::time::TimePeriod;
 
 pub struct RedisKeys;
@@ -71,6 +73,7 @@ impl RedisKeys {
     // Add group leaderboard keys
     pub const PROCESSING_LOCK_KEY: &str = "token_picks:processing_lock";
     pub const NOTIFY_FOLLOWERS_LOCK_KEY: &str = "token_picks:notify_followers_lock:";
+    pub const ANNOUNCE_ACHIEVEMENT_LOCK_KEY: &str = "achievements:announce_lock:";
     pub const GROUP_LEADERBOARD_PREFIX: &'static str = "group:leaderboard";
 
     pub fn get_group_leaderboard_key(group_id: i64, timeframe: &str) -> String {
@@ -93,3 +96,78 @@ impl RedisKeys {
         )
     }
 }
+
+impl RedisKeys {
+    // Pick creation idempotency keys
+    pub const PICK_REQUEST_PREFIX: &'static str = "token_picks:request:";
+
+    /// Where the response of a pick creation request is kept for replays
+    pub fn get_pick_request_key(request_key: &str) -> String {
+        format!(
+            "{}:{}{}",
+            Self::get_env_prefix(),
+            Self::PICK_REQUEST_PREFIX,
+            request_key
+        )
+    }
+
+    /// Held while a pick creation request is in flight
+    pub fn get_pick_request_lock_key(request_key: &str) -> String {
+        format!("{}:lock", Self::get_pick_request_key(request_key))
+    }
+}
+
+impl RedisKeys {
+    // Season keys
+    pub const SEASON_STANDINGS_PREFIX: &'static str = "season:standings:";
+
+    /// Where the standings of a running season are cached
+    pub fn get_season_standings_key(season_id: i64) -> String {
+        format!(
+            "{}:{}{}",
+            Self::get_env_prefix(),
+            Self::SEASON_STANDINGS_PREFIX,
+            season_id
+        )
+    }
+
+    pub const SIMULATION_PREFIX: &'static str = "simulation:";
+
+    /// Where a copy-trading simulation of the picks in `scope` is cached
+    pub fn get_simulation_key(scope: &str, query_key: &str) -> String {
+        format!(
+            "{}:{}{}:{}",
+            Self::get_env_prefix(),
+            Self::SIMULATION_PREFIX,
+            scope,
+            query_key
+        )
+    }
+
+    pub const SIMULATION_CANDLES_PREFIX: &'static str = "simulation-candles:";
+
+    /// Where the candles simulations replay a pick with are cached, shared by every query
+    pub fn get_simulation_candles_key(pick_id: i64) -> String {
+        format!(
+            "{}:{}{}",
+            Self::get_env_prefix(),
+            Self::SIMULATION_CANDLES_PREFIX,
+            pick_id
+        )
+    }
+}
+
+impl RedisKeys {
+    // Import keys
+    pub const IMPORT_JOB_PREFIX: &'static str = "imports:picks:";
+
+    /// Where the status and report of a token picks import are kept
+    pub fn get_import_job_key(import_id: &Uuid) -> String {
+        format!(
+            "{}:{}{}",
+            Self::get_env_prefix(),
+            Self::IMPORT_JOB_PREFIX,
+            import_id
+        )
+    }
+}
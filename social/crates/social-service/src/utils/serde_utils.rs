@@ -25,3 +25,16 @@ where
     let s: Option<Decimal> = Deserialize::deserialize(deserializer).expect("Fuck");
     Ok(s.unwrap_or(Decimal::ZERO))
 }
+
+/// Deserializes a comma separated query value, such as `a,b,c`, skipping empty entries
+pub fn deserialize_comma_separated<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s: String = Deserialize::deserialize(deserializer)?;
+    Ok(s.split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect())
+}
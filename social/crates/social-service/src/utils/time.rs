@@ -31,6 +31,14 @@ pub enum TimePeriod {
 }
 
 impl TimePeriod {
+    pub const ALL: [TimePeriod; 5] = [
+        TimePeriod::SixHours,
+        TimePeriod::Day,
+        TimePeriod::Week,
+        TimePeriod::Month,
+        TimePeriod::AllTime,
+    ];
+
     pub fn seconds(&self) -> i64 {
         match self {
             TimePeriod::SixHours => 21600,